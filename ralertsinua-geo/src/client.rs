@@ -54,6 +54,30 @@ pub trait AlertsInUaGeo: WithBoundingRect + Sync + Send + core::fmt::Debug {
     fn locations(&self) -> [Location; 27];
     fn get_location_by_uid(&self, uid: i32) -> Option<Location>;
     fn get_location_by_name(&self, name: &str) -> Option<Location>;
+
+    /// Resolve location by uid, uk/en name or ISO 3166-2 code, case-insensitive.
+    /// Falls back to the first word of the name, e.g. "Kharkiv" or "Харківська"
+    fn find_location(&self, query: &str) -> Option<Location> {
+        let query = query.trim().to_lowercase();
+        if let Ok(uid) = query.parse::<i32>() {
+            return self.get_location_by_uid(uid);
+        }
+        let locations = self.locations();
+        let first_word = |s: &str| s.split(' ').next().unwrap_or_default().to_lowercase();
+        locations
+            .iter()
+            .find(|l| {
+                [&l.name, &l.name_en, &l.iso_3166_2]
+                    .iter()
+                    .any(|s| s.to_lowercase() == query)
+            })
+            .or_else(|| {
+                locations.iter().find(|l| {
+                    first_word(&l.name) == query || first_word(&l.name_en) == query
+                })
+            })
+            .cloned()
+    }
 }
 
 impl AlertsInUaGeo for AlertsInUaGeoClient {
@@ -99,4 +123,35 @@ mod tests {
         assert_eq!(geo_client.boundary().0.coords_count(), 955);
         assert_eq!(geo_client.locations().len(), 27);
     }
+
+    #[test]
+    fn test_find_location() {
+        let geo = AlertsInUaGeoClient::default();
+        let uid = |q: &str| geo.find_location(q).map(|l| l.location_uid);
+        assert_eq!(uid("31"), Some(31));
+        assert_eq!(uid("Київ"), Some(31));
+        assert_eq!(uid("kyiv"), Some(31));
+        assert_eq!(uid("Kyiv Oblast"), Some(14));
+        assert_eq!(uid("ua-32"), Some(14));
+        assert_eq!(uid("Київська"), Some(14));
+        assert_eq!(uid(" Kharkiv "), Some(22));
+        assert_eq!(uid("Atlantis"), None);
+        assert_eq!(uid("99"), None);
+    }
+
+    #[test]
+    fn test_label_points_inside_locations() {
+        let geo = AlertsInUaGeoClient::default();
+        for location in geo.locations.iter() {
+            let (x, y) = location.label_point();
+            assert!(location.contains(x, y), "{}", location.name_en);
+        }
+        // the center of the bounding rect of Odesa oblast is outside of it
+        let odesa = geo.get_location_by_uid(18).unwrap();
+        let (x, y) = odesa.center();
+        assert!(!odesa.contains(x, y));
+        // Kyiv city is a hole in Kyiv oblast
+        let (x, y) = geo.get_location_by_uid(14).unwrap().label_point();
+        assert!(!geo.get_location_by_uid(31).unwrap().contains(x, y));
+    }
 }
@@ -2,6 +2,8 @@ pub mod client;
 pub mod constants;
 // pub mod grid;
 pub mod location;
+pub mod polylabel;
+pub mod raster;
 pub mod utils;
 
 #[derive(thiserror::Error, Debug)]
@@ -13,5 +15,7 @@ pub enum GeoError {
 pub use client::*;
 pub use constants::*;
 pub use location::*;
+pub use polylabel::*;
+pub use raster::*;
 pub use utils::*;
 pub use GeoError::*;
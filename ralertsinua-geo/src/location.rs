@@ -1,4 +1,4 @@
-use geo::{BoundingRect, Geometry, Polygon, Rect};
+use geo::{BoundingRect, Contains, Coord, Geometry, Point, Polygon, Rect};
 use geojson::de::deserialize_geometry;
 #[cfg(feature = "tui")]
 use ratatui::{
@@ -6,8 +6,11 @@ use ratatui::{
     widgets::canvas::{Painter, Shape},
 };
 use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
 
-use crate::utils::*;
+#[cfg(feature = "tui")]
+use crate::raster::*;
+use crate::{polylabel::label_point, raster::GeoViewport, utils::*};
 
 /// WKT string
 pub type WktString = String;
@@ -30,6 +33,14 @@ pub trait WithBoundingRect {
             [rect.min().y - PADDING, rect.max().y + PADDING],
         )
     }
+
+    /// Viewport showing the padded bounding rectangle on the grid of the resolution, with
+    /// the aspect ratio kept
+    fn get_viewport(&self, resolution: (f64, f64)) -> GeoViewport {
+        let ([left, right], [bottom, top]) = self.get_x_y_bounds();
+        let rect = Rect::new(Coord::from((left, bottom)), Coord::from((right, top)));
+        GeoViewport::fit(rect, resolution)
+    }
 }
 
 /// Ukraine's administrative unit lv4  - *oblast*
@@ -53,6 +64,22 @@ pub struct Location {
     /// Name in en
     #[serde(rename = "name:en")]
     pub name_en: String,
+    /// ISO 3166-2 subdivision code, e.g. "UA-30"
+    #[serde(rename = "ISO3166-2", default)]
+    pub iso_3166_2: String,
+    /// Label point, computed on first use
+    #[serde(skip)]
+    label_point: LabelPointCache,
+}
+
+/// Cached label point, not a part of the location identity
+#[derive(Debug, Default, Clone)]
+struct LabelPointCache(OnceLock<(f64, f64)>);
+
+impl PartialEq for LabelPointCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
 }
 
 impl WithBoundingRect for Location {
@@ -90,6 +117,8 @@ impl Default for Location {
             geometry: default_polygon().into(),
             name: String::default(),
             name_en: String::default(),
+            iso_3166_2: String::default(),
+            label_point: LabelPointCache::default(),
         }
     }
 }
@@ -110,19 +139,33 @@ impl Location {
         let rect = self.bounding_rect();
         rect.center().x_y()
     }
+
+    /// Point inside the location to put its label at, the pole of inaccessibility of the
+    /// largest polygon. Unlike [`Location::center`] it is inside concave locations
+    pub fn label_point(&self) -> (f64, f64) {
+        *self.label_point.0.get_or_init(|| {
+            label_point(&self.geometry)
+                .map(|p| p.x_y())
+                .unwrap_or_else(|| self.center())
+        })
+    }
+
+    /// Check if the point (lon/lat) is inside the location boundary
+    #[inline]
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        self.geometry.contains(&Point::new(x, y))
+    }
 }
 
 /// Draws location boundary with [`Canvas`]
 #[cfg(feature = "tui")]
 impl Shape for Location {
-    /// This method draws points of `Polygon` of the location with `Painter` object. It iterates over the exterior coordinates of the boundary and paints each point with `Painter` using the `paint` method
+    /// This method draws lines between the vertices of all rings of the location geometry, including holes and parts of `MultiPolygon`. Use [`Outline`] to clip them to the canvas
     #[inline]
     fn draw(&self, painter: &mut Painter) {
-        self.boundary().exterior().coords().for_each(|coord| {
-            if let Some((x, y)) = painter.get_point(coord.x, coord.y) {
-                painter.paint(x, y, Color::Reset);
-            }
-        });
+        rings(&self.geometry)
+            .into_iter()
+            .for_each(|ring| paint_visible_ring(ring, painter, Color::Reset));
     }
 }
 
@@ -140,13 +183,9 @@ impl Default for CountryBoundary {
 #[cfg(feature = "tui")]
 impl Shape for CountryBoundary {
     #[inline]
-    /// This method draws points of `Polygon` of the boundary with `Painter` object. It iterates over the exterior coordinates of the boundary and paints each point with `Painter` using the `paint` method
+    /// This method draws lines between the vertices of the exterior of the boundary. Use [`Outline`] to clip them to the canvas
     fn draw(&self, painter: &mut Painter) {
-        self.0.exterior().coords().for_each(|coord| {
-            if let Some((x, y)) = painter.get_point(coord.x, coord.y) {
-                painter.paint(x, y, Color::Reset);
-            }
-        });
+        paint_visible_ring(self.0.exterior(), painter, Color::Reset);
     }
 }
 
@@ -179,7 +218,10 @@ mod tests {
         assert_eq!(location.name(), "Київ");
         assert_eq!(location.name_en, "Kyiv");
         assert_eq!(location.location_type, "city");
+        assert_eq!(location.iso_3166_2, "UA-30");
         assert_eq!(location.bounding_rect().coords_count(), 4);
         assert_eq!(location.geometry().coords_count(), 12);
+        assert!(location.contains(30.52, 50.45));
+        assert!(!location.contains(30.0, 50.45));
     }
 }
@@ -0,0 +1,170 @@
+use geo::{Area, BoundingRect, Centroid, Contains, Geometry, Point, Polygon};
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+/// Square cell of the search, with the distance from its center to the polygon outline
+#[derive(Debug, Clone, Copy)]
+struct Cell {
+    x: f64,
+    y: f64,
+    /// Half of the cell size
+    h: f64,
+    /// Distance from the center to the outline, negative outside of the polygon
+    d: f64,
+    /// Upper bound of the distance of any point in the cell
+    max: f64,
+}
+
+impl Cell {
+    fn new(x: f64, y: f64, h: f64, polygon: &Polygon) -> Self {
+        let d = signed_distance(x, y, polygon);
+        Self {
+            x,
+            y,
+            h,
+            d,
+            max: d + h * std::f64::consts::SQRT_2,
+        }
+    }
+}
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        self.max == other.max
+    }
+}
+
+impl Eq for Cell {}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.max.total_cmp(&other.max)
+    }
+}
+
+/// Distance from the point to the nearest ring of the polygon, negative outside of it
+fn signed_distance(x: f64, y: f64, polygon: &Polygon) -> f64 {
+    let distance = std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .flat_map(|ring| ring.lines())
+        .map(|line| {
+            let (ax, ay) = line.start.x_y();
+            let (dx, dy) = (line.end.x - ax, line.end.y - ay);
+            let length = dx * dx + dy * dy;
+            let t = if length > 0.0 {
+                (((x - ax) * dx + (y - ay) * dy) / length).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            (x - ax - t * dx).hypot(y - ay - t * dy)
+        })
+        .fold(f64::INFINITY, f64::min);
+    if polygon.contains(&Point::new(x, y)) {
+        distance
+    } else {
+        -distance
+    }
+}
+
+/// Pole of inaccessibility: the point inside the polygon farthest from its outline, found
+/// within the precision with the quadtree search of Mapbox polylabel. Unlike the center of
+/// the bounding rect or the centroid it is always inside of concave polygons
+pub fn polylabel(polygon: &Polygon, precision: f64) -> Point {
+    let Some(rect) = polygon.bounding_rect() else {
+        return Point::new(0.0, 0.0);
+    };
+    let size = rect.width().min(rect.height());
+    if size <= 0.0 {
+        return rect.min().into();
+    }
+    let h = size / 2.0;
+    let mut queue = BinaryHeap::new();
+    let mut x = rect.min().x;
+    while x < rect.max().x {
+        let mut y = rect.min().y;
+        while y < rect.max().y {
+            queue.push(Cell::new(x + h, y + h, h, polygon));
+            y += size;
+        }
+        x += size;
+    }
+
+    let center = rect.center();
+    let mut best = Cell::new(center.x, center.y, 0.0, polygon);
+    if let Some(centroid) = polygon.centroid() {
+        let cell = Cell::new(centroid.x(), centroid.y(), 0.0, polygon);
+        if cell.d > best.d {
+            best = cell;
+        }
+    }
+    while let Some(cell) = queue.pop() {
+        if cell.d > best.d {
+            best = cell;
+        }
+        if cell.max - best.d <= precision {
+            continue;
+        }
+        let h = cell.h / 2.0;
+        for (dx, dy) in [(-h, -h), (h, -h), (-h, h), (h, h)] {
+            queue.push(Cell::new(cell.x + dx, cell.y + dy, h, polygon));
+        }
+    }
+    Point::new(best.x, best.y)
+}
+
+/// Label point of the geometry, the pole of inaccessibility of its largest polygon with the
+/// precision of a hundredth of its size
+pub fn label_point(geometry: &Geometry) -> Option<Point> {
+    let polygon = match geometry {
+        Geometry::Polygon(p) => p,
+        Geometry::MultiPolygon(mp) => mp
+            .iter()
+            .max_by(|a, b| a.unsigned_area().total_cmp(&b.unsigned_area()))?,
+        _ => return geometry.centroid(),
+    };
+    let rect = polygon.bounding_rect()?;
+    let precision = rect.width().max(rect.height()) / 100.0;
+    Some(polylabel(polygon, precision))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::{polygon, MultiPolygon};
+
+    #[test]
+    fn test_polylabel_square() {
+        let square = polygon![(x: 0.0, y: 0.0), (x: 4.0, y: 0.0), (x: 4.0, y: 4.0), (x: 0.0, y: 4.0)];
+        let point = polylabel(&square, 0.01);
+        assert!((point.x() - 2.0).abs() < 0.05 && (point.y() - 2.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_polylabel_concave() {
+        // "C" shape, the center of the bounding rect and the centroid are outside of it
+        let shape = polygon![
+            (x: 0.0, y: 0.0), (x: 10.0, y: 0.0), (x: 10.0, y: 2.0), (x: 2.0, y: 2.0),
+            (x: 2.0, y: 8.0), (x: 10.0, y: 8.0), (x: 10.0, y: 10.0), (x: 0.0, y: 10.0),
+        ];
+        assert!(!shape.contains(&shape.bounding_rect().unwrap().center()));
+        let point = polylabel(&shape, 0.01);
+        assert!(shape.contains(&point));
+        assert!(signed_distance(point.x(), point.y(), &shape) > 0.9);
+    }
+
+    #[test]
+    fn test_label_point_of_largest_polygon() {
+        let multi: Geometry = MultiPolygon::new(vec![
+            polygon![(x: 0.0, y: 0.0), (x: 1.0, y: 0.0), (x: 1.0, y: 1.0), (x: 0.0, y: 1.0)],
+            polygon![(x: 5.0, y: 5.0), (x: 9.0, y: 5.0), (x: 9.0, y: 9.0), (x: 5.0, y: 9.0)],
+        ])
+        .into();
+        let point = label_point(&multi).unwrap();
+        assert!((point.x() - 7.0).abs() < 0.1 && (point.y() - 7.0).abs() < 0.1);
+    }
+}
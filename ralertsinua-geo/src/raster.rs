@@ -0,0 +1,462 @@
+use geo::{Coord, Geometry, LineString, Rect};
+#[cfg(feature = "tui")]
+use ratatui::{
+    prelude::*,
+    widgets::canvas::{Painter, Shape},
+};
+
+use crate::location::XY_Bounds;
+
+/// Visible part of the map and resolution of the canvas grid in dots
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoViewport {
+    pub x_bounds: [f64; 2],
+    pub y_bounds: [f64; 2],
+    pub resolution: (f64, f64),
+}
+
+impl GeoViewport {
+    pub fn new((x_bounds, y_bounds): XY_Bounds, resolution: (f64, f64)) -> Self {
+        Self {
+            x_bounds,
+            y_bounds,
+            resolution,
+        }
+    }
+
+    /// Viewport showing the whole rect with the aspect ratio of the map: dots of Braille
+    /// are square, a degree of longitude is shorter than a degree of latitude by the cosine
+    /// of the latitude. The rect is extended around its center to fit the grid
+    pub fn fit(rect: Rect, resolution: (f64, f64)) -> Self {
+        let (res_x, res_y) = resolution;
+        let center = rect.center();
+        let (mut width, mut height) = (rect.width(), rect.height());
+        if res_x > 1.0 && res_y > 1.0 && width > 0.0 && height > 0.0 {
+            let lon_scale = center.y.to_radians().cos().max(0.1);
+            // degrees of latitude per dot, the same for both axes after scaling
+            let per_dot = (width * lon_scale / (res_x - 1.0)).max(height / (res_y - 1.0));
+            width = per_dot * (res_x - 1.0) / lon_scale;
+            height = per_dot * (res_y - 1.0);
+        }
+        Self {
+            x_bounds: [center.x - width / 2.0, center.x + width / 2.0],
+            y_bounds: [center.y - height / 2.0, center.y + height / 2.0],
+            resolution,
+        }
+    }
+
+    /// Bounds as pairs for [`Canvas`](ratatui::widgets::canvas::Canvas)
+    pub fn bounds(&self) -> XY_Bounds {
+        (self.x_bounds, self.y_bounds)
+    }
+
+    /// Visible rect
+    pub fn rect(&self) -> Rect {
+        Rect::new(
+            Coord::from((self.x_bounds[0], self.y_bounds[0])),
+            Coord::from((self.x_bounds[1], self.y_bounds[1])),
+        )
+    }
+
+    /// Size of a dot in degrees of longitude and latitude
+    pub fn dot_size(&self) -> (f64, f64) {
+        (
+            (self.x_bounds[1] - self.x_bounds[0]) / (self.resolution.0 - 1.0).max(1.0),
+            (self.y_bounds[1] - self.y_bounds[0]) / (self.resolution.1 - 1.0).max(1.0),
+        )
+    }
+
+    /// Position on the grid in dots, not clipped to the grid, the same conversion as
+    /// [`Painter::get_point`] without truncation
+    pub fn to_grid(&self, x: f64, y: f64) -> (f64, f64) {
+        let [left, right] = self.x_bounds;
+        let [bottom, top] = self.y_bounds;
+        (
+            (x - left) * (self.resolution.0 - 1.0) / (right - left),
+            (top - y) * (self.resolution.1 - 1.0) / (top - bottom),
+        )
+    }
+
+    /// Number of dot columns and rows
+    fn size(&self) -> (usize, usize) {
+        (
+            self.resolution.0.max(0.0) as usize,
+            self.resolution.1.max(0.0) as usize,
+        )
+    }
+}
+
+/// Exterior and interior rings of polygons, empty for other geometries
+pub fn rings(geometry: &Geometry) -> Vec<&LineString> {
+    match geometry {
+        Geometry::Polygon(p) => {
+            std::iter::once(p.exterior()).chain(p.interiors()).collect()
+        }
+        Geometry::MultiPolygon(mp) => mp
+            .iter()
+            .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Horizontal spans `(row, first column, last column)` of the dots inside the geometry.
+/// Scanline with the even-odd rule over all rings, so holes stay empty and parts of
+/// multipolygons are filled independently. Rings are simplified the same way as outlines
+pub fn scanline_spans(
+    geometry: &Geometry,
+    viewport: &GeoViewport,
+) -> Vec<(usize, usize, usize)> {
+    let (columns, rows) = viewport.size();
+    if columns == 0 || rows == 0 {
+        return Vec::new();
+    }
+    let edges: Vec<((f64, f64), (f64, f64))> = rings(geometry)
+        .into_iter()
+        .flat_map(|ring| {
+            let points: Vec<(f64, f64)> =
+                ring.coords().map(|c| viewport.to_grid(c.x, c.y)).collect();
+            let points = simplify(&points);
+            points.windows(2).map(|w| (w[0], w[1])).collect::<Vec<_>>()
+        })
+        .collect();
+    let (min_y, max_y) = edges
+        .iter()
+        .flat_map(|(a, b)| [a.1, b.1])
+        .fold((f64::MAX, f64::MIN), |(min, max), y| {
+            (min.min(y), max.max(y))
+        });
+    if edges.is_empty() || max_y < 0.0 || min_y >= rows as f64 {
+        return Vec::new();
+    }
+    let first_row = min_y.max(0.0) as usize;
+    let last_row = (max_y as usize).min(rows - 1);
+
+    let mut spans = Vec::new();
+    let mut crossings: Vec<f64> = Vec::new();
+    for row in first_row..=last_row {
+        // sample at the middle of the dot
+        let y = row as f64 + 0.5;
+        crossings.clear();
+        crossings.extend(
+            edges
+                .iter()
+                .filter(|((_, y1), (_, y2))| (*y1 <= y) != (*y2 <= y))
+                .map(|&((x1, y1), (x2, y2))| x1 + (y - y1) * (x2 - x1) / (y2 - y1)),
+        );
+        crossings.sort_by(f64::total_cmp);
+        for pair in crossings.chunks_exact(2) {
+            let from = (pair[0] - 0.5).ceil().max(0.0);
+            let to = (pair[1] - 0.5).floor().min(columns as f64 - 1.0);
+            if from <= to {
+                spans.push((row, from as usize, to as usize));
+            }
+        }
+    }
+    spans
+}
+
+/// How the area is filled
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FillPattern {
+    /// Every dot
+    #[default]
+    Solid,
+    /// Diagonal stripes, every fourth dot
+    Hatched,
+}
+
+impl FillPattern {
+    /// Whether the dot at the column and row of the raster is painted
+    pub fn paints(&self, column: usize, row: usize) -> bool {
+        match self {
+            FillPattern::Solid => true,
+            FillPattern::Hatched => (column + row).is_multiple_of(4),
+        }
+    }
+}
+
+/// Draws area of the geometry filled with the color with [`Canvas`]
+#[cfg(feature = "tui")]
+#[derive(Debug, Clone)]
+pub struct Fill<'a> {
+    pub geometry: &'a Geometry,
+    pub viewport: GeoViewport,
+    pub color: Color,
+    pub pattern: FillPattern,
+}
+
+#[cfg(feature = "tui")]
+impl Shape for Fill<'_> {
+    fn draw(&self, painter: &mut Painter) {
+        for (row, from, to) in scanline_spans(self.geometry, &self.viewport) {
+            (from..=to)
+                .filter(|&column| self.pattern.paints(column, row))
+                .for_each(|column| painter.paint(column, row, self.color));
+        }
+    }
+}
+
+/// Drop vertices closer than a dot to the previous kept one, the number of segments to
+/// rasterize is bounded by the resolution and not by the detail of the geometry.
+/// The first and the last vertices are kept to close the ring
+pub fn simplify(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut kept: Vec<(f64, f64)> = Vec::with_capacity(points.len());
+    for (i, &(x, y)) in points.iter().enumerate() {
+        let is_last = i + 1 == points.len();
+        match kept.last() {
+            Some(&(kx, ky)) if !is_last && (x - kx).abs() < 1.0 && (y - ky).abs() < 1.0 => {
+            }
+            _ => kept.push((x, y)),
+        }
+    }
+    kept
+}
+
+/// Clip the segment to the rectangle from `(0, 0)` to `max`, Liang-Barsky algorithm
+pub fn clip_segment(
+    (x0, y0): (f64, f64),
+    (x1, y1): (f64, f64),
+    (max_x, max_y): (f64, f64),
+) -> Option<((f64, f64), (f64, f64))> {
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    let (mut t0, mut t1) = (0.0_f64, 1.0_f64);
+    for (p, q) in [(-dx, x0), (dx, max_x - x0), (-dy, y0), (dy, max_y - y0)] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+    (t0 <= t1).then_some(((x0 + t0 * dx, y0 + t0 * dy), (x0 + t1 * dx, y0 + t1 * dy)))
+}
+
+/// Dots of the line between two dots including both ends, Bresenham's algorithm
+pub fn bresenham(
+    (x0, y0): (usize, usize),
+    (x1, y1): (usize, usize),
+    mut paint: impl FnMut(usize, usize),
+) {
+    let (x0, y0, x1, y1) = (x0 as i64, y0 as i64, x1 as i64, y1 as i64);
+    let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+    let (sx, sy) = ((x1 - x0).signum(), (y1 - y0).signum());
+    let (mut x, mut y, mut error) = (x0, y0, dx + dy);
+    loop {
+        paint(x as usize, y as usize);
+        if x == x1 && y == y1 {
+            break;
+        }
+        let e2 = 2 * error;
+        if e2 >= dy {
+            error += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            error += dx;
+            y += sy;
+        }
+    }
+}
+
+/// Rasterize the rings as connected lines, simplified for the resolution and clipped to
+/// the grid
+pub fn trace_rings(
+    rings: &[&LineString],
+    viewport: &GeoViewport,
+    mut paint: impl FnMut(usize, usize),
+) {
+    let (columns, rows) = viewport.size();
+    if columns == 0 || rows == 0 {
+        return;
+    }
+    let max = (columns as f64 - 1.0, rows as f64 - 1.0);
+    for ring in rings {
+        let points: Vec<(f64, f64)> =
+            ring.coords().map(|c| viewport.to_grid(c.x, c.y)).collect();
+        for segment in simplify(&points).windows(2) {
+            if let Some((a, b)) = clip_segment(segment[0], segment[1], max) {
+                let dot = |(x, y): (f64, f64)| (x as usize, y as usize);
+                bresenham(dot(a), dot(b), &mut paint);
+            }
+        }
+    }
+}
+
+/// Draws all rings of the geometry as lines with [`Canvas`], clipped to the viewport
+#[cfg(feature = "tui")]
+#[derive(Debug, Clone)]
+pub struct Outline<'a> {
+    pub rings: Vec<&'a LineString>,
+    pub viewport: GeoViewport,
+    pub color: Color,
+}
+
+#[cfg(feature = "tui")]
+impl<'a> Outline<'a> {
+    pub fn new(geometry: &'a Geometry, viewport: GeoViewport, color: Color) -> Self {
+        Self {
+            rings: rings(geometry),
+            viewport,
+            color,
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+impl Shape for Outline<'_> {
+    fn draw(&self, painter: &mut Painter) {
+        trace_rings(&self.rings, &self.viewport, |x, y| {
+            painter.paint(x, y, self.color)
+        });
+    }
+}
+
+/// Draws lines between the vertices of the ring visible on the canvas, for shapes drawn
+/// without a [`GeoViewport`]: segments crossing the canvas bounds are skipped
+#[cfg(feature = "tui")]
+pub fn paint_visible_ring(ring: &LineString, painter: &mut Painter, color: Color) {
+    let mut last: Option<(usize, usize)> = None;
+    for coord in ring.coords() {
+        let point = painter.get_point(coord.x, coord.y);
+        match (last, point) {
+            (Some(a), Some(b)) => bresenham(a, b, |x, y| painter.paint(x, y, color)),
+            (None, Some((x, y))) => painter.paint(x, y, color),
+            _ => {}
+        }
+        last = point;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::{polygon, MultiPolygon};
+
+    fn viewport() -> GeoViewport {
+        // 1 dot per unit, 11 x 11 dots
+        GeoViewport::new(([0.0, 10.0], [0.0, 10.0]), (11.0, 11.0))
+    }
+
+    fn count(spans: &[(usize, usize, usize)]) -> usize {
+        spans.iter().map(|(_, from, to)| to - from + 1).sum()
+    }
+
+    #[test]
+    fn test_to_grid() {
+        let viewport = viewport();
+        assert_eq!(viewport.to_grid(0.0, 10.0), (0.0, 0.0));
+        assert_eq!(viewport.to_grid(10.0, 0.0), (10.0, 10.0));
+        assert_eq!(viewport.to_grid(-5.0, 15.0), (-5.0, -5.0));
+    }
+
+    #[test]
+    fn test_fit_keeps_aspect_ratio() {
+        let rect = Rect::new(Coord::from((20.0, 40.0)), Coord::from((40.0, 50.0)));
+        // wide grid, the rect is extended horizontally
+        let viewport = GeoViewport::fit(rect, (201.0, 51.0));
+        let (dot_x, dot_y) = viewport.dot_size();
+        assert!((dot_x * 45_f64.to_radians().cos() - dot_y).abs() < 1e-9);
+        assert_eq!(viewport.y_bounds, [40.0, 50.0]);
+        assert!(viewport.x_bounds[0] < 20.0 && viewport.x_bounds[1] > 40.0);
+        assert_eq!(viewport.rect().center(), rect.center());
+        // without a grid yet
+        let viewport = GeoViewport::fit(rect, (0.0, 0.0));
+        assert_eq!(viewport.bounds(), ([20.0, 40.0], [40.0, 50.0]));
+    }
+
+    #[test]
+    fn test_fill_square() {
+        let square: Geometry = polygon![(x: 2.0, y: 2.0), (x: 8.0, y: 2.0), (x: 8.0, y: 8.0), (x: 2.0, y: 8.0)].into();
+        let spans = scanline_spans(&square, &viewport());
+        assert_eq!(spans.len(), 6);
+        assert!(spans.iter().all(|&(_, from, to)| from == 2 && to == 7));
+    }
+
+    #[test]
+    fn test_fill_with_hole() {
+        let with_hole: Geometry = polygon!(
+            exterior: [(x: 0.0, y: 0.0), (x: 10.0, y: 0.0), (x: 10.0, y: 10.0), (x: 0.0, y: 10.0)],
+            interiors: [[(x: 4.0, y: 4.0), (x: 6.0, y: 4.0), (x: 6.0, y: 6.0), (x: 4.0, y: 6.0)]],
+        )
+        .into();
+        let spans = scanline_spans(&with_hole, &viewport());
+        // rows crossing the hole are split in two
+        assert_eq!(spans.iter().filter(|(row, _, _)| *row == 5).count(), 2);
+        assert_eq!(count(&spans), 100 - 4);
+    }
+
+    #[test]
+    fn test_fill_multipolygon_and_clipping() {
+        let multi: Geometry = MultiPolygon::new(vec![
+            polygon![(x: 0.0, y: 0.0), (x: 2.0, y: 0.0), (x: 2.0, y: 2.0), (x: 0.0, y: 2.0)],
+            polygon![(x: 8.0, y: 8.0), (x: 20.0, y: 8.0), (x: 20.0, y: 20.0), (x: 8.0, y: 20.0)],
+        ])
+        .into();
+        let spans = scanline_spans(&multi, &viewport());
+        // the second one is clipped to the grid
+        assert_eq!(count(&spans), 4 + 3 * 2);
+        assert!(spans.iter().all(|&(row, _, to)| row <= 10 && to <= 10));
+    }
+
+    #[test]
+    fn test_bresenham() {
+        let mut dots = Vec::new();
+        bresenham((0, 0), (5, 2), |x, y| dots.push((x, y)));
+        assert_eq!(dots, [(0, 0), (1, 0), (2, 1), (3, 1), (4, 2), (5, 2)]);
+
+        dots.clear();
+        bresenham((2, 6), (2, 3), |x, y| dots.push((x, y)));
+        assert_eq!(dots, [(2, 6), (2, 5), (2, 4), (2, 3)]);
+    }
+
+    #[test]
+    fn test_clip_segment() {
+        let max = (10.0, 10.0);
+        assert_eq!(
+            clip_segment((-5.0, 5.0), (15.0, 5.0), max),
+            Some(((0.0, 5.0), (10.0, 5.0)))
+        );
+        assert_eq!(
+            clip_segment((2.0, 2.0), (4.0, 4.0), max),
+            Some(((2.0, 2.0), (4.0, 4.0)))
+        );
+        assert_eq!(clip_segment((-5.0, -5.0), (-1.0, 20.0), max), None);
+        assert_eq!(clip_segment((12.0, 0.0), (20.0, 10.0), max), None);
+    }
+
+    #[test]
+    fn test_simplify() {
+        let points = [(0.0, 0.0), (0.3, 0.2), (0.9, 0.9), (1.5, 0.0), (1.6, 0.1)];
+        assert_eq!(simplify(&points), [(0.0, 0.0), (1.5, 0.0), (1.6, 0.1)]);
+    }
+
+    #[test]
+    fn test_trace_rings_connected_and_clipped() {
+        let square = polygon![(x: -5.0, y: 2.0), (x: 8.0, y: 2.0), (x: 8.0, y: 8.0), (x: -5.0, y: 8.0)];
+        let mut dots = Vec::new();
+        trace_rings(&[square.exterior()], &viewport(), |x, y| dots.push((x, y)));
+        dots.sort();
+        dots.dedup();
+        // right side is whole, the left one is outside of the grid
+        assert!((2..=8).all(|y| dots.contains(&(8, y))));
+        assert!((0..=8).all(|x| dots.contains(&(x, 2)) && dots.contains(&(x, 8))));
+        assert!(dots.iter().all(|&(x, y)| x <= 10 && y <= 10));
+        assert!(!dots.contains(&(4, 5)));
+    }
+
+    #[test]
+    fn test_hatched_pattern() {
+        let painted = (0..4)
+            .flat_map(|row| (0..4).map(move |column| (column, row)))
+            .filter(|&(column, row)| FillPattern::Hatched.paints(column, row))
+            .count();
+        assert_eq!(painted, 4);
+    }
+}
@@ -50,10 +50,10 @@ pub trait WithName {
     {
         let locale: Language = locale.into();
         if locale.as_str() == "uk" {
-            return self.name();
+            self.name()
         } else {
-            return self.name_en();
-        };
+            self.name_en()
+        }
     }
 }
 
@@ -143,7 +143,7 @@ mod tests {
                 ]
             }"#;
         let locations: [Location; 1] =
-            deserialize_feature_collection_to_fixed_array(&geojson_str, "uk").unwrap();
+            deserialize_feature_collection_to_fixed_array(geojson_str, "uk").unwrap();
         assert_eq!(locations.len(), 1);
 
         let location = &locations[0];
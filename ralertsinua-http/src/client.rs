@@ -10,11 +10,15 @@ use reqwest::{
 };
 use serde::Deserialize;
 use std::fmt;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::Instant,
+};
 
 #[cfg(feature = "cache")]
 use crate::cache::*;
-use crate::error::*;
+use crate::{connectivity::*, error::*, stats::*};
 
 type Query<'a> = HashMap<&'a str, &'a str>;
 type Result<T> = miette::Result<T, ApiError>;
@@ -29,6 +33,8 @@ pub struct AlertsInUaClient {
     client: Client,
     #[cfg(feature = "cache")]
     cache_manager: Arc<dyn CacheManagerSync>,
+    connectivity: Arc<RwLock<Connectivity>>,
+    stats: Arc<RwLock<RequestStats>>,
 }
 
 impl std::fmt::Debug for AlertsInUaClient {
@@ -59,6 +65,8 @@ impl AlertsInUaClient {
             client,
             #[cfg(feature = "cache")]
             cache_manager,
+            connectivity: Arc::default(),
+            stats: Arc::default(),
         }
     }
 }
@@ -68,7 +76,37 @@ impl AlertsInUaClient {
         format!("{}{}{}", self.base_url, API_VERSION, url)
     }
 
+    /// Performs the request and feeds its outcome into the connectivity state machine
+    /// and request statistics
     async fn request<R, D>(&self, method: Method, url: &str, add_data: D) -> Result<R>
+    where
+        R: for<'de> Deserialize<'de>,
+        D: Fn(RequestBuilder) -> RequestBuilder,
+    {
+        let started = Instant::now();
+        let result = self.send_request(method, url, add_data).await;
+        let latency = started.elapsed();
+        if let Ok(mut stats) = self.stats.write() {
+            let not_modified = matches!(result, Ok((_, true)));
+            stats.record(url, latency, not_modified, result.as_ref().err());
+        }
+        if let Ok(mut connectivity) = self.connectivity.write() {
+            match &result {
+                Ok(_) => connectivity.on_success(),
+                Err(e) => connectivity.on_error(e),
+            }
+        }
+
+        result.map(|(data, _)| data)
+    }
+
+    /// Returns deserialized data and whether it was served from cache (`304 Not Modified`)
+    async fn send_request<R, D>(
+        &self,
+        method: Method,
+        url: &str,
+        add_data: D,
+    ) -> Result<(R, bool)>
     where
         R: for<'de> Deserialize<'de>,
         D: Fn(RequestBuilder) -> RequestBuilder,
@@ -91,10 +129,12 @@ impl AlertsInUaClient {
                 cached_data = bytes;
             }
             // Here we set the If-Modified-Since header from the last_modified
-            headers.insert(
-                "If-Modified-Since",
-                last_modified.parse().map_err(http::Error::from)?,
-            );
+            if !last_modified.is_empty() {
+                headers.insert(
+                    "If-Modified-Since",
+                    last_modified.parse().map_err(http::Error::from)?,
+                );
+            }
         }
 
         req = req.headers(headers);
@@ -125,8 +165,14 @@ impl AlertsInUaClient {
             return err;
         }
 
-        last_modified = format!("{:?}", res.headers().get("Last-Modified").unwrap());
+        last_modified = res
+            .headers()
+            .get("Last-Modified")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
         // -------------------------------------------------------------
+        let not_modified = res.status() == StatusCode::NOT_MODIFIED;
         let data: Bytes = match res.status() {
             #[cfg(feature = "cache")]
             StatusCode::NOT_MODIFIED => {
@@ -149,7 +195,7 @@ impl AlertsInUaClient {
         };
 
         // Return deserialized data
-        Ok(serde_json::from_slice(&data)?)
+        Ok((serde_json::from_slice(&data)?, not_modified))
     }
 }
 
@@ -183,7 +229,7 @@ impl BaseHttpClient for AlertsInUaClient {
 
 /// The API for the AlertsInUaClient
 #[async_trait]
-pub trait AlertsInUaApi: fmt::Debug {
+pub trait AlertsInUaApi: fmt::Debug + Send + Sync {
     async fn get_active_alerts(&self) -> Result<Alerts>;
 
     async fn get_alerts_history(&self, location_aid: &i8, period: &str) -> Result<Alerts>;
@@ -193,6 +239,12 @@ pub trait AlertsInUaApi: fmt::Debug {
     async fn get_air_raid_alert_statuses_by_location(
         &self,
     ) -> Result<AirRaidAlertOblastStatuses>;
+
+    /// Connectivity state derived from the outcome of the requests made so far
+    fn connectivity(&self) -> Connectivity;
+
+    /// Request statistics by endpoint: latency, cache hits, errors
+    fn stats(&self) -> RequestStats;
 }
 
 #[async_trait]
@@ -220,6 +272,17 @@ impl AlertsInUaApi for AlertsInUaClient {
         let result = AirRaidAlertOblastStatuses::new(data, Some(true));
         Ok(result)
     }
+
+    fn connectivity(&self) -> Connectivity {
+        self.connectivity
+            .read()
+            .map(|c| c.clone())
+            .unwrap_or_default()
+    }
+
+    fn stats(&self) -> RequestStats {
+        self.stats.read().map(|s| s.clone()).unwrap_or_default()
+    }
 }
 
 // The existence of this function makes the compiler catch if the Buf
@@ -302,4 +365,62 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_connectivity_follows_request_outcome() {
+        let mut server = MockServer::new_async().await;
+        let client = AlertsInUaClient::new(server.url().as_str(), "token");
+        assert_eq!(client.connectivity().state(), ConnectivityState::Pending);
+
+        let mock = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_header("Last-Modified", "Tue, 14 May 2024 18:18:18 GMT")
+            .with_body(r#""ANNAANNANNNPANANANNNNAANNNN""#)
+            .create_async()
+            .await;
+        let _ = client.get_air_raid_alert_statuses_by_location().await;
+        mock.remove_async().await;
+        let connectivity = client.connectivity();
+        assert_eq!(connectivity.state(), ConnectivityState::Online);
+        assert!(connectivity.last_success().is_some());
+
+        let mock = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(500)
+            .create_async()
+            .await;
+        let result = client.get_air_raid_alert_statuses_by_location().await;
+        mock.remove_async().await;
+        assert!(result.is_err());
+        assert_eq!(client.connectivity().state(), ConnectivityState::Degraded);
+        // last successful update is kept while degraded
+        assert!(client.connectivity().last_success().is_some());
+
+        server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(401)
+            .create_async()
+            .await;
+        let result = client.get_active_alerts().await;
+        assert!(matches!(result, Err(ApiError::UnauthorizedError(_))));
+        assert_eq!(
+            client.connectivity().state(),
+            ConnectivityState::Unauthorized
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connectivity_offline_when_unreachable() {
+        // nothing listens on port 9 (discard) on the loopback interface
+        let client = AlertsInUaClient::new("http://127.0.0.1:9", "token");
+        for _ in 0..OFFLINE_THRESHOLD {
+            let result = client.get_active_alerts().await;
+            assert!(result.is_err());
+        }
+        assert_eq!(client.connectivity().state(), ConnectivityState::Offline);
+        assert_eq!(
+            client.connectivity().consecutive_failures(),
+            OFFLINE_THRESHOLD
+        );
+    }
 }
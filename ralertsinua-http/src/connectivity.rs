@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    time::{Duration, SystemTime},
+};
+
+use crate::ApiError;
+
+/// Number of consecutive transport failures after which the API is considered offline
+pub const OFFLINE_THRESHOLD: u32 = 3;
+
+/// Reachability of the API, derived from the outcome of actual requests
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConnectivityState {
+    /// No request has completed yet
+    #[default]
+    Pending,
+    /// Last request succeeded
+    Online,
+    /// API is reachable, but the last request failed (rate limit, server error, bad payload)
+    /// or the network failed less than [`OFFLINE_THRESHOLD`] times in a row
+    Degraded,
+    /// API is not reachable, or no request has succeeded yet
+    Offline,
+    /// API rejected the token
+    Unauthorized,
+}
+
+impl fmt::Display for ConnectivityState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ConnectivityState::Pending => "Pending",
+            ConnectivityState::Online => "Online",
+            ConnectivityState::Degraded => "Degraded",
+            ConnectivityState::Offline => "Offline",
+            ConnectivityState::Unauthorized => "Unauthorized",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Connectivity state machine, fed with the result of every request made by the client
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Connectivity {
+    state: ConnectivityState,
+    last_success: Option<SystemTime>,
+    consecutive_failures: u32,
+}
+
+impl Connectivity {
+    pub fn state(&self) -> ConnectivityState {
+        self.state
+    }
+
+    pub fn is_online(&self) -> bool {
+        self.state == ConnectivityState::Online
+    }
+
+    /// Time of the last successful request (including `304 Not Modified`)
+    pub fn last_success(&self) -> Option<SystemTime> {
+        self.last_success
+    }
+
+    /// Time elapsed since the last successful request
+    pub fn last_success_age(&self) -> Option<Duration> {
+        self.last_success
+            .and_then(|t| SystemTime::now().duration_since(t).ok())
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    /// Record a successful request
+    pub fn on_success(&mut self) {
+        self.state = ConnectivityState::Online;
+        self.last_success = Some(SystemTime::now());
+        self.consecutive_failures = 0;
+    }
+
+    /// Record a failed request, the next state depends on the kind of error
+    pub fn on_error(&mut self, err: &ApiError) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        self.state = match err {
+            ApiError::UnauthorizedError(_) | ApiError::InvalidToken => {
+                ConnectivityState::Unauthorized
+            }
+            ApiError::Unknown(e) if e.is_connect() || e.is_timeout() || e.is_request() => {
+                if self.consecutive_failures >= OFFLINE_THRESHOLD
+                    || matches!(
+                        self.state,
+                        ConnectivityState::Pending | ConnectivityState::Offline
+                    )
+                {
+                    ConnectivityState::Offline
+                } else {
+                    ConnectivityState::Degraded
+                }
+            }
+            _ => ConnectivityState::Degraded,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_pending() {
+        let c = Connectivity::default();
+        assert_eq!(c.state(), ConnectivityState::Pending);
+        assert!(!c.is_online());
+        assert!(c.last_success().is_none());
+        assert!(c.last_success_age().is_none());
+    }
+
+    #[test]
+    fn test_transitions() {
+        let mut c = Connectivity::default();
+        c.on_success();
+        assert!(c.is_online());
+        assert!(c.last_success_age().is_some());
+
+        c.on_error(&ApiError::RateLimitError);
+        assert_eq!(c.state(), ConnectivityState::Degraded);
+        assert_eq!(c.consecutive_failures(), 1);
+
+        c.on_error(&ApiError::InvalidToken);
+        assert_eq!(c.state(), ConnectivityState::Unauthorized);
+
+        c.on_success();
+        assert_eq!(c.state(), ConnectivityState::Online);
+        assert_eq!(c.consecutive_failures(), 0);
+    }
+}
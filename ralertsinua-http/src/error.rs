@@ -3,23 +3,17 @@
 ///
 /// Sample usage:
 ///
-/// ```
+/// ```no_run
 /// # #[tokio::main]
 /// # async fn main() {
-/// use http::{HttpError, AlertsInUaClient, BaseHttpClient};
+/// use ralertsinua_http::{AlertsInUaApi, AlertsInUaClient, ApiError};
 ///
-/// let client = AlertsInUaClient::default();
-/// let response = client.get("wrongurl", None, &Default::default()).await;
-/// match response {
-///     Ok(data) => println!("request succeeded: {:?}", data),
-///     Err(HttpError::Client(e)) => eprintln!("request failed: {}", e),
-///     Err(HttpError::StatusCode(response)) => {
-///         let code = response.status().as_u16();
-///         match response.json::<rspotify_model::ApiError>().await {
-///             Ok(api_error) => eprintln!("status code {}: {:?}", code, api_error),
-///             Err(_) => eprintln!("status code {}", code),
-///         }
-///     },
+/// let client = AlertsInUaClient::new("https://api.alerts.in.ua", "token");
+/// match client.get_active_alerts().await {
+///     Ok(alerts) => println!("request succeeded: {} alerts", alerts.len()),
+///     Err(ApiError::UnauthorizedError(e)) => eprintln!("invalid token: {}", e),
+///     Err(ApiError::RateLimitError) => eprintln!("too many requests, slow down"),
+///     Err(e) => eprintln!("request failed: {}", e),
 /// }
 /// # }
 /// ```
@@ -63,3 +57,22 @@ pub enum ApiError {
     #[error("API Error: Internal error")]
     Internal,
 }
+
+impl ApiError {
+    /// Name of the variant, e.g. for metrics labels
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ApiError::Unknown(_) => "Unknown",
+            ApiError::InvalidToken => "InvalidToken",
+            ApiError::UnauthorizedError(_) => "UnauthorizedError",
+            ApiError::RateLimitError => "RateLimitError",
+            ApiError::InternalServerError => "InternalServerError",
+            ApiError::ForbiddenError => "ForbiddenError",
+            ApiError::InvalidParameterException => "InvalidParameterException",
+            ApiError::InvalidURL(_) => "InvalidURL",
+            ApiError::HttpError(_) => "HttpError",
+            ApiError::ParseJson(_) => "ParseJson",
+            ApiError::Internal => "Internal",
+        }
+    }
+}
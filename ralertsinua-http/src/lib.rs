@@ -1,9 +1,13 @@
 #[cfg(feature = "cache")]
 pub mod cache;
 pub mod client;
+pub mod connectivity;
 pub mod error;
+pub mod stats;
 
 #[cfg(feature = "cache")]
 pub use cache::*;
 pub use client::*;
+pub use connectivity::*;
 pub use error::*;
+pub use stats::*;
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, time::Duration};
+
+use crate::ApiError;
+
+/// Counters of a single endpoint
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EndpointStats {
+    /// Total number of requests, including failed
+    pub requests: u64,
+    /// Number of `304 Not Modified` responses served from cache
+    pub not_modified: u64,
+    /// Sum of request durations
+    pub latency_sum: Duration,
+    /// Duration of the last request
+    pub latency_last: Duration,
+    /// Number of failed requests by [`ApiError::kind`]
+    pub errors: BTreeMap<String, u64>,
+}
+
+impl EndpointStats {
+    /// Share of requests answered with `304 Not Modified`
+    pub fn cache_hit_ratio(&self) -> f64 {
+        if self.requests == 0 {
+            return 0.0;
+        }
+        self.not_modified as f64 / self.requests as f64
+    }
+}
+
+/// Request statistics of the client, by endpoint path (e.g. `/alerts/active.json`)
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestStats {
+    pub endpoints: BTreeMap<String, EndpointStats>,
+}
+
+impl RequestStats {
+    /// Record outcome of the request to the endpoint
+    pub fn record(
+        &mut self,
+        endpoint: &str,
+        latency: Duration,
+        not_modified: bool,
+        error: Option<&ApiError>,
+    ) {
+        let stats = self.endpoints.entry(endpoint.to_string()).or_default();
+        stats.requests += 1;
+        stats.latency_sum += latency;
+        stats.latency_last = latency;
+        if not_modified {
+            stats.not_modified += 1;
+        }
+        if let Some(e) = error {
+            *stats.errors.entry(e.kind().to_string()).or_default() += 1;
+        }
+    }
+
+    /// Totals over all endpoints
+    pub fn total(&self) -> EndpointStats {
+        self.endpoints
+            .values()
+            .fold(EndpointStats::default(), |mut total, s| {
+                total.requests += s.requests;
+                total.not_modified += s.not_modified;
+                total.latency_sum += s.latency_sum;
+                total.latency_last = s.latency_last;
+                s.errors.iter().for_each(|(k, v)| {
+                    *total.errors.entry(k.clone()).or_default() += v;
+                });
+                total
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut stats = RequestStats::default();
+        let endpoint = "/alerts/active.json";
+        stats.record(endpoint, Duration::from_millis(100), false, None);
+        stats.record(endpoint, Duration::from_millis(50), true, None);
+        stats.record(
+            endpoint,
+            Duration::from_millis(10),
+            false,
+            Some(&ApiError::RateLimitError),
+        );
+        stats.record(
+            "/other",
+            Duration::from_millis(10),
+            false,
+            Some(&ApiError::RateLimitError),
+        );
+
+        let s = &stats.endpoints[endpoint];
+        assert_eq!(s.requests, 3);
+        assert_eq!(s.not_modified, 1);
+        assert_eq!(s.latency_sum, Duration::from_millis(160));
+        assert_eq!(s.latency_last, Duration::from_millis(10));
+        assert_eq!(s.errors["RateLimitError"], 1);
+        assert!((s.cache_hit_ratio() - 1.0 / 3.0).abs() < f64::EPSILON);
+
+        let total = stats.total();
+        assert_eq!(total.requests, 4);
+        assert_eq!(total.errors["RateLimitError"], 2);
+    }
+}
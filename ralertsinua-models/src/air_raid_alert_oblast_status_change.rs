@@ -0,0 +1,60 @@
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{AirRaidAlertOblastStatus, AlertStatus};
+
+/// Transition of a single oblast between two consecutive [`AirRaidAlertOblastStatuses`](crate::AirRaidAlertOblastStatuses)
+#[derive(Debug, Clone, Getters, PartialEq, Serialize, Deserialize)]
+pub struct AirRaidAlertOblastStatusChange {
+    pub location_uid: i32,
+    #[get = "pub"]
+    location_title: String,
+    #[get = "pub"]
+    location_title_en: String,
+    #[get = "pub"]
+    previous: AlertStatus,
+    #[get = "pub"]
+    current: AlertStatus,
+}
+
+impl fmt::Display for AirRaidAlertOblastStatusChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.location_title_en, self.previous, self.current
+        )
+    }
+}
+
+impl AirRaidAlertOblastStatusChange {
+    pub fn new(
+        previous: &AirRaidAlertOblastStatus,
+        current: &AirRaidAlertOblastStatus,
+    ) -> Self {
+        Self {
+            location_uid: current.location_uid,
+            location_title: current.location_title().to_string(),
+            location_title_en: current.location_title_en().to_string(),
+            previous: previous.status().clone(),
+            current: current.status().clone(),
+        }
+    }
+
+    /// Alert started on the whole oblast
+    pub fn is_alert_start(&self) -> bool {
+        self.current == AlertStatus::A
+    }
+
+    /// Alert is over, no part of the oblast is under alert anymore
+    pub fn is_alert_end(&self) -> bool {
+        matches!(self.previous, AlertStatus::A | AlertStatus::P)
+            && self.current == AlertStatus::N
+    }
+
+    /// Alert is active on a part of the oblast only
+    pub fn is_partial(&self) -> bool {
+        self.current == AlertStatus::P
+    }
+}
@@ -1,7 +1,10 @@
 use miette::Result;
 use serde::{Deserialize, Serialize};
 
-use crate::{AirRaidAlertOblastStatus, AlertStatus, ModelError, REGIONS_DATA};
+use crate::{
+    AirRaidAlertOblastStatus, AirRaidAlertOblastStatusChange, AlertStatus, ModelError,
+    REGIONS_DATA,
+};
 
 #[derive(Debug, Deserialize, Clone, PartialEq, Serialize)]
 pub struct AirRaidAlertOblastStatuses {
@@ -51,7 +54,7 @@ impl Default for AirRaidAlertOblastStatuses {
 }
 
 impl AirRaidAlertOblastStatuses {
-    pub fn iter(&self) -> std::slice::Iter<AirRaidAlertOblastStatus> {
+    pub fn iter(&self) -> std::slice::Iter<'_, AirRaidAlertOblastStatus> {
         self.oblast_statuses.iter()
     }
     pub fn len(&self) -> usize {
@@ -133,4 +136,85 @@ impl AirRaidAlertOblastStatuses {
     pub fn get_no_alert_oblasts(&self) -> Vec<AirRaidAlertOblastStatus> {
         self.filter_by_status(AlertStatus::N)
     }
+
+    /// Sort by the alert state: alerts first, then partial alerts, no alerts and unknown
+    /// statuses, in the order of the regions otherwise
+    pub fn sort_by_status(&mut self) {
+        self.oblast_statuses.sort_by_key(|s| match s.status() {
+            AlertStatus::A => 0,
+            AlertStatus::P => 1,
+            AlertStatus::N => 2,
+            AlertStatus::L => 3,
+            AlertStatus::O => 4,
+        });
+    }
+
+    /// Compare with the previous statuses and return oblasts which status has changed.
+    /// Oblasts which previous status is unknown (`L` loading or `O` offline) are skipped
+    pub fn diff(
+        &self,
+        previous: &AirRaidAlertOblastStatuses,
+    ) -> Vec<AirRaidAlertOblastStatusChange> {
+        self.oblast_statuses
+            .iter()
+            .filter_map(|current| {
+                let prev = previous.get_by_location_uid(current.location_uid)?;
+                match prev.status() {
+                    AlertStatus::L | AlertStatus::O => None,
+                    status if status == current.status() => None,
+                    _ => Some(AirRaidAlertOblastStatusChange::new(&prev, current)),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let previous = AirRaidAlertOblastStatuses::new(
+            "ANNAANNANNNPANANANNNNAANNNN".to_string(),
+            Some(false),
+        );
+        let current = AirRaidAlertOblastStatuses::new(
+            "NNNAANNANNNAANANANNNNAANNNP".to_string(),
+            Some(false),
+        );
+
+        let changes = current.diff(&previous);
+        assert_eq!(changes.len(), 3);
+
+        assert_eq!(changes[0].location_uid, 29);
+        assert!(changes[0].is_alert_end());
+        assert_eq!(changes[1].location_uid, 15);
+        assert!(changes[1].is_alert_start());
+        assert_eq!(changes[2].previous(), &AlertStatus::N);
+        assert!(changes[2].is_partial());
+    }
+
+    #[test]
+    fn test_diff_skips_unknown_previous() {
+        let previous = AirRaidAlertOblastStatuses::default();
+        let current = AirRaidAlertOblastStatuses::new(
+            "AAAAAAAAAAAAAAAAAAAAAAAAAAA".to_string(),
+            Some(true),
+        );
+        assert!(current.diff(&previous).is_empty());
+    }
+
+    #[test]
+    fn test_sort_by_status() {
+        let mut statuses = AirRaidAlertOblastStatuses::new(
+            "NNNAANNANNNPANANANNNNAANNNN".to_string(),
+            Some(false),
+        );
+        statuses.sort_by_status();
+        let order: String = statuses.iter().map(|s| char::from(s.status())).collect();
+        assert_eq!(order, "AAAAAAAAPNNNNNNNNNNNNNNNNNN");
+        // stable within the state
+        assert_eq!(statuses.get(0).unwrap().location_uid, 9);
+    }
 }
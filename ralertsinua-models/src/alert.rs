@@ -1,7 +1,7 @@
 use crate::{alert_type::*, LocationType};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, skip_serializing_none, DisplayFromStr};
-use time::OffsetDateTime;
+use time::{format_description::well_known::Iso8601, OffsetDateTime};
 
 #[skip_serializing_none]
 #[serde_as]
@@ -56,6 +56,18 @@ impl Alert {
         let offset_duration = now - self.started_at;
         offset_duration.try_into().unwrap()
     }
+
+    /// Time the alert was over, `None` while it is active or if the time is malformed
+    pub fn get_finished_at(&self) -> Option<OffsetDateTime> {
+        let finished_at = self.finished_at.as_deref()?;
+        OffsetDateTime::parse(finished_at, &Iso8601::DEFAULT).ok()
+    }
+
+    /// Start and end of the alert, the one which is not over yet lasts until `now`
+    pub fn get_interval(&self, now: OffsetDateTime) -> (OffsetDateTime, OffsetDateTime) {
+        let end = self.get_finished_at().unwrap_or(now).max(self.started_at);
+        (self.started_at, end)
+    }
 }
 
 mod tests {
@@ -101,5 +113,30 @@ mod tests {
         assert_eq!(alert.started_at.unix_timestamp(), 1_649_090_739);
         assert_eq!(alert.updated_at.unix_timestamp(), 1_698_603_757);
         assert_eq!(alert.finished_at, None);
+        assert_eq!(alert.get_finished_at(), None);
+    }
+
+    #[test]
+    fn test_alert_finished_at() {
+        use super::*;
+        use serde_json::json;
+
+        let alert: Alert = serde_json::from_value(json!({
+            "alert_type": "air_raid",
+            "finished_at": "2024-05-05T16:30:12.000Z",
+            "id": 73993,
+            "location_oblast": "Сумська область",
+            "location_oblast_uid": 20,
+            "location_title": "Сумська область",
+            "location_type": "oblast",
+            "location_uid": "20",
+            "notes": null,
+            "started_at": "2024-05-05T15:48:31.000Z",
+            "updated_at": "2024-05-05T16:30:12.818Z"
+        }))
+        .unwrap();
+        let finished_at = alert.get_finished_at().unwrap();
+        assert_eq!(finished_at.unix_timestamp(), 1_714_926_612);
+        assert!(finished_at > alert.started_at);
     }
 }
@@ -14,20 +14,20 @@ use serde::{Deserialize, Serialize};
 )]
 pub enum AlertStatus {
     /// Active
-    #[strum(to_string = "Active", props(icon = "🜸", color = "red"))] // 🔴
+    #[strum(to_string = "Active", props(icon = "🜸"))] // 🔴
     A,
     /// Partially active
-    #[strum(to_string = "Partial", props(icon = "🌤", color = "yellow"))] // 🟡
+    #[strum(to_string = "Partial", props(icon = "🌤"))] // 🟡
     P,
     /// No information
-    #[strum(to_string = "No info", props(icon = "🌣", color = "blue"))] // 🟢
+    #[strum(to_string = "No info", props(icon = "🌣"))] // 🟢
     #[default]
     N,
     /// Loading
-    #[strum(to_string = "Loading", props(icon = "↻", color = "gray"))]
+    #[strum(to_string = "Loading", props(icon = "↻"))]
     L,
     /// Offline
-    #[strum(to_string = "Offline", props(icon = "?", color = "darkgray"))]
+    #[strum(to_string = "Offline", props(icon = "?"))]
     O,
 }
 
@@ -42,3 +42,15 @@ impl From<char> for AlertStatus {
         }
     }
 }
+
+impl From<&AlertStatus> for char {
+    fn from(status: &AlertStatus) -> Self {
+        match status {
+            AlertStatus::A => 'A',
+            AlertStatus::P => 'P',
+            AlertStatus::N => 'N',
+            AlertStatus::L => 'L',
+            AlertStatus::O => 'O',
+        }
+    }
+}
@@ -63,11 +63,19 @@ pub struct Alerts {
 }
 
 impl Alerts {
+    /// Alerts without the disclaimer, e.g. loaded from a local copy, updated now
+    pub fn new(alerts: Vec<Alert>) -> Self {
+        Self {
+            alerts,
+            ..Default::default()
+        }
+    }
+
     pub fn get_alerts(&self) -> Vec<Alert> {
         self.alerts.clone()
     }
 
-    pub fn iter(&self) -> std::slice::Iter<Alert> {
+    pub fn iter(&self) -> std::slice::Iter<'_, Alert> {
         self.alerts.iter()
     }
 
@@ -123,6 +131,14 @@ impl Alerts {
             .collect()
     }
 
+    pub fn get_alerts_by_location_oblast_uid(&self, oblast_uid: i32) -> Vec<Alert> {
+        self.alerts
+            .iter()
+            .filter(|alert| alert.location_oblast_uid == oblast_uid)
+            .cloned()
+            .collect()
+    }
+
     pub fn get_air_raid_alerts(&self) -> Vec<Alert> {
         self.get_alerts_by_alert_type(AlertType::AirRaid)
     }
@@ -199,7 +215,7 @@ mod tests {
 
         assert_eq!(alerts.iter().next(), Some(alert1));
         assert_eq!(alerts.len(), 2);
-        assert_eq!(alerts.is_empty(), false);
+        assert!(!alerts.is_empty());
         assert_eq!(
             alerts.meta.get_last_updated_at().unix_timestamp(),
             1_714_989_765
@@ -233,6 +249,10 @@ mod tests {
         assert_eq!(expected_alert.len(), 1);
         assert_eq!(expected_alert[0].id, alert2.id);
 
+        let expected_alert = alerts.get_alerts_by_location_oblast_uid(16);
+        assert_eq!(expected_alert.len(), 1);
+        assert_eq!(expected_alert[0].id, alert1.id);
+
         let expected_alert = alerts.get_alerts_by_location_title("Луганська область");
         assert_eq!(expected_alert.len(), 1);
         assert_eq!(expected_alert[0].id, alert1.id);
@@ -3,10 +3,16 @@ use thiserror::Error;
 /// Groups up the kinds of errors that may happen in this crate.
 #[derive(Debug, Error)]
 pub enum ModelError {
-    // #[error("json parse error: {0}")]
-    // ParseJson(#[from] serde_json::Error),
+    #[cfg(feature = "export")]
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "export")]
+    #[error("csv error: {0}")]
+    Csv(#[from] csv::Error),
     #[error("json serialize error: {0}")]
     TimeError(#[from] time::error::Error),
+    #[error("time format error: {0}")]
+    TimeFormat(#[from] time::error::Format),
     #[error("input/output error: {0}")]
     Io(#[from] std::io::Error),
     #[error("unknown error")]
@@ -0,0 +1,286 @@
+use geojson::{Feature, FeatureCollection, JsonObject};
+use ralertsinua_geo::Location;
+use std::io::Write;
+use time::{format_description::BorrowedFormatItem, OffsetDateTime, UtcOffset};
+use time_macros::format_description;
+
+use crate::{
+    AirRaidAlertOblastStatus, AirRaidAlertOblastStatuses, Alert, Alerts, ModelError,
+};
+
+type Result<T> = std::result::Result<T, ModelError>;
+
+/// iCalendar UTC date-time, e.g. "20240505T154831Z"
+const ICALENDAR_DATE_TIME: &[BorrowedFormatItem] =
+    format_description!("[year][month][day]T[hour][minute][second]Z");
+/// Lines longer than 75 octets are folded
+const ICALENDAR_LINE_OCTETS: usize = 75;
+
+const ALERT_HEADERS: [&str; 9] = [
+    "id",
+    "location_uid",
+    "location_title",
+    "location_type",
+    "alert_type",
+    "started_at",
+    "finished_at",
+    "location_oblast_uid",
+    "notes",
+];
+const STATUS_HEADERS: [&str; 4] = ["uid", "title", "title_en", "status"];
+
+fn alert_record(alert: &Alert) -> Result<Vec<String>> {
+    Ok(vec![
+        alert.id.to_string(),
+        alert.location_uid.to_string(),
+        alert.location_title.clone(),
+        alert.location_type.to_string(),
+        alert.alert_type.to_string(),
+        alert
+            .started_at
+            .format(&time::format_description::well_known::Rfc3339)?,
+        alert.finished_at.clone().unwrap_or_default(),
+        alert.location_oblast_uid.to_string(),
+        alert.notes.clone().unwrap_or_default(),
+    ])
+}
+
+fn status_record(status: &AirRaidAlertOblastStatus) -> Vec<String> {
+    vec![
+        status.location_uid.to_string(),
+        status.location_title().to_string(),
+        status.location_title_en().to_string(),
+        char::from(status.status()).to_string(),
+    ]
+}
+
+fn write_csv<W: Write>(headers: &[&str], records: Vec<Vec<String>>, out: W) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(headers)?;
+    for record in records {
+        writer.write_record(record)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_ndjson<'a, T, I, W>(records: I, mut out: W) -> Result<()>
+where
+    T: serde::Serialize + 'a,
+    I: IntoIterator<Item = &'a T>,
+    W: Write,
+{
+    for record in records {
+        serde_json::to_writer(&mut out, record)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Escape TEXT value of iCalendar property
+fn icalendar_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Write content line, folded at 75 octets without splitting characters
+fn write_icalendar_line<W: Write>(out: &mut W, line: &str) -> Result<()> {
+    let mut octets = 0;
+    for c in line.chars() {
+        // continuation lines start with a space
+        if octets + c.len_utf8() > ICALENDAR_LINE_OCTETS {
+            out.write_all(b"\r\n ")?;
+            octets = 1;
+        }
+        write!(out, "{}", c)?;
+        octets += c.len_utf8();
+    }
+    out.write_all(b"\r\n")?;
+    Ok(())
+}
+
+fn icalendar_date_time(date_time: OffsetDateTime) -> Result<String> {
+    Ok(date_time
+        .to_offset(UtcOffset::UTC)
+        .format(ICALENDAR_DATE_TIME)?)
+}
+
+impl Alerts {
+    /// Write alerts as CSV with a header row
+    pub fn write_csv<W: Write>(&self, out: W) -> Result<()> {
+        let records = self.iter().map(alert_record).collect::<Result<_>>()?;
+        write_csv(&ALERT_HEADERS, records, out)
+    }
+
+    /// Write alerts as JSON Lines, an alert per line
+    pub fn write_ndjson<W: Write>(&self, out: W) -> Result<()> {
+        write_ndjson(self.iter(), out)
+    }
+
+    /// Write alerts as iCalendar events of their intervals, the ones which are not over
+    /// have no end
+    pub fn write_icalendar<W: Write>(&self, mut out: W) -> Result<()> {
+        write_icalendar_line(&mut out, "BEGIN:VCALENDAR")?;
+        write_icalendar_line(&mut out, "VERSION:2.0")?;
+        write_icalendar_line(&mut out, "PRODID:-//ralertsinua//alerts.in.ua//EN")?;
+        for alert in self.iter() {
+            let mut lines = vec![
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:alert-{}@alerts.in.ua", alert.id),
+                format!("DTSTAMP:{}", icalendar_date_time(alert.updated_at)?),
+                format!("DTSTART:{}", icalendar_date_time(alert.started_at)?),
+            ];
+            if let Some(finished_at) = alert.get_finished_at() {
+                lines.push(format!("DTEND:{}", icalendar_date_time(finished_at)?));
+            }
+            lines.push(format!(
+                "SUMMARY:{}",
+                icalendar_text(&format!("{}: {}", alert.alert_type, alert.location_title))
+            ));
+            lines.push(format!(
+                "LOCATION:{}",
+                icalendar_text(&alert.location_oblast)
+            ));
+            if let Some(notes) = alert.notes.as_deref().filter(|n| !n.is_empty()) {
+                lines.push(format!("DESCRIPTION:{}", icalendar_text(notes)));
+            }
+            lines.push("END:VEVENT".to_string());
+            for line in lines {
+                write_icalendar_line(&mut out, &line)?;
+            }
+        }
+        write_icalendar_line(&mut out, "END:VCALENDAR")?;
+        Ok(())
+    }
+}
+
+impl AirRaidAlertOblastStatuses {
+    /// Write statuses as CSV with a header row
+    pub fn write_csv<W: Write>(&self, out: W) -> Result<()> {
+        write_csv(
+            &STATUS_HEADERS,
+            self.iter().map(status_record).collect(),
+            out,
+        )
+    }
+
+    /// Write statuses as JSON Lines, a status per line
+    pub fn write_ndjson<W: Write>(&self, out: W) -> Result<()> {
+        write_ndjson(self.iter(), out)
+    }
+
+    /// Feature collection of the statuses with the boundaries of their locations, statuses
+    /// of unknown locations are left out
+    pub fn to_geojson(&self, locations: &[Location]) -> FeatureCollection {
+        let features = self
+            .iter()
+            .filter_map(|status| {
+                let location = locations
+                    .iter()
+                    .find(|l| l.location_uid == status.location_uid)?;
+                let mut properties = JsonObject::new();
+                properties.insert("uid".into(), status.location_uid.into());
+                properties.insert("title".into(), status.location_title().as_str().into());
+                properties.insert(
+                    "title_en".into(),
+                    status.location_title_en().as_str().into(),
+                );
+                properties.insert(
+                    "status".into(),
+                    char::from(status.status()).to_string().into(),
+                );
+                Some(Feature {
+                    geometry: Some(location.geometry().into()),
+                    properties: Some(properties),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        FeatureCollection {
+            bbox: None,
+            features,
+            foreign_members: None,
+        }
+    }
+
+    /// Write statuses as GeoJSON feature collection, see [`Self::to_geojson`]
+    pub fn write_geojson<W: Write>(&self, locations: &[Location], out: W) -> Result<()> {
+        serde_json::to_writer(out, &self.to_geojson(locations))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::AlertBuilder;
+
+    fn alerts() -> Alerts {
+        let alert = |id, to| AlertBuilder::new(id).title("Київська область").hours(1, to);
+        Alerts::new(vec![
+            alert(1, Some(2))
+                .notes("Ракетна небезпека, укриття; негайно")
+                .build(),
+            alert(2, None).build(),
+        ])
+    }
+
+    #[test]
+    fn test_alerts_export() {
+        let alerts = alerts();
+        let mut csv = Vec::new();
+        alerts.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.starts_with("id,location_uid,location_title"));
+        assert!(
+            csv.contains("2,14,Київська область,oblast,air_raid,2024-05-05T01:00:00Z,,14,")
+        );
+
+        let mut ndjson = Vec::new();
+        alerts.write_ndjson(&mut ndjson).unwrap();
+        let lines: Vec<Alert> = String::from_utf8(ndjson)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines, alerts.get_alerts());
+
+        let mut ics = Vec::new();
+        alerts.write_icalendar(&mut ics).unwrap();
+        let ics = String::from_utf8(ics).unwrap();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("DTSTART:20240505T010000Z\r\nDTEND:20240505T020000Z\r\n"));
+        // the active alert has no end
+        assert_eq!(ics.matches("DTEND").count(), 1);
+        assert!(ics.lines().all(|l| l.len() <= ICALENDAR_LINE_OCTETS));
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(
+            unfolded.contains("DESCRIPTION:Ракетна небезпека\\, укриття\\; негайно\r\n")
+        );
+    }
+
+    #[test]
+    fn test_statuses_geojson() {
+        let statuses = AirRaidAlertOblastStatuses::new(
+            "ANNAANNANNNPANANANNNNAANNNN".to_string(),
+            Some(false),
+        );
+        let mut location = Location::default();
+        location.location_uid = 29;
+        let geojson = statuses.to_geojson(&[location]);
+        assert_eq!(geojson.features.len(), 1);
+        let feature = &geojson.features[0];
+        assert!(feature.geometry.is_some());
+        assert_eq!(feature.property("uid"), Some(&29.into()));
+        assert_eq!(feature.property("status"), Some(&"A".into()));
+
+        let mut ndjson = Vec::new();
+        statuses.write_ndjson(&mut ndjson).unwrap();
+        assert_eq!(String::from_utf8(ndjson).unwrap().lines().count(), 27);
+    }
+}
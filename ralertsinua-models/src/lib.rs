@@ -1,4 +1,5 @@
 pub mod air_raid_alert_oblast_status;
+pub mod air_raid_alert_oblast_status_change;
 pub mod air_raid_alert_oblast_statuses;
 pub mod alert;
 pub mod alert_status;
@@ -6,9 +7,15 @@ pub mod alert_type;
 pub mod alerts;
 pub mod constants;
 pub mod error;
+#[cfg(feature = "export")]
+pub mod export;
 pub mod location_type;
+pub mod stats;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 
 pub use air_raid_alert_oblast_status::*;
+pub use air_raid_alert_oblast_status_change::*;
 pub use air_raid_alert_oblast_statuses::*;
 pub use alert::*;
 pub use alert_status::*;
@@ -17,3 +24,4 @@ pub use alerts::*;
 pub use constants::*;
 pub use error::*;
 pub use location_type::*;
+pub use stats::*;
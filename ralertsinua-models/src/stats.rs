@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashSet},
+};
+use time::OffsetDateTime;
+
+use crate::Alert;
+
+/// Alert statistics of a single location
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocationStats {
+    pub location_uid: i32,
+    pub location_title: String,
+    pub count: usize,
+    pub total_seconds: i64,
+    pub average_seconds: i64,
+    pub longest_seconds: i64,
+    pub shortest_seconds: i64,
+}
+
+/// Two oblasts with alerts at the same time
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoOccurrence {
+    pub oblast_uid: i32,
+    pub oblast_title: String,
+    pub other_oblast_uid: i32,
+    pub other_oblast_title: String,
+    /// Times the alerts of one oblast overlapped the alerts of the other one
+    pub count: usize,
+    /// Time both oblasts were under alert
+    pub overlap_seconds: i64,
+}
+
+/// Statistics over a collection of alerts, times of day and days of week are in UTC
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertStats {
+    pub count: usize,
+    pub total_seconds: i64,
+    /// By location, the longest total alert time first
+    pub locations: Vec<LocationStats>,
+    /// Alerts started in each hour of the day, in the offset of the time computed at
+    pub by_hour: [usize; 24],
+    /// Alerts started on each day of the week, from Monday
+    pub by_weekday: [usize; 7],
+    /// Pairs of oblasts, the longest overlap first
+    pub co_occurrences: Vec<CoOccurrence>,
+}
+
+type Interval = (OffsetDateTime, OffsetDateTime);
+
+/// Merge overlapping intervals, sorted by start
+fn merge_intervals(mut intervals: Vec<Interval>) -> Vec<Interval> {
+    intervals.sort();
+    let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+    for (start, end) in intervals {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Number of overlapping pairs of the merged intervals and their total overlap
+fn overlap(a: &[Interval], b: &[Interval]) -> (usize, i64) {
+    let (mut i, mut j) = (0, 0);
+    let (mut count, mut seconds) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let start = a[i].0.max(b[j].0);
+        let end = a[i].1.min(b[j].1);
+        if start < end {
+            count += 1;
+            seconds += (end - start).whole_seconds();
+        }
+        if a[i].1 < b[j].1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    (count, seconds)
+}
+
+impl AlertStats {
+    /// Compute the statistics, alerts which are not over yet last until `now`. Hours and
+    /// weekdays are in the offset of `now`, e.g. local time.
+    /// Alerts repeated with the same id, e.g. from histories of several locations, count once
+    pub fn new<'a, I>(alerts: I, now: OffsetDateTime) -> Self
+    where
+        I: IntoIterator<Item = &'a Alert>,
+    {
+        let mut seen = HashSet::new();
+        let alerts: Vec<&Alert> =
+            alerts.into_iter().filter(|a| seen.insert(a.id)).collect();
+        let mut stats = Self {
+            count: alerts.len(),
+            ..Default::default()
+        };
+
+        let mut locations: BTreeMap<i32, LocationStats> = BTreeMap::new();
+        let mut oblasts: BTreeMap<i32, (String, Vec<Interval>)> = BTreeMap::new();
+        for alert in &alerts {
+            let (start, end) = alert.get_interval(now);
+            let seconds = (end - start).whole_seconds();
+            stats.total_seconds += seconds;
+
+            let location =
+                locations
+                    .entry(alert.location_uid)
+                    .or_insert_with(|| LocationStats {
+                        location_uid: alert.location_uid,
+                        location_title: alert.location_title.clone(),
+                        shortest_seconds: seconds,
+                        ..Default::default()
+                    });
+            location.count += 1;
+            location.total_seconds += seconds;
+            location.longest_seconds = location.longest_seconds.max(seconds);
+            location.shortest_seconds = location.shortest_seconds.min(seconds);
+
+            let started_at = alert.started_at.to_offset(now.offset());
+            stats.by_hour[usize::from(started_at.hour())] += 1;
+            stats.by_weekday
+                [usize::from(started_at.weekday().number_days_from_monday())] += 1;
+
+            oblasts
+                .entry(alert.location_oblast_uid)
+                .or_insert_with(|| (alert.location_oblast.clone(), Vec::new()))
+                .1
+                .push((start, end));
+        }
+
+        stats.locations = locations
+            .into_values()
+            .map(|mut l| {
+                l.average_seconds = l.total_seconds / l.count as i64;
+                l
+            })
+            .collect();
+        stats.locations.sort_by_key(|l| Reverse(l.total_seconds));
+
+        let oblasts: Vec<(i32, String, Vec<Interval>)> = oblasts
+            .into_iter()
+            .map(|(uid, (title, intervals))| (uid, title, merge_intervals(intervals)))
+            .collect();
+        for (i, (uid, title, intervals)) in oblasts.iter().enumerate() {
+            for (other_uid, other_title, other_intervals) in &oblasts[i + 1..] {
+                let (count, overlap_seconds) = overlap(intervals, other_intervals);
+                if count > 0 {
+                    stats.co_occurrences.push(CoOccurrence {
+                        oblast_uid: *uid,
+                        oblast_title: title.clone(),
+                        other_oblast_uid: *other_uid,
+                        other_oblast_title: other_title.clone(),
+                        count,
+                        overlap_seconds,
+                    });
+                }
+            }
+        }
+        stats
+            .co_occurrences
+            .sort_by_key(|c| Reverse(c.overlap_seconds));
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{at, AlertBuilder, HOUR};
+
+    /// Monday, 2024-05-06 00:00 UTC, in hours after the day start
+    const MONDAY: i64 = 24;
+
+    fn alert(id: i32, uid: i32, oblast_uid: i32, from: i64, to: Option<i64>) -> Alert {
+        AlertBuilder::new(id)
+            .location(uid, oblast_uid)
+            .hours(MONDAY + from, to.map(|h| MONDAY + h))
+            .build()
+    }
+
+    #[test]
+    fn test_alert_stats() {
+        let alerts = [
+            alert(1, 14, 14, 1, Some(3)),
+            alert(2, 14, 14, 25, Some(26)),
+            // hromada in oblast 14 overlapping the first alert
+            alert(3, 1000, 14, 2, Some(4)),
+            alert(4, 31, 31, 2, Some(5)),
+            // still active
+            alert(5, 22, 22, 47, None),
+            // repeated in another history
+            alert(1, 14, 14, 1, Some(3)),
+        ];
+        let now = at(MONDAY + 50);
+        let stats = AlertStats::new(&alerts, now);
+
+        assert_eq!(stats.count, 5);
+        assert_eq!(stats.total_seconds, (2 + 1 + 2 + 3 + 3) * HOUR);
+        let uids: Vec<i32> = stats.locations.iter().map(|l| l.location_uid).collect();
+        assert_eq!(uids, vec![14, 22, 31, 1000]);
+        let kyiv_oblast = &stats.locations[0];
+        assert_eq!(kyiv_oblast.count, 2);
+        assert_eq!(kyiv_oblast.total_seconds, 3 * HOUR);
+        assert_eq!(kyiv_oblast.longest_seconds, 2 * HOUR);
+        assert_eq!(kyiv_oblast.shortest_seconds, HOUR);
+        assert_eq!(kyiv_oblast.average_seconds, 3 * HOUR / 2);
+
+        assert_eq!(stats.by_hour[1], 2);
+        assert_eq!(stats.by_hour[2], 2);
+        assert_eq!(stats.by_hour[23], 1);
+        assert_eq!(stats.by_hour.iter().sum::<usize>(), 5);
+        // Monday and Tuesday
+        assert_eq!(stats.by_weekday, [3, 2, 0, 0, 0, 0, 0]);
+
+        // 23:00 UTC is 2:00 on the next day in Kyiv in summer
+        let kyiv = now.to_offset(time::macros::offset!(+3));
+        let local = AlertStats::new(&alerts, kyiv);
+        assert_eq!(local.by_hour[2], 1);
+        assert_eq!(local.by_hour[4], 2);
+        assert_eq!(local.by_hour[5], 2);
+        assert_eq!(local.by_weekday, [3, 1, 1, 0, 0, 0, 0]);
+
+        // oblast 14 is under alert from 1:00 to 4:00, Kyiv from 2:00 to 5:00
+        assert_eq!(
+            stats.co_occurrences,
+            vec![CoOccurrence {
+                oblast_uid: 14,
+                oblast_title: "oblast 14".to_string(),
+                other_oblast_uid: 31,
+                other_oblast_title: "oblast 31".to_string(),
+                count: 1,
+                overlap_seconds: 2 * HOUR,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_overlap() {
+        let a = merge_intervals(vec![(at(0), at(2)), (at(5), at(6)), (at(1), at(3))]);
+        assert_eq!(a, vec![(at(0), at(3)), (at(5), at(6))]);
+        let b = vec![(at(2), at(5)), (at(5), at(7))];
+        // touching intervals do not overlap
+        assert_eq!(overlap(&a, &b), (2, 2 * HOUR));
+        assert_eq!(overlap(&a, &[]), (0, 0));
+    }
+}
@@ -0,0 +1,86 @@
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::{Alert, AlertType, LocationType};
+
+/// Sunday, 2024-05-05 00:00 UTC, hours of [`AlertBuilder`] are counted from it
+pub const DAY_START: i64 = 1_714_867_200;
+pub const HOUR: i64 = 3600;
+
+/// Time the number of hours after [`DAY_START`]
+pub fn at(hours: i64) -> OffsetDateTime {
+    OffsetDateTime::from_unix_timestamp(DAY_START + hours * HOUR).unwrap()
+}
+
+/// Builds [`Alert`] for tests, by default the active air raid alert in oblast 14 started
+/// at [`DAY_START`]
+#[derive(Debug, Clone)]
+pub struct AlertBuilder(Alert);
+
+impl AlertBuilder {
+    pub fn new(id: i32) -> Self {
+        Self(Alert {
+            id,
+            location_title: "location 14".to_string(),
+            location_type: LocationType::Oblast,
+            started_at: at(0),
+            updated_at: at(0),
+            finished_at: None,
+            alert_type: AlertType::AirRaid,
+            location_oblast: "oblast 14".to_string(),
+            location_uid: 14,
+            location_oblast_uid: 14,
+            notes: None,
+            country: None,
+            calculated: None,
+        })
+    }
+
+    /// Location and its oblast, titled after their uids
+    pub fn location(mut self, uid: i32, oblast_uid: i32) -> Self {
+        self.0.location_uid = uid;
+        self.0.location_oblast_uid = oblast_uid;
+        self.0.location_title = format!("location {}", uid);
+        self.0.location_oblast = format!("oblast {}", oblast_uid);
+        self
+    }
+
+    /// Same title of the location and its oblast
+    pub fn title(mut self, title: &str) -> Self {
+        self.0.location_title = title.to_string();
+        self.0.location_oblast = title.to_string();
+        self
+    }
+
+    pub fn location_type(mut self, location_type: LocationType) -> Self {
+        self.0.location_type = location_type;
+        self
+    }
+
+    pub fn alert_type(mut self, alert_type: AlertType) -> Self {
+        self.0.alert_type = alert_type;
+        self
+    }
+
+    /// Started and updated at the time
+    pub fn started_at(mut self, started_at: OffsetDateTime) -> Self {
+        self.0.started_at = started_at;
+        self.0.updated_at = started_at;
+        self
+    }
+
+    /// Started at the hour and finished at the other one, see [`at`]
+    pub fn hours(self, from: i64, to: Option<i64>) -> Self {
+        let mut builder = self.started_at(at(from));
+        builder.0.finished_at = to.map(|h| at(h).format(&Rfc3339).unwrap());
+        builder
+    }
+
+    pub fn notes(mut self, notes: &str) -> Self {
+        self.0.notes = Some(notes.to_string());
+        self
+    }
+
+    pub fn build(self) -> Alert {
+        self.0
+    }
+}
@@ -1,4 +1,5 @@
 use ralertsinua_geo::{CountryBoundary, Location};
+use ralertsinua_http::Connectivity;
 use ralertsinua_models::*;
 use serde::{
     // de::{self, Deserializer, Visitor},
@@ -17,16 +18,47 @@ pub enum Action {
     Quit,
     Refresh,
     Error(String),
+    Banner(String),
     Help,
-    Online(bool),
+    Cancel,
+    NextTab,
+    PreviousTab,
+    SelectNext,
+    SelectPrevious,
+    SelectFirst,
+    SelectLast,
+    ZoomIn,
+    ZoomOut,
+    PanLeft,
+    PanRight,
+    PanUp,
+    PanDown,
+    FocusSelected,
+    ResetZoom,
+    SortNext,
+    SortReverse,
+    FilterAlertType,
+    FilterOblast,
+    Search,
+    SearchDone,
+    ShowOnMap,
+    LoadHistory,
+    SaveSnapshot,
+    Online(Connectivity),
     Locale,
     SelectTab(usize),
     SelectLocationByUid(Option<usize>),
     FetchGeo,
-    GetLocations([Location; 27]),
+    GetLocations(Box<[Location; 27]>),
     GetBoundaries(CountryBoundary),
     FetchActiveAlerts,
     GetActiveAlerts(Alerts),
+    FetchAlertsHistory(i32),
+    /// The history request deferred by the rate limit is due
+    RetryAlertsHistory,
+    GetAlertsHistory(i32, Alerts),
+    GetStoredAlerts(Alerts),
     FetchAirRaidAlertOblastStatuses,
     GetAirRaidAlertOblastStatuses(AirRaidAlertOblastStatuses),
+    GetStatusChanges(Vec<AirRaidAlertOblastStatusChange>),
 }
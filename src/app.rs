@@ -1,11 +1,13 @@
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::KeyEvent;
 #[allow(unused_imports)]
 use miette::{Context, WrapErr};
 use ralertsinua_geo::*;
 use ralertsinua_http::*;
-use ralertsinua_models::*;
+use ralertsinua_models::{AirRaidAlertOblastStatuses, Alerts};
 use ratatui::prelude::*;
-use std::sync::Arc;
+use rust_i18n::t;
+use std::{fs::File, io::BufWriter, path::PathBuf, sync::Arc, time::Instant};
+use time::OffsetDateTime;
 use tokio::{
     sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
     time::{sleep, Duration},
@@ -13,10 +15,16 @@ use tokio::{
 #[allow(unused)]
 use tracing::{debug, error, trace};
 
-use crate::{action::*, components::*, config::*, error::*, layout::*, tui};
+use crate::{
+    action::*, components::*, config::*, error::*, history::HistoryCache, hooks::*,
+    keymap::*, layout::*, mode::Mode, notifications::*, store::*, tui, utils::get_data_dir,
+};
 
 type Result<T> = miette::Result<T, AppError>;
 
+/// Stored alerts loaded on start for the statistics, the ones over before are left out
+const STORED_ALERTS_PERIOD: time::Duration = time::Duration::days(31);
+
 pub struct App {
     action_tx: UnboundedSender<Action>,
     action_rx: UnboundedReceiver<Action>,
@@ -30,6 +38,20 @@ pub struct App {
     pub should_suspend: bool,
     pub selected_tab: LayoutTab,
     pub last_tick_key_events: Vec<KeyEvent>,
+    /// Help popup is open and captures all keys
+    pub show_help: bool,
+    /// Text filter of the alerts table is being typed, keys go to the input
+    pub searching: bool,
+    /// Last fetched statuses including partial alerts, to detect transitions of the watched locations
+    pub statuses: Option<AirRaidAlertOblastStatuses>,
+    pub notifications: Notifications,
+    /// Last fetched active alerts, for the start time of the alerts passed to hooks
+    pub alerts: Option<Alerts>,
+    pub hooks: Option<HookRunner>,
+    /// Alerts history fetched on demand, by location
+    pub history: HistoryCache,
+    /// Every alert and transition observed, none if the database failed to open
+    pub store: Option<AlertStore>,
 }
 
 impl App {
@@ -40,17 +62,39 @@ impl App {
     ) -> Result<Self> {
         let (action_tx, action_rx) = mpsc::unbounded_channel();
         let header = Header::new();
-        let map = Map::new();
+        let map: Box<dyn Component<'static>> = match config.accessible() {
+            true => Box::new(StatusReport::new()),
+            false => Box::new(Map::new()),
+        };
         let list = LocationsList::new();
+        let alerts = AlertsTable::new();
+        let history = History::new();
+        let statistics = Statistics::new();
         let fps = FpsCounter::new();
         let logger = Logger::new();
+        let banner = Banner::new();
+        let help = Help::new();
         let components: Vec<Box<dyn Component<'static>>> = vec![
             Box::new(header),
-            Box::new(map),
+            map,
             Box::new(list),
+            Box::new(alerts),
+            Box::new(history),
+            Box::new(statistics),
             Box::new(fps),
             Box::new(logger),
+            Box::new(banner),
+            Box::new(help),
         ];
+        let notifications = Notifications::new(&config, geo_client.as_ref());
+        let hooks = config.hooks().clone().map(HookRunner::new);
+        let store = match AlertStore::open_default() {
+            Ok(store) => Some(store),
+            Err(e) => {
+                error!(target: "app", "alerts are not stored: {:?}", e);
+                None
+            }
+        };
         Ok(Self {
             action_tx,
             action_rx,
@@ -62,14 +106,181 @@ impl App {
             should_suspend: false,
             selected_tab: LayoutTab::default(),
             last_tick_key_events: Vec::new(),
+            show_help: false,
+            searching: false,
+            statuses: None,
+            notifications,
+            alerts: None,
+            hooks,
+            history: HistoryCache::default(),
+            store,
         })
     }
 
+    /// Notify about transitions of the watched locations since the previous fetch,
+    /// announce and run hooks on transitions of any location
+    fn notify_transitions(&mut self, statuses: &AirRaidAlertOblastStatuses) -> Result<()> {
+        if let Some(previous) = self.statuses.replace(statuses.clone()) {
+            let changes = statuses.diff(&previous);
+            if let Some(store) = self.store.as_mut() {
+                if let Err(e) = store.save_transitions(&changes, OffsetDateTime::now_utc())
+                {
+                    error!(target: "app", "failed to store transitions: {:?}", e);
+                }
+            }
+            let locale = self.config.get_locale();
+            let banners = self.notifications.notify(
+                &changes,
+                locale.as_str(),
+                &mut std::io::stdout(),
+            );
+            for message in banners {
+                self.action_tx.send(Action::Banner(message))?;
+            }
+            if !changes.is_empty() {
+                self.action_tx
+                    .send(Action::GetStatusChanges(changes.clone()))?;
+            }
+            if let Some(hooks) = &self.hooks {
+                changes
+                    .iter()
+                    .filter_map(|c| HookEvent::from_change(c, self.alerts.as_ref()))
+                    .for_each(|event| {
+                        hooks.run(event);
+                    });
+            }
+        }
+        Ok(())
+    }
+
+    /// Save the statuses with the boundaries as GeoJSON and the active alerts as CSV to the
+    /// snapshots directory, returns the directory
+    fn save_snapshot(&self) -> Result<PathBuf> {
+        let dir = get_data_dir().join("snapshots");
+        std::fs::create_dir_all(&dir)?;
+        let now = OffsetDateTime::now_utc();
+        let stamp = format!(
+            "{}{:02}{:02}-{:02}{:02}{:02}",
+            now.year(),
+            now.month() as u8,
+            now.day(),
+            now.hour(),
+            now.minute(),
+            now.second()
+        );
+        if let Some(statuses) = &self.statuses {
+            let file = File::create(dir.join(format!("statuses-{}.geojson", stamp)))?;
+            statuses.write_geojson(&self.geo_client.locations(), BufWriter::new(file))?;
+        }
+        if let Some(alerts) = &self.alerts {
+            let file = File::create(dir.join(format!("alerts-{}.csv", stamp)))?;
+            alerts.write_csv(BufWriter::new(file))?;
+        }
+        Ok(dir)
+    }
+
+    /// Save the alerts to the store, failures are logged only
+    fn store_alerts(&mut self, alerts: &Alerts) {
+        if let Some(store) = self.store.as_mut() {
+            match store.save_alerts(alerts.iter()) {
+                Ok(n) => debug!(target:"app", "stored {} new or updated alerts", n),
+                Err(e) => error!(target: "app", "failed to store alerts: {:?}", e),
+            }
+        }
+    }
+
+    /// Stored alerts of the location and of the locations inside of it
+    fn stored_history(&self, uid: i32) -> Option<Alerts> {
+        let query = AlertQuery {
+            location_uid: Some(uid),
+            ..Default::default()
+        };
+        match self.store.as_ref()?.query_alerts(&query) {
+            Ok(alerts) => Some(Alerts::new(alerts)),
+            Err(e) => {
+                error!(target: "app", "failed to query stored alerts: {:?}", e);
+                None
+            }
+        }
+    }
+
+    /// Send the history of the location from the cache or the API, within the rate limit of
+    /// the endpoint. Once the limit allows, the location asked for last over the limit is
+    /// fetched, the stored history is shown meanwhile. The stored alerts older than a month
+    /// are added to the fetched ones, and replace them when the API fails
+    async fn fetch_alerts_history(&mut self, uid: i32) -> Result<()> {
+        let now = Instant::now();
+        if let Some(alerts) = self.history.get(uid, now).cloned() {
+            self.history.cancel_pending();
+            self.action_tx.send(Action::GetAlertsHistory(uid, alerts))?;
+            return Ok(());
+        }
+        let Ok(location_aid) = i8::try_from(uid) else {
+            return Ok(());
+        };
+        if let Err(wait) = self.history.acquire(now) {
+            debug!(target:"app", "alerts history of {} is delayed by {:?}", uid, wait);
+            if let Some(alerts) = self.stored_history(uid) {
+                self.action_tx.send(Action::GetAlertsHistory(uid, alerts))?;
+            }
+            // a single retry fetches the location selected last
+            if self.history.defer(uid) {
+                let tx = self.action_tx.clone();
+                tokio::spawn(async move {
+                    sleep(wait).await;
+                    let _ = tx.send(Action::RetryAlertsHistory);
+                });
+            }
+            return Ok(());
+        }
+        self.history.cancel_pending();
+        match self
+            .api_client
+            .get_alerts_history(&location_aid, "month_ago")
+            .await
+        {
+            Ok(alerts) => {
+                debug!(target:"app", "get_alerts_history: total {} alerts", alerts.len());
+                self.store_alerts(&alerts);
+                let alerts = self.stored_history(uid).unwrap_or(alerts);
+                self.history.insert(uid, alerts.clone(), now);
+                self.action_tx.send(Action::GetAlertsHistory(uid, alerts))?;
+            }
+            Err(e) => {
+                error!(target: "app", "get_alerts_history failed, keeping last data: {}", e);
+                self.action_tx.send(Action::Error(e.to_string()))?;
+                let alerts = match self.history.get_stale(uid) {
+                    Some(alerts) => Some(alerts.clone()),
+                    None => self.stored_history(uid),
+                };
+                if let Some(alerts) = alerts {
+                    self.action_tx.send(Action::GetAlertsHistory(uid, alerts))?;
+                }
+            }
+        }
+        self.action_tx
+            .send(Action::Online(self.api_client.connectivity()))?;
+        Ok(())
+    }
+
     pub async fn init(&mut self) -> Result<()> {
         self.action_tx.send(Action::FetchGeo)?;
         self.action_tx
             .send(Action::FetchAirRaidAlertOblastStatuses)?;
         self.action_tx.send(Action::FetchActiveAlerts)?;
+        let query = AlertQuery {
+            from: Some(OffsetDateTime::now_utc() - STORED_ALERTS_PERIOD),
+            ..Default::default()
+        };
+        match self.store.as_ref().map(|store| store.query_alerts(&query)) {
+            Some(Ok(alerts)) => {
+                debug!(target:"app", "stored alerts: total {}", alerts.len());
+                self.action_tx
+                    .send(Action::GetStoredAlerts(Alerts::new(alerts)))?;
+            }
+            Some(Err(e)) => error!(target: "app", "failed to query stored alerts: {:?}", e),
+            None => {}
+        }
         Ok(())
     }
 
@@ -85,12 +296,57 @@ impl App {
         &self.selected_tab
     }
 
+    /// Modes whose keybindings are active, the most specific first
+    pub fn modes(&self) -> Vec<Mode> {
+        if self.show_help {
+            return vec![Mode::Help];
+        }
+        if self.searching {
+            return vec![Mode::Search];
+        }
+        match self.selected_tab {
+            LayoutTab::Tab1 => vec![Mode::List, Mode::Map, Mode::Global],
+            LayoutTab::Tab2 => vec![Mode::Table, Mode::Global],
+            LayoutTab::Tab3 => vec![Mode::History, Mode::Global],
+            _ => vec![Mode::Global],
+        }
+    }
+
+    /// Map the keys pressed so far to an action, keys of an unknown chord are dropped
+    /// except the last one, which may start a new chord
+    fn handle_key(&mut self, key_event: KeyEvent) -> Result<()> {
+        let modes = self.modes();
+        let keymap = self.config.keymap();
+        self.last_tick_key_events.push(key_event);
+        let mut keys: Vec<Key> = self
+            .last_tick_key_events
+            .iter()
+            .map(|&e| e.into())
+            .collect();
+        let mut lookup = keymap.lookup(&modes, &keys);
+        if lookup == KeyLookup::None && keys.len() > 1 {
+            keys.drain(..keys.len() - 1);
+            self.last_tick_key_events
+                .drain(..self.last_tick_key_events.len() - 1);
+            lookup = keymap.lookup(&modes, &keys);
+        }
+        match lookup {
+            KeyLookup::Action(action) => {
+                self.last_tick_key_events.clear();
+                self.action_tx.send(action)?;
+            }
+            KeyLookup::Pending => {}
+            KeyLookup::None => self.last_tick_key_events.clear(),
+        }
+        Ok(())
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         let periodic_action_tx = self.action_tx.clone();
         let mut tui = tui::Tui::new()?
             .tick_rate(self.config.tick_rate())
-            .frame_rate(self.config.frame_rate());
-        // tui.mouse(true);
+            .frame_rate(self.config.frame_rate())
+            .mouse(*self.config.mouse());
         tui.enter()?;
 
         // ---------------------------------------------------------------------
@@ -131,42 +387,19 @@ impl App {
                     tui::Event::Resize(x, y) => {
                         self.action_tx.send(Action::Resize(x, y))?
                     }
-                    tui::Event::Key(key_event) => match key_event.code {
-                        KeyCode::Char('q') => {
-                            self.action_tx.send(Action::Quit)?;
-                        }
-                        KeyCode::Char('c') | KeyCode::Char('C') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                self.action_tx.send(Action::Quit)?;
-                            }
-                        }
-                        KeyCode::Right => {
-                            self.next_tab();
-                            self.action_tx
-                                .send(Action::SelectTab(self.selected_tab as usize))?;
-                        }
-                        KeyCode::Left => {
-                            self.previous_tab();
-                            self.action_tx
-                                .send(Action::SelectTab(self.selected_tab as usize))?;
-                        }
-                        KeyCode::Char('u') => {
-                            // self.action_tx.send(Action::Fetch)?;
-                        }
-                        KeyCode::Char('l') => {
-                            self.action_tx.send(Action::Locale)?;
-                        }
-                        KeyCode::Char('r') => {
-                            self.action_tx.send(Action::Refresh)?;
-                        }
-                        KeyCode::Char('z') => {
-                            self.action_tx.send(Action::Suspend)?;
-                        }
-                        _ => {}
-                    },
+                    tui::Event::Key(key_event) => self.handle_key(key_event)?,
                     _ => {}
                 }
+                let selected_tab = *self.selected_tab();
                 for component in self.components.iter_mut() {
+                    if !receives_event(
+                        &e,
+                        component.as_ref(),
+                        self.show_help,
+                        &selected_tab,
+                    ) {
+                        continue;
+                    }
                     if let Some(action) = component.handle_events(Some(e.clone()))? {
                         self.action_tx.send(action)?;
                     }
@@ -182,8 +415,35 @@ impl App {
                         self.last_tick_key_events.drain(..);
                     }
                     Action::Quit => self.should_quit = true,
+                    Action::Help => self.show_help = !self.show_help,
+                    Action::Search => self.searching = true,
+                    Action::SearchDone | Action::Cancel => self.searching = false,
+                    Action::SelectTab(tab) => {
+                        self.selected_tab = LayoutTab::from_repr(tab).unwrap_or_default();
+                    }
+                    Action::NextTab => {
+                        self.next_tab();
+                        self.action_tx
+                            .send(Action::SelectTab(self.selected_tab as usize))?;
+                    }
+                    Action::PreviousTab => {
+                        self.previous_tab();
+                        self.action_tx
+                            .send(Action::SelectTab(self.selected_tab as usize))?;
+                    }
                     Action::Suspend => self.should_suspend = true,
                     Action::Resume => self.should_suspend = false,
+                    Action::SaveSnapshot => match self.save_snapshot() {
+                        Ok(dir) => {
+                            let path = dir.display().to_string();
+                            let message = t!("messages.snapshot", path = path);
+                            self.action_tx.send(Action::Banner(message.to_string()))?;
+                        }
+                        Err(e) => {
+                            error!(target: "app", "failed to save snapshot: {:?}", e);
+                            self.action_tx.send(Action::Error(e.to_string()))?;
+                        }
+                    },
                     Action::Locale => {
                         self.config.toggle_locale();
                         self.action_tx.send(Action::Refresh)?;
@@ -226,32 +486,66 @@ impl App {
                         let locations = self.geo_client.locations();
                         debug!(target:"app", "fetch geo: total {} alerts", locations.len());
                         self.action_tx.send(Action::GetBoundaries(boundary))?;
-                        self.action_tx.send(Action::GetLocations(locations))?;
+                        self.action_tx
+                            .send(Action::GetLocations(Box::new(locations)))?;
                     }
                     Action::FetchActiveAlerts => {
-                        let response: Alerts = self.api_client.get_active_alerts().await?;
-                        debug!(target:"app", "get_active_alerts: total {} alerts", response.len());
-                        self.action_tx.send(Action::GetActiveAlerts(response))?;
+                        match self.api_client.get_active_alerts().await {
+                            Ok(response) => {
+                                debug!(target:"app", "get_active_alerts: total {} alerts", response.len());
+                                self.store_alerts(&response);
+                                if let Some(store) = self.store.as_mut() {
+                                    let now = OffsetDateTime::now_utc();
+                                    if let Err(e) = store.finish_missing(&response, now) {
+                                        error!(target: "app", "failed to finish stored alerts: {:?}", e);
+                                    }
+                                }
+                                self.alerts = Some(response.clone());
+                                self.action_tx.send(Action::GetActiveAlerts(response))?;
+                            }
+                            Err(e) => {
+                                error!(target: "app", "get_active_alerts failed, keeping last data: {}", e);
+                                self.action_tx.send(Action::Error(e.to_string()))?;
+                            }
+                        }
+                        self.action_tx
+                            .send(Action::Online(self.api_client.connectivity()))?;
+                    }
+                    Action::FetchAlertsHistory(uid) => {
+                        self.fetch_alerts_history(uid).await?
+                    }
+                    Action::RetryAlertsHistory => {
+                        if let Some(uid) = self.history.take_pending() {
+                            self.fetch_alerts_history(uid).await?
+                        }
                     }
                     Action::FetchAirRaidAlertOblastStatuses => {
-                        #[allow(clippy::bind_instead_of_map)]
-                        let response: AirRaidAlertOblastStatuses = self
+                        match self
                             .api_client
                             .get_air_raid_alert_statuses_by_location()
                             .await
-                            .and_then(|r| {
-                                trace!(target: "app", "get_air_raid_alert_statuses_by_location: {}", r.raw_data());
-                                Ok(r)
-                            })
-                            .inspect_err(|e| {
-                                error!(target: "app", "error from API catched, possibly offline");
-                                let _ = self.action_tx.send( Action::Error(e.to_string()));
-                                let _ = self.action_tx.send( Action::Online(false));
-                            })
-                            .unwrap_or_default();
-                        debug!(target:"app", "get_air_raid_alert_statuses_by_location: total {} alerts", response.len());
+                        {
+                            Ok(response) => {
+                                trace!(target: "app", "get_air_raid_alert_statuses_by_location: {}", response.raw_data());
+                                debug!(target:"app", "get_air_raid_alert_statuses_by_location: total {} alerts", response.len());
+                                // partial alerts are hidden on oblast level, but are worth
+                                // showing and a notification
+                                let statuses = AirRaidAlertOblastStatuses::new(
+                                    response.raw_data().to_string(),
+                                    Some(false),
+                                );
+                                self.notify_transitions(&statuses)?;
+                                self.action_tx.send(
+                                    Action::GetAirRaidAlertOblastStatuses(statuses),
+                                )?;
+                            }
+                            Err(e) => {
+                                error!(target: "app", "get_air_raid_alert_statuses_by_location failed, keeping last data: {}", e);
+                                self.action_tx.send(Action::Error(e.to_string()))?;
+                            }
+                        }
                         self.action_tx
-                            .send(Action::GetAirRaidAlertOblastStatuses(response))?;
+                            .send(Action::Online(self.api_client.connectivity()))?;
                     }
                     _ => {}
                 }
@@ -266,8 +560,8 @@ impl App {
                 self.action_tx.send(Action::Resume)?;
                 tui = tui::Tui::new()?
                     .tick_rate(self.config.tick_rate())
-                    .frame_rate(self.config.frame_rate());
-                // tui.mouse(true);
+                    .frame_rate(self.config.frame_rate())
+                    .mouse(*self.config.mouse());
                 tui.enter()?;
             } else if self.should_quit {
                 tui.stop()?;
@@ -278,3 +572,37 @@ impl App {
         Ok(())
     }
 }
+
+/// Mouse events go to the components on screen, key and mouse events only to the help while
+/// it is open
+fn receives_event(
+    event: &tui::Event,
+    component: &dyn Component<'_>,
+    show_help: bool,
+    selected_tab: &LayoutTab,
+) -> bool {
+    match event {
+        tui::Event::Key(_) => !show_help || component.is_modal(),
+        tui::Event::Mouse(_) => !show_help && component.is_visible(selected_tab),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    #[test]
+    fn test_keys_go_to_help_only_while_open() {
+        let key = tui::Event::Key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+        let help = Help::new();
+        let list = LocationsList::new();
+        let tab = LayoutTab::Tab1;
+
+        assert!(receives_event(&key, &list, false, &tab));
+        assert!(!receives_event(&key, &list, true, &tab));
+        assert!(receives_event(&key, &help, true, &tab));
+        assert!(receives_event(&tui::Event::Tick, &list, true, &tab));
+    }
+}
@@ -1,21 +1,31 @@
-use crate::utils::version;
-use clap::Parser;
+use crate::{
+    commands::{ExportData, ExportFormat, OutputFormat},
+    render::RenderFormat,
+    utils::version,
+};
+use clap::{Args, Parser, Subcommand};
+use ralertsinua_models::AlertType;
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(author, version = version(), about)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     #[arg(
         short,
         long,
         value_name = "TOKEN",
         help = "API token provided by alerts.in.ua",
         default_value = "",
-        required = false
+        required = false,
+        global = true
     )]
     pub token: String,
 
-    #[arg(short, long, value_name = "LOCALE", help = "Locale", required = false, value_parser = get_available_locales(), default_value = get_default_locale())]
-    pub locale: String,
+    #[arg(short, long, value_name = "LOCALE", help = "Locale, defaults to the system locale", required = false, value_parser = get_available_locales())]
+    pub locale: Option<String>,
 
     #[arg(
         long,
@@ -28,18 +38,361 @@ pub struct Cli {
     #[arg(
         long,
         value_name = "FLOAT",
-        help = "Tick rate, i.e. number of ticks per second",
-        default_value_t = 1.0
+        help = "Tick rate, i.e. number of ticks per second [default: 1.0]"
     )]
-    pub tick_rate: f64,
+    pub tick_rate: Option<f64>,
 
     #[arg(
         long,
         value_name = "FLOAT",
-        help = "Frame rate, i.e. number of frames per second",
-        default_value_t = 1.0
+        help = "Frame rate, i.e. number of frames per second [default: 1.0]"
+    )]
+    pub frame_rate: Option<f64>,
+
+    #[arg(
+        long,
+        help = "Text-only layout for screen readers instead of the map, without icons"
+    )]
+    pub accessible: bool,
+}
+
+/// Non-interactive commands, the TUI is launched when none is given
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Print air raid alert status of every oblast
+    Status(StatusArgs),
+    /// Print active alerts, optionally filtered by type and oblast
+    Alerts(AlertsArgs),
+    /// Print alerts history of the location
+    History(HistoryArgs),
+    /// Print statistics of the alerts history of the locations
+    Stats(StatsArgs),
+    /// Export active alerts, alerts history or statuses to CSV, JSON Lines, GeoJSON or iCalendar
+    Export(ExportArgs),
+    /// Render the status map to SVG, PNG or text without a terminal
+    Render(RenderArgs),
+    /// Watch for air raid alert status changes and print them as they happen
+    Watch(WatchArgs),
+    /// Check air raid alert status of a single location, for monitoring and scripts
+    Check(CheckArgs),
+    /// Serve air raid alert and API metrics in the Prometheus text format
+    ServeMetrics(ServeMetricsArgs),
+    /// Proxy the API for local clients, so the token stays on this host
+    Proxy(ProxyArgs),
+    /// Post alert start and end transitions to the configured webhooks
+    Webhooks(WebhooksArgs),
+    /// Publish retained air raid alert statuses and alerts of every oblast to an MQTT broker
+    Mqtt(MqttArgs),
+    /// Show, locate or create the config file
+    Config(ConfigArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct OutputArgs {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = OutputFormat::default(),
+        help = "Output format"
+    )]
+    pub format: OutputFormat,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct StatusArgs {
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct AlertsArgs {
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    #[arg(
+        long = "type",
+        value_name = "ALERT_TYPE",
+        help = "Alert type: air_raid, artillery_shelling, urban_fights, nuclear, chemical"
+    )]
+    pub alert_type: Option<AlertType>,
+
+    #[arg(long, value_name = "UID", help = "Oblast uid, e.g. 14 for Kyiv Oblast")]
+    pub oblast: Option<i32>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct HistoryArgs {
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    #[arg(value_name = "UID", help = "Location uid, e.g. 31 for Kyiv")]
+    pub uid: i8,
+
+    #[arg(
+        long,
+        value_name = "PERIOD",
+        default_value = "month_ago",
+        help = "History period"
+    )]
+    pub period: String,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct StatsArgs {
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    #[arg(
+        value_name = "UID",
+        required = true,
+        help = "Location uids, e.g. 31 for Kyiv, the history is fetched at most twice a minute"
+    )]
+    pub uids: Vec<i8>,
+
+    #[arg(
+        long,
+        value_name = "PERIOD",
+        default_value = "month_ago",
+        help = "History period"
+    )]
+    pub period: String,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ExportArgs {
+    #[arg(value_enum, default_value_t = ExportData::default(), help = "Data to export")]
+    pub data: ExportData,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = ExportFormat::default(),
+        help = "File format"
+    )]
+    pub format: ExportFormat,
+
+    #[arg(
+        long,
+        value_name = "UID",
+        help = "Location uid to export the alerts history of, e.g. 31 for Kyiv"
+    )]
+    pub uid: Option<i8>,
+
+    #[arg(
+        long,
+        value_name = "PERIOD",
+        default_value = "month_ago",
+        help = "History period"
+    )]
+    pub period: String,
+
+    #[arg(
+        short,
+        long,
+        value_name = "FILE",
+        help = "File to write to, printed when not given"
+    )]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct RenderArgs {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        help = "Output format, defaults to the extension of the file or text"
+    )]
+    pub format: Option<RenderFormat>,
+
+    #[arg(
+        short,
+        long,
+        value_name = "FILE",
+        help = "File to write to, printed when not given"
+    )]
+    pub out: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "WIDTH",
+        help = "Width in pixels for images [default: 1000] or in cells for text [default: 100]"
+    )]
+    pub width: Option<u16>,
+
+    #[arg(
+        long,
+        value_name = "HEIGHT",
+        help = "Height in pixels for images [default: 700] or in cells for text [default: 36]"
     )]
-    pub frame_rate: f64,
+    pub height: Option<u16>,
+
+    #[arg(long, help = "Leave the legend out")]
+    pub no_legend: bool,
+
+    #[arg(long, help = "Leave the time of the render out of the title")]
+    pub no_timestamp: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct WatchArgs {
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Polling interval, defaults to ALERTSINUA_POLLING_INTERVAL_SEC"
+    )]
+    pub interval: Option<u64>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct CheckArgs {
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    #[arg(
+        short = 'L',
+        long,
+        value_name = "LOCATION",
+        help = "Location uid, uk/en name or ISO 3166-2 code, e.g. 31, \"Київ\", \"Kyiv Oblast\", UA-32"
+    )]
+    pub location: String,
+
+    #[arg(long, help = "Block until the alert on the location is over")]
+    pub wait_until_clear: bool,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Polling interval for --wait-until-clear, defaults to ALERTSINUA_POLLING_INTERVAL_SEC"
+    )]
+    pub interval: Option<u64>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ServeMetricsArgs {
+    #[arg(
+        long,
+        value_name = "ADDR",
+        default_value = "127.0.0.1:9184",
+        help = "Address to serve metrics on"
+    )]
+    pub listen: String,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Polling interval, defaults to ALERTSINUA_POLLING_INTERVAL_SEC, at least 20"
+    )]
+    pub interval: Option<u64>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ProxyArgs {
+    #[arg(
+        long,
+        value_name = "ADDR",
+        default_value = "127.0.0.1:8090",
+        help = "Address to serve the API on"
+    )]
+    pub listen: String,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Upstream polling interval, defaults to ALERTSINUA_POLLING_INTERVAL_SEC, at least 20"
+    )]
+    pub interval: Option<u64>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct WebhooksArgs {
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Webhook targets, defaults to webhooks.toml in the config directory"
+    )]
+    pub config: Option<PathBuf>,
+
+    #[arg(long, help = "Print requests instead of sending them")]
+    pub dry_run: bool,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Polling interval, defaults to ALERTSINUA_POLLING_INTERVAL_SEC"
+    )]
+    pub interval: Option<u64>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct MqttArgs {
+    #[arg(
+        long,
+        value_name = "HOST[:PORT]",
+        default_value = "localhost:1883",
+        help = "MQTT broker to publish to"
+    )]
+    pub broker: String,
+
+    #[arg(
+        long,
+        value_name = "PREFIX",
+        default_value = "alerts/ua",
+        help = "Topic prefix, statuses are published to <PREFIX>/<UID>/status"
+    )]
+    pub prefix: String,
+
+    #[arg(
+        long,
+        value_name = "ID",
+        default_value = "ralertsinua",
+        help = "MQTT client id"
+    )]
+    pub client_id: String,
+
+    #[arg(
+        long,
+        value_name = "USERNAME",
+        help = "Broker username, the password is read from ALERTSINUA_MQTT_PASSWORD"
+    )]
+    pub username: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Polling interval, defaults to ALERTSINUA_POLLING_INTERVAL_SEC"
+    )]
+    pub interval: Option<u64>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigCommand {
+    /// Print effective settings with the layer each one comes from
+    Show,
+    /// Print path of the config file
+    Path,
+    /// Write config file template with every setting and its default
+    Init {
+        #[arg(long, help = "Overwrite existing config file")]
+        force: bool,
+    },
 }
 
 #[inline]
@@ -51,11 +404,3 @@ fn get_available_locales() -> Vec<&'static str> {
 
     locales
 }
-
-#[inline]
-fn get_default_locale() -> String {
-    match sys_locale::get_locale() {
-        Some(sl) => sl[..2].to_string(),
-        None => "en".to_string(),
-    }
-}
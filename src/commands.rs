@@ -0,0 +1,340 @@
+use clap::ValueEnum;
+use miette::{IntoDiagnostic, Result};
+use ralertsinua_geo::AlertsInUaGeo;
+use ralertsinua_http::AlertsInUaApi;
+use ralertsinua_models::*;
+use serde::Serialize;
+use std::{io::Write, process::ExitCode, sync::Arc};
+use time::format_description::well_known::Rfc3339;
+
+use crate::{cli::Command, config::Config};
+
+pub mod alerts;
+pub mod check;
+pub mod config;
+pub mod export;
+pub mod history;
+pub mod mqtt;
+pub mod proxy;
+pub mod render;
+pub mod serve_metrics;
+pub mod stats;
+pub mod status;
+pub mod watch;
+pub mod webhooks;
+
+pub use alerts::*;
+pub use check::*;
+pub use config::*;
+pub use export::*;
+pub use history::*;
+pub use mqtt::*;
+pub use proxy::*;
+pub use render::*;
+pub use serve_metrics::*;
+pub use stats::*;
+pub use status::*;
+pub use watch::*;
+pub use webhooks::*;
+
+/// Output format of the non-interactive commands
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable aligned table
+    #[default]
+    Table,
+    /// JSON array, or JSON Lines for streaming commands
+    Json,
+    /// Comma separated values with header row
+    Csv,
+}
+
+/// Process exit code reflecting alert state, follows Nagios plugin conventions
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlertExitCode {
+    #[default]
+    NoAlert = 0,
+    Partial = 1,
+    Active = 2,
+    Unknown = 3,
+}
+
+impl From<AlertExitCode> for ExitCode {
+    fn from(code: AlertExitCode) -> Self {
+        ExitCode::from(code as u8)
+    }
+}
+
+impl From<&AlertStatus> for AlertExitCode {
+    fn from(status: &AlertStatus) -> Self {
+        match status {
+            AlertStatus::A => AlertExitCode::Active,
+            AlertStatus::P => AlertExitCode::Partial,
+            AlertStatus::N => AlertExitCode::NoAlert,
+            AlertStatus::L | AlertStatus::O => AlertExitCode::Unknown,
+        }
+    }
+}
+
+/// Record which can be printed as a row of table or CSV
+pub trait Tabular: Serialize {
+    fn headers() -> &'static [&'static str];
+    fn row(&self) -> Vec<String>;
+}
+
+impl Tabular for AirRaidAlertOblastStatus {
+    fn headers() -> &'static [&'static str] {
+        &["uid", "title", "title_en", "status"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.location_uid.to_string(),
+            self.location_title().to_string(),
+            self.location_title_en().to_string(),
+            char::from(self.status()).to_string(),
+        ]
+    }
+}
+
+impl Tabular for Alert {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "id",
+            "location_uid",
+            "location_title",
+            "location_type",
+            "alert_type",
+            "started_at",
+            "finished_at",
+            "location_oblast_uid",
+            "notes",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.location_uid.to_string(),
+            self.location_title.clone(),
+            self.location_type.to_string(),
+            self.alert_type.to_string(),
+            self.started_at.format(&Rfc3339).unwrap_or_default(),
+            self.finished_at.clone().unwrap_or_default(),
+            self.location_oblast_uid.to_string(),
+            self.notes.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+impl Tabular for LocationStats {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "uid",
+            "title",
+            "count",
+            "total_seconds",
+            "average_seconds",
+            "longest_seconds",
+            "shortest_seconds",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.location_uid.to_string(),
+            self.location_title.clone(),
+            self.count.to_string(),
+            self.total_seconds.to_string(),
+            self.average_seconds.to_string(),
+            self.longest_seconds.to_string(),
+            self.shortest_seconds.to_string(),
+        ]
+    }
+}
+
+impl Tabular for CoOccurrence {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "oblast_uid",
+            "oblast_title",
+            "other_oblast_uid",
+            "other_oblast_title",
+            "count",
+            "overlap_seconds",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.oblast_uid.to_string(),
+            self.oblast_title.clone(),
+            self.other_oblast_uid.to_string(),
+            self.other_oblast_title.clone(),
+            self.count.to_string(),
+            self.overlap_seconds.to_string(),
+        ]
+    }
+}
+
+impl Tabular for AirRaidAlertOblastStatusChange {
+    fn headers() -> &'static [&'static str] {
+        &["uid", "title", "title_en", "previous", "current"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.location_uid.to_string(),
+            self.location_title().to_string(),
+            self.location_title_en().to_string(),
+            char::from(self.previous()).to_string(),
+            char::from(self.current()).to_string(),
+        ]
+    }
+}
+
+/// Print records in the given format
+pub fn print_records<T, W>(records: &[T], format: OutputFormat, out: &mut W) -> Result<()>
+where
+    T: Tabular,
+    W: Write,
+{
+    match format {
+        OutputFormat::Table => print_table(T::headers(), records, out)?,
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, records).into_diagnostic()?;
+            writeln!(out).into_diagnostic()?;
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            writer.write_record(T::headers()).into_diagnostic()?;
+            for record in records {
+                writer.write_record(record.row()).into_diagnostic()?;
+            }
+            writer.flush().into_diagnostic()?;
+        }
+    }
+    Ok(())
+}
+
+/// Print records as a table with columns aligned by the widest cell
+fn print_table<T, W>(headers: &[&str], records: &[T], out: &mut W) -> Result<()>
+where
+    T: Tabular,
+    W: Write,
+{
+    let rows: Vec<Vec<String>> = records.iter().map(Tabular::row).collect();
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            rows.iter()
+                .map(|r| r[i].chars().count())
+                .chain(std::iter::once(h.chars().count()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let format_row = |cells: Vec<String>| -> String {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(c, w)| format!("{:<width$}", c, width = w))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let header = format_row(headers.iter().map(|h| h.to_uppercase()).collect());
+    writeln!(out, "{}", header).into_diagnostic()?;
+    for row in rows {
+        writeln!(out, "{}", format_row(row)).into_diagnostic()?;
+    }
+    Ok(())
+}
+
+/// Run the non-interactive command and return exit code reflecting alert state
+pub async fn run(
+    command: Command,
+    api_client: Arc<dyn AlertsInUaApi>,
+    geo_client: Arc<dyn AlertsInUaGeo>,
+    config: &Config,
+) -> Result<AlertExitCode> {
+    let out = &mut std::io::stdout();
+    match command {
+        Command::Status(args) => status(api_client, &args, out).await,
+        Command::Alerts(args) => alerts(api_client, &args, out).await,
+        Command::History(args) => history(api_client, &args, out).await,
+        Command::Stats(args) => stats(api_client, &args, out).await,
+        Command::Export(args) => export(api_client, geo_client, &args, out).await,
+        Command::Render(args) => render(api_client, geo_client, &args, config, out).await,
+        Command::Watch(args) => watch(api_client, &args, config, out).await,
+        Command::Check(args) => check(api_client, geo_client, &args, config, out).await,
+        Command::ServeMetrics(args) => serve_metrics(api_client, &args, config).await,
+        Command::Proxy(args) => proxy(api_client, &args, config).await,
+        Command::Webhooks(args) => webhooks(api_client, &args, config).await,
+        Command::Mqtt(args) => mqtt(api_client, &args, config).await,
+        Command::Config(_) => {
+            unreachable!("config command runs before the token is required")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn statuses() -> Vec<AirRaidAlertOblastStatus> {
+        AirRaidAlertOblastStatuses::new(
+            "ANNAANNANNNPANANANNNNAANNNN".to_string(),
+            Some(true),
+        )
+        .get_all()[..2]
+            .to_vec()
+    }
+
+    #[test]
+    fn test_print_table() {
+        let mut out = Vec::new();
+        print_records(&statuses(), OutputFormat::Table, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("UID  TITLE"));
+        assert!(lines[1].starts_with("29   Автономна Республіка Крим"));
+        assert!(lines[1].ends_with("A"));
+        assert!(lines[2].ends_with("N"));
+    }
+
+    #[test]
+    fn test_print_csv() {
+        let mut out = Vec::new();
+        print_records(&statuses(), OutputFormat::Csv, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "uid,title,title_en,status\n\
+            29,Автономна Республіка Крим,Autonomous Republic of Crimea,A\n\
+            8,Вінницька область,Vinnytsia Oblast,N\n"
+        );
+    }
+
+    #[test]
+    fn test_print_json() {
+        let mut out = Vec::new();
+        print_records(&statuses(), OutputFormat::Json, &mut out).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(value[0]["location_uid"], 29);
+        assert_eq!(value[0]["status"], "A");
+        assert_eq!(value[1]["location_title_en"], "Vinnytsia Oblast");
+    }
+
+    #[test]
+    fn test_exit_code_by_status() {
+        assert_eq!(AlertExitCode::from(&AlertStatus::A), AlertExitCode::Active);
+        assert_eq!(AlertExitCode::from(&AlertStatus::P), AlertExitCode::Partial);
+        assert_eq!(AlertExitCode::from(&AlertStatus::N), AlertExitCode::NoAlert);
+        assert_eq!(AlertExitCode::from(&AlertStatus::O), AlertExitCode::Unknown);
+    }
+}
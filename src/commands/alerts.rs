@@ -0,0 +1,89 @@
+use miette::{IntoDiagnostic, Result};
+use ralertsinua_http::AlertsInUaApi;
+use ralertsinua_models::*;
+use std::{io::Write, sync::Arc};
+
+use super::{print_records, AlertExitCode};
+use crate::cli::AlertsArgs;
+
+/// Print active alerts filtered by type and oblast.
+/// Exits with [`AlertExitCode::Active`] if there is at least one matching alert
+pub async fn alerts<W: Write>(
+    api_client: Arc<dyn AlertsInUaApi>,
+    args: &AlertsArgs,
+    out: &mut W,
+) -> Result<AlertExitCode> {
+    let alerts = api_client.get_active_alerts().await.into_diagnostic()?;
+    let alerts = filter_alerts(&alerts, args.alert_type.clone(), args.oblast);
+    print_records(&alerts, args.output.format, out)?;
+
+    let code = match alerts.is_empty() {
+        true => AlertExitCode::NoAlert,
+        false => AlertExitCode::Active,
+    };
+    Ok(code)
+}
+
+fn filter_alerts(
+    alerts: &Alerts,
+    alert_type: Option<AlertType>,
+    oblast_uid: Option<i32>,
+) -> Vec<Alert> {
+    let alerts = match alert_type {
+        Some(alert_type) => alerts.get_alerts_by_alert_type(alert_type),
+        None => alerts.get_alerts(),
+    };
+    match oblast_uid {
+        Some(uid) => alerts
+            .into_iter()
+            .filter(|a| a.location_oblast_uid == uid)
+            .collect(),
+        None => alerts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_filter_alerts() {
+        let alerts: Alerts = serde_json::from_value(json!({
+            "alerts": [
+                {
+                    "alert_type": "air_raid", "id": 1, "finished_at": null, "notes": null,
+                    "location_oblast": "Київська область", "location_oblast_uid": 14,
+                    "location_title": "Київська область", "location_type": "oblast",
+                    "location_uid": "14", "started_at": "2024-05-05T15:48:31.000Z",
+                    "updated_at": "2024-05-05T15:48:31.818Z"
+                }, {
+                    "alert_type": "artillery_shelling", "id": 2, "finished_at": null, "notes": null,
+                    "location_oblast": "Київська область", "location_oblast_uid": 14,
+                    "location_title": "Бучанська територіальна громада", "location_type": "hromada",
+                    "location_uid": "1234", "started_at": "2024-05-05T15:48:31.000Z",
+                    "updated_at": "2024-05-05T15:48:31.818Z"
+                }, {
+                    "alert_type": "air_raid", "id": 3, "finished_at": null, "notes": null,
+                    "location_oblast": "Харківська область", "location_oblast_uid": 22,
+                    "location_title": "Харківська область", "location_type": "oblast",
+                    "location_uid": "22", "started_at": "2024-05-05T15:48:31.000Z",
+                    "updated_at": "2024-05-05T15:48:31.818Z"
+                }
+            ],
+            "disclaimer": "",
+            "meta": { "last_updated_at": "2024/05/06 10:02:45 +0000" }
+        }))
+        .unwrap();
+
+        assert_eq!(filter_alerts(&alerts, None, None).len(), 3);
+        assert_eq!(
+            filter_alerts(&alerts, Some(AlertType::AirRaid), None).len(),
+            2
+        );
+        assert_eq!(filter_alerts(&alerts, None, Some(14)).len(), 2);
+        let filtered = filter_alerts(&alerts, Some(AlertType::AirRaid), Some(14));
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].id, 1);
+    }
+}
@@ -0,0 +1,180 @@
+use miette::{miette, IntoDiagnostic, Result};
+use ralertsinua_geo::{AlertsInUaGeo, Location};
+use ralertsinua_http::AlertsInUaApi;
+use ralertsinua_models::*;
+use std::{io::Write, sync::Arc};
+use tokio::time::{interval, Duration, MissedTickBehavior};
+
+use super::{print_records, AlertExitCode, OutputFormat};
+use crate::{cli::CheckArgs, config::Config};
+
+/// Check air raid alert status of a single location.
+/// Exits with `0` for no alert, `1` for partial and `2` for active alert,
+/// or blocks until the alert is over with `--wait-until-clear`
+pub async fn check<W: Write>(
+    api_client: Arc<dyn AlertsInUaApi>,
+    geo_client: Arc<dyn AlertsInUaGeo>,
+    args: &CheckArgs,
+    config: &Config,
+    out: &mut W,
+) -> Result<AlertExitCode> {
+    let location = geo_client.find_location(&args.location).ok_or_else(|| {
+        miette!(
+            help = "use location uid, uk/en name or ISO 3166-2 code, e.g. 31, \"Київ\", \"Kyiv Oblast\", UA-32",
+            "location '{}' not found",
+            args.location
+        )
+    })?;
+    let format = args.output.format;
+    let mut status = fetch_status(api_client.as_ref(), &location).await?;
+    print_status(&location, &status, format, out)?;
+
+    if args.wait_until_clear {
+        let seconds = args.interval.unwrap_or(*config.polling_interval());
+        let mut ticker = interval(Duration::from_secs(seconds));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticker.tick().await;
+        while status != AlertStatus::N {
+            ticker.tick().await;
+            match fetch_status(api_client.as_ref(), &location).await {
+                Ok(s) if s != status => {
+                    status = s;
+                    print_status(&location, &status, format, out)?;
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("failed to fetch status: {}", e),
+            }
+        }
+    }
+
+    Ok(AlertExitCode::from(&status))
+}
+
+async fn fetch_status(
+    api_client: &dyn AlertsInUaApi,
+    location: &Location,
+) -> Result<AlertStatus> {
+    let uid = i8::try_from(location.location_uid).into_diagnostic()?;
+    let data = api_client
+        .get_air_raid_alert_status(&uid)
+        .await
+        .into_diagnostic()?;
+    let status = data
+        .trim_matches('"')
+        .chars()
+        .next()
+        .map(AlertStatus::from)
+        .unwrap_or(AlertStatus::O);
+    Ok(status)
+}
+
+/// Print single line for table format (plugin output), status record otherwise
+fn print_status<W: Write>(
+    location: &Location,
+    status: &AlertStatus,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<()> {
+    match format {
+        OutputFormat::Table => {
+            writeln!(out, "{}: {} ({})", status, location.name_en, location.name)
+                .into_diagnostic()?;
+            out.flush().into_diagnostic()
+        }
+        _ => {
+            let record = AirRaidAlertOblastStatus::new(
+                location.location_uid,
+                location.name.clone(),
+                location.name_en.clone(),
+                char::from(status),
+                Some(false),
+            );
+            print_records(&[record], format, out)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::OutputArgs;
+    use mockito::Server as MockServer;
+    use ralertsinua_geo::AlertsInUaGeoClient;
+    use ralertsinua_http::AlertsInUaClient;
+
+    #[tokio::test]
+    async fn test_check_partial() -> Result<()> {
+        let mut server = MockServer::new_async().await;
+        let api_client = Arc::new(AlertsInUaClient::new(server.url().as_str(), "token"));
+        let geo_client = Arc::new(AlertsInUaGeoClient::default());
+        let mock = server
+            .mock("GET", "/v1/iot/active_air_raid_alerts/14.json")
+            .with_header("Last-Modified", "Tue, 14 May 2024 18:18:18 GMT")
+            .with_body(r#""P""#)
+            .create_async()
+            .await;
+        let args = CheckArgs {
+            output: OutputArgs {
+                format: OutputFormat::Table,
+            },
+            location: "UA-32".to_string(),
+            wait_until_clear: false,
+            interval: None,
+        };
+        let mut out = Vec::new();
+
+        let code =
+            check(api_client, geo_client, &args, &Config::default(), &mut out).await?;
+
+        mock.assert();
+        assert_eq!(code, AlertExitCode::Partial);
+        assert_eq!(
+            String::from_utf8(out).into_diagnostic()?,
+            "Partial: Kyiv Oblast (Київська область)\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_rejects_zero_interval() {
+        use clap::Parser;
+        let parse = |interval| {
+            crate::cli::Cli::try_parse_from([
+                "ralertsinua",
+                "check",
+                "-L",
+                "31",
+                "--wait-until-clear",
+                "--interval",
+                interval,
+            ])
+        };
+        assert!(parse("0").is_err());
+        assert!(parse("1").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_check_unknown_location() {
+        let api_client = Arc::new(AlertsInUaClient::new("http://127.0.0.1:9", "token"));
+        let geo_client = Arc::new(AlertsInUaGeoClient::default());
+        let args = CheckArgs {
+            output: OutputArgs {
+                format: OutputFormat::Table,
+            },
+            location: "Atlantis".to_string(),
+            wait_until_clear: false,
+            interval: None,
+        };
+
+        let result = check(
+            api_client,
+            geo_client,
+            &args,
+            &Config::default(),
+            &mut Vec::new(),
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+}
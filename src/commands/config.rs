@@ -0,0 +1,81 @@
+use miette::{miette, IntoDiagnostic, Result};
+use std::{io::Write, path::Path};
+
+use super::AlertExitCode;
+use crate::{
+    cli::{Cli, ConfigArgs, ConfigCommand},
+    config::{Config, CONFIG_TEMPLATE},
+};
+
+/// Show, locate or create the config file, works without a token
+pub fn config<W: Write>(
+    args: &ConfigArgs,
+    path: &Path,
+    cli: &Cli,
+    out: &mut W,
+) -> Result<AlertExitCode> {
+    match args.command {
+        ConfigCommand::Show => {
+            let config = Config::load(path, cli)?;
+            writeln!(out, "# {}", path.display()).into_diagnostic()?;
+            write!(out, "{}", config.to_annotated_toml()?).into_diagnostic()?;
+        }
+        ConfigCommand::Path => writeln!(out, "{}", path.display()).into_diagnostic()?,
+        ConfigCommand::Init { force } => {
+            if path.exists() && !force {
+                return Err(miette!(
+                    help = "use --force to overwrite it",
+                    "config file already exists, {}",
+                    path.display()
+                ));
+            }
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).into_diagnostic()?;
+            }
+            std::fs::write(path, CONFIG_TEMPLATE).into_diagnostic()?;
+            writeln!(out, "created {}", path.display()).into_diagnostic()?;
+        }
+    }
+    Ok(AlertExitCode::NoAlert)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn test_init_and_show() {
+        let dir =
+            std::env::temp_dir().join(format!("ralertsinua-config-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        let cli = Cli::parse_from([
+            "ralertsinua",
+            "--locale",
+            "en",
+            "--tick-rate",
+            "4",
+            "config",
+            "init",
+        ]);
+        let args = ConfigArgs {
+            command: ConfigCommand::Init { force: false },
+        };
+        let mut out = Vec::new();
+
+        config(&args, &path, &cli, &mut out).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), CONFIG_TEMPLATE);
+        assert!(config(&args, &path, &cli, &mut out).is_err());
+
+        let args = ConfigArgs {
+            command: ConfigCommand::Show,
+        };
+        let mut out = Vec::new();
+        config(&args, &path, &cli, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("tick_rate = 4.0  # cli\n"));
+        assert!(out.contains("\n[keybindings.list]\n"));
+        assert!(out.contains("\n\"gg\" = \"SelectFirst\"\n"));
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
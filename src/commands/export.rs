@@ -0,0 +1,165 @@
+use clap::ValueEnum;
+use miette::{miette, IntoDiagnostic, Result};
+use ralertsinua_geo::AlertsInUaGeo;
+use ralertsinua_http::AlertsInUaApi;
+use ralertsinua_models::*;
+use std::{fs::File, io::Write, sync::Arc};
+
+use super::AlertExitCode;
+use crate::cli::ExportArgs;
+
+/// Data to export
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportData {
+    /// Active alerts, or the history of the location given with `--uid`
+    #[default]
+    Alerts,
+    /// Air raid alert status of every oblast, including partial alerts
+    Statuses,
+}
+
+/// File format of the export
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Comma separated values with header row
+    #[default]
+    Csv,
+    /// JSON Lines, a record per line
+    Ndjson,
+    /// GeoJSON feature collection of the oblast boundaries, statuses only
+    Geojson,
+    /// iCalendar with an event per alert, alerts only
+    Ics,
+}
+
+impl ExportData {
+    /// Check the data can be written in the format
+    pub fn check_format(&self, format: ExportFormat) -> Result<()> {
+        match (self, format) {
+            (ExportData::Alerts, ExportFormat::Geojson) => Err(miette!(
+                "alerts can not be exported as geojson, only statuses"
+            )),
+            (ExportData::Statuses, ExportFormat::Ics) => {
+                Err(miette!("statuses can not be exported as ics, only alerts"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Write alerts in the format
+pub fn export_alerts<W: Write>(
+    alerts: &Alerts,
+    format: ExportFormat,
+    out: W,
+) -> Result<()> {
+    match format {
+        ExportFormat::Csv => alerts.write_csv(out),
+        ExportFormat::Ndjson => alerts.write_ndjson(out),
+        ExportFormat::Ics => alerts.write_icalendar(out),
+        ExportFormat::Geojson => return ExportData::Alerts.check_format(format),
+    }
+    .into_diagnostic()
+}
+
+/// Write statuses in the format, GeoJSON joins them with the boundaries of the locations
+pub fn export_statuses<W: Write>(
+    statuses: &AirRaidAlertOblastStatuses,
+    geo_client: &dyn AlertsInUaGeo,
+    format: ExportFormat,
+    out: W,
+) -> Result<()> {
+    match format {
+        ExportFormat::Csv => statuses.write_csv(out),
+        ExportFormat::Ndjson => statuses.write_ndjson(out),
+        ExportFormat::Geojson => statuses.write_geojson(&geo_client.locations(), out),
+        ExportFormat::Ics => return ExportData::Statuses.check_format(format),
+    }
+    .into_diagnostic()
+}
+
+/// Export alerts or statuses to the file, or print them when no file is given
+pub async fn export<W: Write>(
+    api_client: Arc<dyn AlertsInUaApi>,
+    geo_client: Arc<dyn AlertsInUaGeo>,
+    args: &ExportArgs,
+    out: &mut W,
+) -> Result<AlertExitCode> {
+    args.data.check_format(args.format)?;
+    let mut file = match &args.out {
+        Some(path) => Some(File::create(path).into_diagnostic()?),
+        None => None,
+    };
+    let out: &mut dyn Write = match file.as_mut() {
+        Some(file) => file,
+        None => out,
+    };
+    match args.data {
+        ExportData::Alerts => {
+            let alerts = match args.uid {
+                Some(uid) => api_client.get_alerts_history(&uid, &args.period).await,
+                None => api_client.get_active_alerts().await,
+            }
+            .into_diagnostic()?;
+            export_alerts(&alerts, args.format, out)?;
+        }
+        ExportData::Statuses => {
+            let response = api_client
+                .get_air_raid_alert_statuses_by_location()
+                .await
+                .into_diagnostic()?;
+            // partial alerts are hidden on oblast level
+            let statuses = AirRaidAlertOblastStatuses::new(
+                response.raw_data().to_string(),
+                Some(false),
+            );
+            export_statuses(&statuses, geo_client.as_ref(), args.format, out)?;
+        }
+    }
+    Ok(AlertExitCode::NoAlert)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server as MockServer;
+    use ralertsinua_geo::AlertsInUaGeoClient;
+    use ralertsinua_http::AlertsInUaClient;
+
+    #[tokio::test]
+    async fn test_export_statuses() -> Result<()> {
+        let mut server = MockServer::new_async().await;
+        let api_client = Arc::new(AlertsInUaClient::new(server.url().as_str(), "token"));
+        let geo_client = Arc::new(AlertsInUaGeoClient::default());
+        server
+            .mock("GET", "/v1/iot/active_air_raid_alerts_by_oblast.json")
+            .with_header("Last-Modified", "Tue, 14 May 2024 18:18:18 GMT")
+            .with_body(r#""NNNNNNNNNNNPNNNNNNNNNNNNNNN""#)
+            .create_async()
+            .await;
+        let mut args = ExportArgs {
+            data: ExportData::Statuses,
+            format: ExportFormat::Geojson,
+            uid: None,
+            period: "month_ago".to_string(),
+            out: None,
+        };
+        let mut out = Vec::new();
+        export(api_client.clone(), geo_client.clone(), &args, &mut out).await?;
+
+        let geojson: serde_json::Value = serde_json::from_slice(&out).into_diagnostic()?;
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 27);
+        let partial = features
+            .iter()
+            .filter(|f| f["properties"]["status"] == "P")
+            .count();
+        assert_eq!(partial, 1);
+
+        args.format = ExportFormat::Ics;
+        assert!(export(api_client, geo_client, &args, &mut out)
+            .await
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use miette::{IntoDiagnostic, Result};
+use ralertsinua_http::AlertsInUaApi;
+use std::{io::Write, sync::Arc};
+
+use super::{print_records, AlertExitCode};
+use crate::cli::HistoryArgs;
+
+/// Print alerts history of the location.
+/// Exits with [`AlertExitCode::Active`] if some alert from the history is not finished yet
+pub async fn history<W: Write>(
+    api_client: Arc<dyn AlertsInUaApi>,
+    args: &HistoryArgs,
+    out: &mut W,
+) -> Result<AlertExitCode> {
+    let alerts = api_client
+        .get_alerts_history(&args.uid, &args.period)
+        .await
+        .into_diagnostic()?;
+    print_records(&alerts.get_alerts(), args.output.format, out)?;
+
+    let code = match alerts.iter().any(|a| a.finished_at.is_none()) {
+        true => AlertExitCode::Active,
+        false => AlertExitCode::NoAlert,
+    };
+    Ok(code)
+}
@@ -0,0 +1,76 @@
+use miette::{IntoDiagnostic, Result};
+use ralertsinua_http::AlertsInUaApi;
+use rumqttc::AsyncClient;
+use std::sync::Arc;
+use tokio::time::{timeout, Duration};
+use tracing::warn;
+
+use super::AlertExitCode;
+use crate::{
+    cli::MqttArgs,
+    config::Config,
+    mqtt::{drive, mqtt_options, publish, shutdown, MqttPublisher, MqttTopics},
+    poller::StatusPoller,
+};
+
+/// How long to wait for the pending messages to be sent on shutdown
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Publish retained statuses and active alerts of every oblast to the broker until
+/// interrupted with Ctrl-C
+pub async fn mqtt(
+    api_client: Arc<dyn AlertsInUaApi>,
+    args: &MqttArgs,
+    config: &Config,
+) -> Result<AlertExitCode> {
+    let topics = MqttTopics::new(&args.prefix);
+    let password = std::env::var("ALERTSINUA_MQTT_PASSWORD").unwrap_or_default();
+    let credentials = args.username.as_deref().map(|u| (u, password.as_str()));
+    let options = mqtt_options(&args.client_id, &args.broker, credentials, &topics)?;
+    let (client, eventloop) = AsyncClient::new(options, 64);
+    let connection = tokio::spawn(drive(eventloop, client.clone(), topics.clone()));
+
+    let seconds = args.interval.unwrap_or(*config.polling_interval());
+    let mut poller =
+        StatusPoller::new(api_client.clone(), Duration::from_secs(seconds)).keep_partial();
+    let mut publisher = MqttPublisher::new(topics);
+    eprintln!(
+        "publishing alerts to mqtt://{}/{}, polling every {}s, press Ctrl-C to stop",
+        args.broker, args.prefix, seconds
+    );
+
+    let mut baseline_published = false;
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            result = poller.next() => {
+                let (changes, statuses) = match (result, poller.current()) {
+                    (Ok(changes), Some(statuses)) => (changes, statuses.clone()),
+                    (Err(e), _) => {
+                        warn!(target: "app", "failed to fetch statuses: {}", e);
+                        continue;
+                    }
+                    _ => continue,
+                };
+                // the first poll only sets the baseline, every status is published then
+                let mut messages = match baseline_published {
+                    true => publisher.change_messages(&changes),
+                    false => publisher.status_messages(&statuses),
+                };
+                baseline_published = true;
+                match api_client.get_active_alerts().await {
+                    Ok(alerts) => messages.extend(publisher.alert_messages(&statuses, &alerts)),
+                    Err(e) => warn!(target: "app", "failed to fetch active alerts: {}", e),
+                }
+                publish(&client, &messages).await.into_diagnostic()?;
+            }
+        }
+    }
+
+    shutdown(&client, publisher.topics()).await?;
+    if timeout(SHUTDOWN_TIMEOUT, connection).await.is_err() {
+        warn!(target: "app", "mqtt broker did not acknowledge disconnect in time");
+    }
+
+    Ok(AlertExitCode::NoAlert)
+}
@@ -0,0 +1,70 @@
+use miette::{IntoDiagnostic, Result};
+use ralertsinua_http::AlertsInUaApi;
+use std::sync::Arc;
+use tokio::time::Duration;
+use tracing::warn;
+
+use super::AlertExitCode;
+use crate::{
+    cli::ProxyArgs,
+    config::Config,
+    poller::StatusPoller,
+    proxy::{router, ProxyState, MIN_PROXY_INTERVAL_SEC},
+};
+
+/// Poll statuses and active alerts upstream and feed them into the proxy state
+async fn poll_upstream(state: Arc<ProxyState>, period: Duration) {
+    let api_client = state.api_client();
+    let mut poller = StatusPoller::new(api_client.clone(), period);
+    loop {
+        let result = poller.next().await;
+        state.record_upstream_request().await;
+        match result {
+            Ok(changes) => {
+                if let Some(statuses) = poller.current() {
+                    state.set_statuses(statuses, changes).await;
+                }
+            }
+            Err(e) => warn!(target: "app", "failed to fetch statuses: {}", e),
+        }
+        let result = api_client.get_active_alerts().await;
+        state.record_upstream_request().await;
+        match result {
+            Ok(alerts) => state.set_alerts(&alerts).await,
+            Err(e) => warn!(target: "app", "failed to fetch active alerts: {}", e),
+        }
+    }
+}
+
+/// Serve the `/v1/...` API and the `/v1/events` stream of status changes to local clients
+/// until interrupted with Ctrl-C, the token never leaves the proxy
+pub async fn proxy(
+    api_client: Arc<dyn AlertsInUaApi>,
+    args: &ProxyArgs,
+    config: &Config,
+) -> Result<AlertExitCode> {
+    let seconds = args
+        .interval
+        .unwrap_or(*config.polling_interval())
+        .max(MIN_PROXY_INTERVAL_SEC);
+    let state = Arc::new(ProxyState::new(api_client));
+    let listener = tokio::net::TcpListener::bind(&args.listen)
+        .await
+        .into_diagnostic()?;
+    eprintln!(
+        "proxying alerts.in.ua on http://{}, polling every {}s, press Ctrl-C to stop",
+        listener.local_addr().into_diagnostic()?,
+        seconds
+    );
+
+    let poller = tokio::spawn(poll_upstream(state.clone(), Duration::from_secs(seconds)));
+    axum::serve(listener, router(state))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+        .into_diagnostic()?;
+    poller.abort();
+
+    Ok(AlertExitCode::NoAlert)
+}
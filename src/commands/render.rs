@@ -0,0 +1,50 @@
+use miette::{IntoDiagnostic, Result};
+use ralertsinua_geo::AlertsInUaGeo;
+use ralertsinua_http::AlertsInUaApi;
+use ralertsinua_models::AirRaidAlertOblastStatuses;
+use std::{io::Write, sync::Arc};
+use time::OffsetDateTime;
+
+use super::AlertExitCode;
+use crate::{cli::RenderArgs, config::Config, render::*};
+
+/// Render the current status map to the file, or print it when no file is given.
+/// The format defaults to the one of the file extension, text is printed otherwise
+pub async fn render<W: Write>(
+    api_client: Arc<dyn AlertsInUaApi>,
+    geo_client: Arc<dyn AlertsInUaGeo>,
+    args: &RenderArgs,
+    config: &Config,
+    out: &mut W,
+) -> Result<AlertExitCode> {
+    let format = args
+        .format
+        .or_else(|| args.out.as_deref().and_then(RenderFormat::from_path))
+        .unwrap_or(RenderFormat::Text);
+    let (width, height) = match format {
+        RenderFormat::Svg | RenderFormat::Png => DEFAULT_IMAGE_SIZE,
+        RenderFormat::Text | RenderFormat::Ansi => DEFAULT_TEXT_SIZE,
+    };
+    let options = RenderOptions {
+        width: args.width.unwrap_or(width),
+        height: args.height.unwrap_or(height),
+        legend: !args.no_legend,
+        locale: config.get_locale().to_string(),
+        timestamp: (!args.no_timestamp).then(OffsetDateTime::now_utc),
+        theme: config.theme().clone(),
+    };
+
+    let response = api_client
+        .get_air_raid_alert_statuses_by_location()
+        .await
+        .into_diagnostic()?;
+    // partial alerts are hidden on oblast level
+    let statuses =
+        AirRaidAlertOblastStatuses::new(response.raw_data().to_string(), Some(false));
+    let bytes = MapRenderer::new(geo_client.as_ref(), &statuses, options).render(format)?;
+    match &args.out {
+        Some(path) => std::fs::write(path, bytes).into_diagnostic()?,
+        None => out.write_all(&bytes).into_diagnostic()?,
+    }
+    Ok(AlertExitCode::NoAlert)
+}
@@ -0,0 +1,137 @@
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+use miette::{IntoDiagnostic, Result};
+use ralertsinua_http::AlertsInUaApi;
+use ralertsinua_models::AirRaidAlertOblastStatuses;
+use std::sync::Arc;
+use tokio::{
+    sync::RwLock,
+    time::{interval, Duration, MissedTickBehavior},
+};
+use tracing::{debug, warn};
+
+use super::AlertExitCode;
+use crate::{
+    cli::ServeMetricsArgs,
+    config::Config,
+    metrics::{encode, MetricsSnapshot},
+};
+
+/// Lower bound of the polling interval, each poll makes two requests and the API
+/// allows about 8-10 requests per minute
+pub const MIN_METRICS_INTERVAL_SEC: u64 = 20;
+
+type SharedSnapshot = Arc<RwLock<MetricsSnapshot>>;
+
+async fn metrics_handler(State(snapshot): State<SharedSnapshot>) -> impl IntoResponse {
+    let body = encode(&*snapshot.read().await);
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        body,
+    )
+}
+
+/// Fetch statuses and active alerts, keep the previous data on failure
+async fn poll(api_client: &Arc<dyn AlertsInUaApi>, snapshot: &SharedSnapshot) {
+    let statuses = api_client.get_air_raid_alert_statuses_by_location().await;
+    let alerts = api_client.get_active_alerts().await;
+    let mut snapshot = snapshot.write().await;
+    match statuses {
+        // partial alerts are hidden on oblast level
+        Ok(statuses) => {
+            snapshot.statuses = Some(AirRaidAlertOblastStatuses::new(
+                statuses.raw_data().to_string(),
+                Some(false),
+            ))
+        }
+        Err(e) => warn!(target: "app", "failed to fetch statuses: {}", e),
+    }
+    match alerts {
+        Ok(alerts) => snapshot.alerts = Some(alerts),
+        Err(e) => warn!(target: "app", "failed to fetch active alerts: {}", e),
+    }
+    snapshot.stats = api_client.stats();
+    snapshot.connectivity = api_client.connectivity();
+    debug!(target: "app", "metrics updated, api is {}", snapshot.connectivity.state());
+}
+
+/// Serve metrics in the Prometheus text format on `/metrics` until interrupted with Ctrl-C
+pub async fn serve_metrics(
+    api_client: Arc<dyn AlertsInUaApi>,
+    args: &ServeMetricsArgs,
+    config: &Config,
+) -> Result<AlertExitCode> {
+    let seconds = args
+        .interval
+        .unwrap_or(*config.polling_interval())
+        .max(MIN_METRICS_INTERVAL_SEC);
+    let snapshot = SharedSnapshot::default();
+    let app = Router::new()
+        .route("/metrics", get(metrics_handler))
+        .with_state(snapshot.clone());
+    let listener = tokio::net::TcpListener::bind(&args.listen)
+        .await
+        .into_diagnostic()?;
+    eprintln!(
+        "serving metrics on http://{}/metrics, polling every {}s, press Ctrl-C to stop",
+        listener.local_addr().into_diagnostic()?,
+        seconds
+    );
+
+    let poller = tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(seconds));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            poll(&api_client, &snapshot).await;
+        }
+    });
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+        .into_diagnostic()?;
+    poller.abort();
+
+    Ok(AlertExitCode::NoAlert)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ralertsinua_http::AlertsInUaClient;
+
+    #[tokio::test]
+    async fn test_poll_updates_snapshot() {
+        let mut server = mockito::Server::new_async().await;
+        let _statuses = server
+            .mock("GET", "/v1/iot/active_air_raid_alerts_by_oblast.json")
+            .with_status(200)
+            .with_body("\"ANNAANNANNNPANANANNNNAANNNN\"")
+            .create_async()
+            .await;
+        let _alerts = server
+            .mock("GET", "/v1/alerts/active.json")
+            .with_status(500)
+            .create_async()
+            .await;
+        let api_client: Arc<dyn AlertsInUaApi> =
+            Arc::new(AlertsInUaClient::new(&server.url(), "token"));
+        let snapshot = SharedSnapshot::default();
+
+        poll(&api_client, &snapshot).await;
+
+        let snapshot = snapshot.read().await;
+        assert!(snapshot.statuses.is_some());
+        assert!(snapshot.alerts.is_none());
+        let text = encode(&snapshot);
+        assert!(text.contains(
+            r#"alertsinua_api_errors_total{endpoint="/alerts/active.json",kind="InternalServerError"} 1"#
+        ));
+        assert!(text.contains(r#"status="A"} 1"#));
+        assert!(text.contains(r#"status="P"} 1"#));
+    }
+}
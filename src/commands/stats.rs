@@ -0,0 +1,137 @@
+use miette::{IntoDiagnostic, Result};
+use ralertsinua_http::AlertsInUaApi;
+use ralertsinua_models::*;
+use std::{io::Write, sync::Arc, time::Instant};
+use tracing::debug;
+
+use super::{print_records, AlertExitCode, OutputFormat};
+use crate::{cli::StatsArgs, history::HistoryCache, utils::now_local};
+
+/// Print statistics of the alerts history of the locations, in local time. Histories are
+/// fetched within the rate limit of the endpoint.
+/// Exits with [`AlertExitCode::Active`] if some of the alerts is not finished yet
+pub async fn stats<W: Write>(
+    api_client: Arc<dyn AlertsInUaApi>,
+    args: &StatsArgs,
+    out: &mut W,
+) -> Result<AlertExitCode> {
+    let mut limit = HistoryCache::default();
+    let mut alerts = Vec::new();
+    for uid in &args.uids {
+        while let Err(wait) = limit.acquire(Instant::now()) {
+            debug!(target: "app", "alerts history of {} is delayed by {:?}", uid, wait);
+            tokio::time::sleep(wait).await;
+        }
+        let history = api_client
+            .get_alerts_history(uid, &args.period)
+            .await
+            .into_diagnostic()?;
+        alerts.extend(history.get_alerts());
+    }
+    let stats = AlertStats::new(&alerts, now_local());
+    print_stats(&stats, args.output.format, out)?;
+
+    let code = match alerts.iter().any(|a| a.finished_at.is_none()) {
+        true => AlertExitCode::Active,
+        false => AlertExitCode::NoAlert,
+    };
+    Ok(code)
+}
+
+/// Print the whole statistics as JSON, statistics of the locations as CSV, or tables of the
+/// locations and the co-occurrences with the distributions between them
+fn print_stats<W: Write>(
+    stats: &AlertStats,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<()> {
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, stats).into_diagnostic()?;
+            writeln!(out).into_diagnostic()?;
+        }
+        OutputFormat::Csv => print_records(&stats.locations, format, out)?,
+        OutputFormat::Table => {
+            print_records(&stats.locations, format, out)?;
+            let join = |counts: &[usize]| {
+                counts
+                    .iter()
+                    .map(usize::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            writeln!(out).into_diagnostic()?;
+            writeln!(out, "BY HOUR (LOCAL, 0-23) {}", join(&stats.by_hour))
+                .into_diagnostic()?;
+            writeln!(out, "BY WEEKDAY (MON-SUN)  {}", join(&stats.by_weekday))
+                .into_diagnostic()?;
+            writeln!(out).into_diagnostic()?;
+            print_records(&stats.co_occurrences, format, out)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn stats() -> AlertStats {
+        AlertStats {
+            count: 1,
+            total_seconds: 3600,
+            locations: vec![LocationStats {
+                location_uid: 31,
+                location_title: "м. Київ".to_string(),
+                count: 1,
+                total_seconds: 3600,
+                average_seconds: 3600,
+                longest_seconds: 3600,
+                shortest_seconds: 3600,
+            }],
+            by_hour: [0; 24],
+            by_weekday: [1, 0, 0, 0, 0, 0, 0],
+            co_occurrences: vec![CoOccurrence {
+                oblast_uid: 14,
+                oblast_title: "Київська область".to_string(),
+                other_oblast_uid: 31,
+                other_oblast_title: "м. Київ".to_string(),
+                count: 1,
+                overlap_seconds: 1800,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_print_stats_table() {
+        let mut out = Vec::new();
+        print_stats(&stats(), OutputFormat::Table, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "UID  TITLE    COUNT  TOTAL_SECONDS  AVERAGE_SECONDS  LONGEST_SECONDS  SHORTEST_SECONDS");
+        assert_eq!(
+            lines[3],
+            "BY HOUR (LOCAL, 0-23) 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0"
+        );
+        assert_eq!(lines[4], "BY WEEKDAY (MON-SUN)  1 0 0 0 0 0 0");
+        assert!(lines[6].starts_with("OBLAST_UID  OBLAST_TITLE"));
+        let cells: Vec<&str> = lines[7]
+            .split("  ")
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .collect();
+        assert_eq!(
+            cells,
+            vec!["14", "Київська область", "31", "м. Київ", "1", "1800"]
+        );
+    }
+
+    #[test]
+    fn test_print_stats_json() {
+        let mut out = Vec::new();
+        print_stats(&stats(), OutputFormat::Json, &mut out).unwrap();
+        let parsed: AlertStats = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed, stats());
+    }
+}
@@ -0,0 +1,65 @@
+use miette::{IntoDiagnostic, Result};
+use ralertsinua_http::AlertsInUaApi;
+use ralertsinua_models::AirRaidAlertOblastStatuses;
+use std::{io::Write, sync::Arc};
+
+use super::{print_records, AlertExitCode};
+use crate::cli::StatusArgs;
+
+/// Print air raid alert status of every oblast.
+/// Exits with the most severe status among the oblasts
+pub async fn status<W: Write>(
+    api_client: Arc<dyn AlertsInUaApi>,
+    args: &StatusArgs,
+    out: &mut W,
+) -> Result<AlertExitCode> {
+    let response = api_client
+        .get_air_raid_alert_statuses_by_location()
+        .await
+        .into_diagnostic()?;
+    // partial alerts are hidden on oblast level
+    let statuses =
+        AirRaidAlertOblastStatuses::new(response.raw_data().to_string(), Some(false));
+    print_records(statuses.get_all(), args.output.format, out)?;
+
+    let code = statuses
+        .iter()
+        .map(|s| AlertExitCode::from(s.status()))
+        .max()
+        .unwrap_or_default();
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cli::OutputArgs, commands::OutputFormat};
+    use mockito::Server as MockServer;
+    use ralertsinua_http::AlertsInUaClient;
+
+    #[tokio::test]
+    async fn test_status_exit_code() -> Result<()> {
+        let mut server = MockServer::new_async().await;
+        let api_client = Arc::new(AlertsInUaClient::new(server.url().as_str(), "token"));
+        server
+            .mock("GET", "/v1/iot/active_air_raid_alerts_by_oblast.json")
+            .with_header("Last-Modified", "Tue, 14 May 2024 18:18:18 GMT")
+            .with_body(r#""NNNNNNNNNNNPNNNNNNNNNNNNNNN""#)
+            .create_async()
+            .await;
+        let args = StatusArgs {
+            output: OutputArgs {
+                format: OutputFormat::Csv,
+            },
+        };
+        let mut out = Vec::new();
+
+        let code = status(api_client, &args, &mut out).await?;
+
+        assert_eq!(code, AlertExitCode::Partial);
+        let out = String::from_utf8(out).into_diagnostic()?;
+        assert_eq!(out.lines().count(), 28);
+        assert!(out.contains(",P"));
+        Ok(())
+    }
+}
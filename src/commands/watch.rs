@@ -0,0 +1,123 @@
+use miette::{IntoDiagnostic, Result};
+use ralertsinua_http::AlertsInUaApi;
+use ralertsinua_models::*;
+use serde::Serialize;
+use std::{io::Write, sync::Arc};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tokio::time::Duration;
+
+use super::{AlertExitCode, OutputFormat, Tabular};
+use crate::{
+    cli::WatchArgs,
+    config::Config,
+    hooks::{HookEvent, HookRunner},
+    poller::StatusPoller,
+};
+
+/// Status change with the time it was observed at
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchEvent {
+    #[serde(with = "time::serde::rfc3339")]
+    pub observed_at: OffsetDateTime,
+    #[serde(flatten)]
+    pub change: AirRaidAlertOblastStatusChange,
+}
+
+impl Tabular for WatchEvent {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "observed_at",
+            "uid",
+            "title",
+            "title_en",
+            "previous",
+            "current",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        let mut row = vec![self.observed_at.format(&Rfc3339).unwrap_or_default()];
+        row.extend(self.change.row());
+        row
+    }
+}
+
+/// Print one event per line, so the output can be consumed while streaming
+fn print_event<W: Write>(
+    event: &WatchEvent,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<()> {
+    match format {
+        OutputFormat::Table => {
+            let c = &event.change;
+            writeln!(
+                out,
+                "{}  {}  {}",
+                event.observed_at.format(&Rfc3339).unwrap_or_default(),
+                c.location_title_en(),
+                format_args!("{} -> {}", c.previous(), c.current()),
+            )
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer(&mut *out, event).into_diagnostic()?;
+            writeln!(out)
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new().from_writer(&mut *out);
+            writer.write_record(event.row()).into_diagnostic()?;
+            writer.flush()
+        }
+    }
+    .into_diagnostic()?;
+    out.flush().into_diagnostic()
+}
+
+/// Watch for air raid alert status changes until interrupted with Ctrl-C.
+/// Exits with the most severe status seen on the last poll
+pub async fn watch<W: Write>(
+    api_client: Arc<dyn AlertsInUaApi>,
+    args: &WatchArgs,
+    config: &Config,
+    out: &mut W,
+) -> Result<AlertExitCode> {
+    let seconds = args.interval.unwrap_or(*config.polling_interval());
+    let format = args.output.format;
+    let mut poller = StatusPoller::new(api_client, Duration::from_secs(seconds));
+    let hooks = config.hooks().clone().map(HookRunner::new);
+    eprintln!(
+        "watching air raid alert statuses every {}s, press Ctrl-C to stop",
+        seconds
+    );
+    if format == OutputFormat::Csv {
+        let mut writer = csv::WriterBuilder::new().from_writer(&mut *out);
+        writer
+            .write_record(WatchEvent::headers())
+            .into_diagnostic()?;
+        writer.flush().into_diagnostic()?;
+    }
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            result = poller.next() => match result {
+                Ok(changes) => {
+                    let observed_at = OffsetDateTime::now_utc();
+                    for change in changes {
+                        if let (Some(hooks), Some(event)) = (&hooks, HookEvent::from_change(&change, None)) {
+                            hooks.run(event);
+                        }
+                        print_event(&WatchEvent { observed_at, change }, format, out)?;
+                    }
+                }
+                Err(e) => eprintln!("failed to fetch statuses: {}", e),
+            },
+        }
+    }
+
+    let code = poller
+        .current()
+        .and_then(|s| s.iter().map(|s| AlertExitCode::from(s.status())).max())
+        .unwrap_or(AlertExitCode::Unknown);
+    Ok(code)
+}
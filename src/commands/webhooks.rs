@@ -0,0 +1,65 @@
+use miette::{miette, Result};
+use ralertsinua_http::AlertsInUaApi;
+use std::sync::Arc;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+use tracing::warn;
+
+use super::AlertExitCode;
+use crate::{
+    cli::WebhooksArgs,
+    config::Config,
+    utils::{get_config_dir, get_data_dir},
+    webhooks::{poll_once, Notifier, WebhooksConfig, WebhooksState},
+};
+
+/// Delay before the first retry of a failed webhook request
+const WEBHOOK_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Post alert transitions to the configured webhooks until interrupted with Ctrl-C
+pub async fn webhooks(
+    api_client: Arc<dyn AlertsInUaApi>,
+    args: &WebhooksArgs,
+    config: &Config,
+) -> Result<AlertExitCode> {
+    let config_path = args
+        .config
+        .clone()
+        .unwrap_or_else(|| get_config_dir().join("webhooks.toml"));
+    let webhooks_config = WebhooksConfig::load(&config_path)?;
+    if webhooks_config.targets.is_empty() {
+        return Err(miette!(
+            help = "add [[targets]] tables with 'name' and 'url'",
+            "no webhook targets in {}",
+            config_path.display()
+        ));
+    }
+    let state_path = get_data_dir().join("webhooks_state.json");
+    let mut state = WebhooksState::load(&state_path)?;
+    let notifier = Notifier::new(webhooks_config.targets, WEBHOOK_BACKOFF, args.dry_run);
+
+    let seconds = args.interval.unwrap_or(*config.polling_interval());
+    let mut interval = interval(Duration::from_secs(seconds));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    eprintln!(
+        "posting alert transitions to webhooks from {}, polling every {}s{}, press Ctrl-C to stop",
+        config_path.display(),
+        seconds,
+        if args.dry_run { ", dry run" } else { "" }
+    );
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = interval.tick() => {
+                match poll_once(api_client.as_ref(), &notifier, &mut state).await {
+                    // dry run must not affect the state of the real daemon
+                    Ok(_) if !args.dry_run => state.save(&state_path)?,
+                    Ok(_) => {}
+                    Err(e) => warn!(target: "app", "failed to poll: {}", e),
+                }
+            }
+        }
+    }
+
+    Ok(AlertExitCode::NoAlert)
+}
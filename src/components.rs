@@ -13,17 +13,29 @@ use crate::{
     utils::type_of,
 };
 
+pub mod alerts;
+pub mod banner;
 pub mod fps;
 pub mod header;
+pub mod help;
+pub mod history;
 pub mod list;
 pub mod logger;
 pub mod map;
+pub mod report;
+pub mod statistics;
 
+pub use alerts::*;
+pub use banner::*;
 pub use fps::*;
 pub use header::*;
+pub use help::*;
+pub use history::*;
 pub use list::*;
 pub use logger::*;
 pub use map::*;
+pub use report::*;
+pub use statistics::*;
 
 pub type Result<T> = miette::Result<T, AppError>;
 
@@ -45,6 +57,10 @@ pub trait WithPlacement<'a> {
             None => true,
         }
     }
+    /// Check if the component is a modal popup, the only one to receive events while open
+    fn is_modal(&self) -> bool {
+        false
+    }
     /// Debug self message
     fn debug(&self) {
         debug!(target:"app", "initializing component {}", type_of(self));
@@ -105,7 +121,7 @@ pub trait Component<'a>: WithPlacement<'a> {
     fn handle_events(&mut self, event: Option<Event>) -> Result<Option<Action>> {
         let r = match event {
             Some(Event::Key(key_event)) => self.handle_key_events(key_event)?,
-            // Some(Event::Mouse(mvent)) => self.handle_mouse_events(mouse_event)?,
+            Some(Event::Mouse(mouse_event)) => self.handle_mouse_events(mouse_event)?,
             _ => None,
         };
         Ok(r)
@@ -0,0 +1,583 @@
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use ralertsinua_geo::*;
+use ralertsinua_models::*;
+use ratatui::{
+    prelude::*,
+    widgets::{
+        block::{Position, Title},
+        Block, Cell, Row, Table, TableState,
+    },
+};
+use rust_i18n::t;
+use std::{cmp::Ordering, collections::HashMap};
+use strum::{Display, EnumCount, FromRepr};
+use time::OffsetDateTime;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame, Result, WithPlacement};
+use crate::{action::Action, config::*, layout::*, tui_helpers::*};
+
+/// Alert types to cycle the filter through, after them the filter is off
+const ALERT_TYPES: [AlertType; 5] = [
+    AlertType::AirRaid,
+    AlertType::ArtilleryShelling,
+    AlertType::UrbanFights,
+    AlertType::Nuclear,
+    AlertType::Chemical,
+];
+
+/// Column of the table, also the key the rows are sorted by
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Display, FromRepr, EnumCount)]
+pub enum AlertsColumn {
+    Location,
+    LocationType,
+    AlertType,
+    #[default]
+    StartedAt,
+    Duration,
+    Notes,
+}
+
+impl AlertsColumn {
+    /// The next column, after the last one the first
+    pub fn next(self) -> Self {
+        Self::from_repr((self as usize + 1) % Self::COUNT).unwrap_or_default()
+    }
+
+    /// Order of the alerts by the column, the newest first on ties
+    pub fn compare(self, a: &Alert, b: &Alert) -> Ordering {
+        let ordering = match self {
+            Self::Location => a.location_title.cmp(&b.location_title),
+            Self::LocationType => a
+                .location_type
+                .to_string()
+                .cmp(&b.location_type.to_string()),
+            Self::AlertType => a.alert_type.to_string().cmp(&b.alert_type.to_string()),
+            Self::StartedAt => a.started_at.cmp(&b.started_at),
+            Self::Duration => b.started_at.cmp(&a.started_at),
+            Self::Notes => a.notes.cmp(&b.notes),
+        };
+        ordering.then_with(|| b.started_at.cmp(&a.started_at))
+    }
+}
+
+/// Conditions the listed alerts have to meet, empty ones match any alert
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AlertsFilter {
+    pub alert_type: Option<AlertType>,
+    pub oblast_uid: Option<i32>,
+    /// Case insensitive part of the location, the oblast or the notes
+    pub text: String,
+}
+
+impl AlertsFilter {
+    pub fn matches(&self, alert: &Alert) -> bool {
+        let text = self.text.to_lowercase();
+        self.alert_type
+            .as_ref()
+            .is_none_or(|t| *t == alert.alert_type)
+            && self
+                .oblast_uid
+                .is_none_or(|uid| uid == alert.location_oblast_uid)
+            && (text.is_empty()
+                || [
+                    alert.location_title.as_str(),
+                    alert.location_oblast.as_str(),
+                    alert.notes.as_deref().unwrap_or_default(),
+                ]
+                .iter()
+                .any(|s| s.to_lowercase().contains(&text)))
+    }
+
+    /// Next alert type in `ALERT_TYPES`, none after the last one
+    pub fn next_alert_type(&mut self) {
+        let next = match &self.alert_type {
+            None => 0,
+            Some(t) => ALERT_TYPES.iter().position(|a| a == t).map_or(0, |i| i + 1),
+        };
+        self.alert_type = ALERT_TYPES.get(next).cloned();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Alerts which match the filter, sorted by the column
+pub fn filter_and_sort(
+    alerts: &Alerts,
+    filter: &AlertsFilter,
+    column: AlertsColumn,
+    descending: bool,
+) -> Vec<Alert> {
+    let mut rows: Vec<Alert> = alerts
+        .iter()
+        .filter(|a| filter.matches(a))
+        .cloned()
+        .collect();
+    rows.sort_by(|a, b| {
+        let ordering = column.compare(a, b);
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+    rows
+}
+
+/// Time since the start of the alert, zero if the clocks disagree
+fn format_duration(alert: &Alert, now: OffsetDateTime) -> String {
+    format_seconds((now - alert.started_at).whole_seconds())
+}
+
+fn format_started_at(alert: &Alert) -> String {
+    let t = alert.started_at.to_offset(time::UtcOffset::UTC);
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}",
+        t.year(),
+        t.month() as u8,
+        t.day(),
+        t.hour(),
+        t.minute()
+    )
+}
+
+/// Table of every active alert, sorted and filtered by the user
+#[derive(Debug)]
+pub struct AlertsTable {
+    command_tx: Option<UnboundedSender<Action>>,
+    placement: LayoutPoint,
+    config: Config,
+    alerts: Alerts,
+    /// English names of the locations on the map, by uid
+    names_en: HashMap<i32, String>,
+    /// Uids of the locations on the map, to show alerts of smaller ones on their oblast
+    map_uids: Vec<i32>,
+    rows: Vec<Alert>,
+    column: AlertsColumn,
+    descending: bool,
+    filter: AlertsFilter,
+    /// Text filter is being typed
+    searching: bool,
+    state: TableState,
+    selected_tab: LayoutTab,
+    /// Area drawn last, to find the row under the mouse
+    area: Rect,
+}
+
+impl AlertsTable {
+    pub fn new() -> Self {
+        Self {
+            command_tx: None,
+            placement: LayoutPoint(LayoutArea::Inner, Some(LayoutTab::Tab2)),
+            config: Config::default(),
+            alerts: Alerts::default(),
+            names_en: HashMap::new(),
+            map_uids: Vec::new(),
+            rows: Vec::new(),
+            column: AlertsColumn::default(),
+            descending: true,
+            filter: AlertsFilter::default(),
+            searching: false,
+            state: TableState::default(),
+            selected_tab: LayoutTab::default(),
+            area: Rect::default(),
+        }
+    }
+
+    /// Apply the filter and the order, keeping the selected alert if it is still listed
+    fn refresh_rows(&mut self) {
+        let selected_id = self.selected().map(|a| a.id);
+        self.rows =
+            filter_and_sort(&self.alerts, &self.filter, self.column, self.descending);
+        let index = selected_id
+            .and_then(|id| self.rows.iter().position(|a| a.id == id))
+            .or_else(|| (!self.rows.is_empty()).then_some(0));
+        self.state.select(index);
+    }
+
+    pub fn selected(&self) -> Option<&Alert> {
+        self.state.selected().and_then(|i| self.rows.get(i))
+    }
+
+    fn select(&mut self, index: impl FnOnce(usize, usize) -> usize) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let current = self.state.selected().unwrap_or(0);
+        self.state.select(Some(index(current, self.rows.len())));
+    }
+
+    /// Uid of the location on the map the selected alert is in
+    fn selected_map_uid(&self) -> Option<usize> {
+        let alert = self.selected()?;
+        let uid = if self.map_uids.contains(&alert.location_uid) {
+            alert.location_uid
+        } else {
+            alert.location_oblast_uid
+        };
+        Some(uid as usize)
+    }
+
+    /// Index of the row in the row of the terminal, if any
+    fn index_at(&self, column: u16, row: u16) -> Option<usize> {
+        let (_, row) = get_inner_position(self.area, column, row)?;
+        // the first row is the header
+        let index = self.state.offset() + usize::from(row.checked_sub(1)?);
+        (index < self.rows.len()).then_some(index)
+    }
+
+    fn location_name<'b>(&'b self, alert: &'b Alert) -> &'b str {
+        let locale = self.config.get_locale();
+        match self.names_en.get(&alert.location_uid) {
+            Some(name) if locale.as_str() != "uk" => name,
+            _ => &alert.location_title,
+        }
+    }
+
+    fn title(&self) -> Line<'static> {
+        let direction = if self.descending { "▼" } else { "▲" };
+        let mut parts = vec![
+            format!(
+                "{} {}/{}",
+                t!("views.Alerts.title"),
+                self.rows.len(),
+                self.alerts.len()
+            ),
+            format!(
+                "{} {}",
+                t!(&format!("views.Alerts.columns.{}", self.column)),
+                direction
+            ),
+        ];
+        if let Some(alert_type) = &self.filter.alert_type {
+            parts.push(t!(&format!("alert_types.{}", alert_type)).to_string());
+        }
+        if let Some(oblast) = self
+            .filter
+            .oblast_uid
+            .and_then(|uid| self.alerts.iter().find(|a| a.location_oblast_uid == uid))
+        {
+            parts.push(oblast.location_oblast.clone());
+        }
+        Line::from(parts.join(" · ")).fg(self.config.theme().text)
+    }
+
+    fn row(&self, alert: &Alert, now: OffsetDateTime) -> Row<'static> {
+        let theme = self.config.theme();
+        let color = if alert.alert_type == AlertType::AirRaid {
+            theme.alert
+        } else {
+            theme.partial
+        };
+        Row::new(vec![
+            Cell::from(self.location_name(alert).to_string()),
+            Cell::from(t!(&format!("location_types.{}", alert.location_type)).to_string()),
+            Cell::from(t!(&format!("alert_types.{}", alert.alert_type)).to_string())
+                .fg(color),
+            Cell::from(format_started_at(alert)),
+            Cell::from(format_duration(alert, now)),
+            Cell::from(alert.notes.clone().unwrap_or_default()),
+        ])
+    }
+}
+
+impl WithPlacement<'_> for AlertsTable {
+    fn placement(&self) -> &LayoutPoint {
+        &self.placement
+    }
+}
+
+impl Component<'_> for AlertsTable {
+    fn init(&mut self, _area: Rect) -> Result<()> {
+        self.debug();
+        Ok(())
+    }
+
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    /// Typing the text filter, the keys bound in `Mode::Search` end it
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if !self.searching {
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.filter.text.push(c)
+            }
+            KeyCode::Backspace => {
+                self.filter.text.pop();
+            }
+            _ => return Ok(None),
+        }
+        self.refresh_rows();
+        Ok(None)
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        if get_inner_position(self.area, mouse.column, mouse.row).is_none() {
+            return Ok(None);
+        }
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(i) = self.index_at(mouse.column, mouse.row) {
+                    self.state.select(Some(i));
+                }
+            }
+            MouseEventKind::ScrollDown => self.select(|i, len| (i + 1).min(len - 1)),
+            MouseEventKind::ScrollUp => self.select(|i, _| i.saturating_sub(1)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SelectTab(tab) => {
+                self.selected_tab = LayoutTab::from_repr(tab).unwrap_or_default();
+            }
+            Action::GetActiveAlerts(alerts) => {
+                self.alerts = alerts;
+                self.refresh_rows();
+            }
+            Action::GetLocations(locations) => {
+                self.map_uids = locations.iter().map(|l| l.location_uid).collect();
+                self.names_en = locations
+                    .iter()
+                    .map(|l| (l.location_uid, l.name_en().to_string()))
+                    .collect();
+            }
+            Action::Search => self.searching = true,
+            Action::SearchDone => self.searching = false,
+            Action::Cancel if self.searching => {
+                self.searching = false;
+                self.filter.text.clear();
+                self.refresh_rows();
+            }
+            _ if !self.is_visible(&self.selected_tab) => {}
+            Action::SelectNext => self.select(|i, len| (i + 1) % len),
+            Action::SelectPrevious => self.select(|i, len| (i + len - 1) % len),
+            Action::SelectFirst => self.select(|_, _| 0),
+            Action::SelectLast => self.select(|_, len| len - 1),
+            Action::SortNext => {
+                self.column = self.column.next();
+                self.refresh_rows();
+            }
+            Action::SortReverse => {
+                self.descending = !self.descending;
+                self.refresh_rows();
+            }
+            Action::FilterAlertType => {
+                self.filter.next_alert_type();
+                self.refresh_rows();
+            }
+            Action::FilterOblast => {
+                self.filter.oblast_uid = match self.filter.oblast_uid {
+                    Some(_) => None,
+                    None => self.selected().map(|a| a.location_oblast_uid),
+                };
+                self.refresh_rows();
+            }
+            Action::Cancel => {
+                self.filter = AlertsFilter::default();
+                self.refresh_rows();
+            }
+            Action::ShowOnMap => {
+                if let (Some(uid), Some(tx)) = (self.selected_map_uid(), &self.command_tx) {
+                    tx.send(Action::SelectLocationByUid(Some(uid)))?;
+                    tx.send(Action::FocusSelected)?;
+                    return Ok(Some(Action::SelectTab(LayoutTab::Tab1 as usize)));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>) -> Result<()> {
+        let area = self.get_area(f.size())?;
+        self.area = area;
+        let now = OffsetDateTime::now_utc();
+        let header = Row::new(
+            [
+                AlertsColumn::Location,
+                AlertsColumn::LocationType,
+                AlertsColumn::AlertType,
+                AlertsColumn::StartedAt,
+                AlertsColumn::Duration,
+                AlertsColumn::Notes,
+            ]
+            .map(|c| {
+                let cell =
+                    Cell::from(t!(&format!("views.Alerts.columns.{}", c)).to_string());
+                if c == self.column {
+                    cell.add_modifier(Modifier::UNDERLINED)
+                } else {
+                    cell
+                }
+            }),
+        )
+        .add_modifier(Modifier::BOLD);
+        let rows: Vec<Row> = self.rows.iter().map(|a| self.row(a, now)).collect();
+        let mut block = Block::bordered().title(self.title());
+        if self.searching || !self.filter.text.is_empty() {
+            let cursor = if self.searching { "_" } else { "" };
+            block = block.title(
+                Title::from(format!("/{}{}", self.filter.text, cursor))
+                    .position(Position::Bottom),
+            );
+        }
+        let widths = [
+            Constraint::Fill(2),
+            Constraint::Length(10),
+            Constraint::Length(20),
+            Constraint::Length(16),
+            Constraint::Length(12),
+            Constraint::Fill(1),
+        ];
+        let widget = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .fg(self.config.theme().selected),
+            )
+            .highlight_symbol(">>");
+
+        f.render_stateful_widget(widget, area, &mut self.state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ralertsinua_models::test_util::{at, AlertBuilder};
+
+    /// Alerts start a minute apart from 10:00
+    fn alert(id: i32, title: &str, oblast_uid: i32, alert_type: AlertType) -> Alert {
+        let alert = AlertBuilder::new(id)
+            .location(oblast_uid, oblast_uid)
+            .title(title)
+            .alert_type(alert_type)
+            .started_at(at(10) + time::Duration::minutes(id.into()));
+        match id {
+            2 => alert.notes("Згідно інформації").build(),
+            _ => alert.build(),
+        }
+    }
+
+    fn alerts() -> Alerts {
+        Alerts::new(vec![
+            alert(1, "Харківська область", 22, AlertType::AirRaid),
+            alert(2, "Сумська область", 20, AlertType::ArtilleryShelling),
+            alert(3, "Київська область", 14, AlertType::AirRaid),
+        ])
+    }
+
+    fn ids(rows: &[Alert]) -> Vec<i32> {
+        rows.iter().map(|a| a.id).collect()
+    }
+
+    #[test]
+    fn test_sort() {
+        let alerts = alerts();
+        let filter = AlertsFilter::default();
+        let rows = filter_and_sort(&alerts, &filter, AlertsColumn::StartedAt, true);
+        assert_eq!(ids(&rows), vec![3, 2, 1]);
+        let rows = filter_and_sort(&alerts, &filter, AlertsColumn::Duration, true);
+        assert_eq!(ids(&rows), vec![1, 2, 3]);
+        let rows = filter_and_sort(&alerts, &filter, AlertsColumn::Location, false);
+        assert_eq!(ids(&rows), vec![3, 2, 1]);
+        // alerts without notes go first, ties are the newest first
+        let rows = filter_and_sort(&alerts, &filter, AlertsColumn::Notes, false);
+        assert_eq!(ids(&rows), vec![3, 1, 2]);
+        assert_eq!(AlertsColumn::Notes.next(), AlertsColumn::Location);
+    }
+
+    #[test]
+    fn test_filter() {
+        let alerts = alerts();
+        let sorted = |filter: &AlertsFilter| {
+            ids(&filter_and_sort(
+                &alerts,
+                filter,
+                AlertsColumn::Location,
+                false,
+            ))
+        };
+        let mut filter = AlertsFilter::default();
+        filter.next_alert_type();
+        assert_eq!(filter.alert_type, Some(AlertType::AirRaid));
+        assert_eq!(sorted(&filter), vec![3, 1]);
+        filter.oblast_uid = Some(22);
+        assert_eq!(sorted(&filter), vec![1]);
+
+        let mut filter = AlertsFilter {
+            text: "ЗГІДНО".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(sorted(&filter), vec![2]);
+        filter.text = "ська".to_string();
+        assert_eq!(sorted(&filter), vec![3, 2, 1]);
+
+        let mut filter = AlertsFilter {
+            alert_type: Some(AlertType::Chemical),
+            ..Default::default()
+        };
+        filter.next_alert_type();
+        assert!(filter.is_empty());
+    }
+
+    #[test]
+    fn test_table_actions() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut table = AlertsTable::new();
+        table.register_action_handler(tx).unwrap();
+        table.update(Action::GetActiveAlerts(alerts())).unwrap();
+        assert_eq!(table.selected().map(|a| a.id), Some(3));
+
+        // hidden table ignores the navigation of the list
+        table.update(Action::SelectNext).unwrap();
+        assert_eq!(table.selected().map(|a| a.id), Some(3));
+
+        table
+            .update(Action::SelectTab(LayoutTab::Tab2 as usize))
+            .unwrap();
+        table.update(Action::SelectNext).unwrap();
+        assert_eq!(table.selected().map(|a| a.id), Some(2));
+        // selection follows the alert when the order changes
+        table.update(Action::SortReverse).unwrap();
+        assert_eq!(table.selected().map(|a| a.id), Some(2));
+        assert_eq!(ids(&table.rows), vec![1, 2, 3]);
+
+        table.update(Action::Search).unwrap();
+        for c in "київ".chars() {
+            let key = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+            table.handle_key_events(key).unwrap();
+        }
+        assert_eq!(ids(&table.rows), vec![3]);
+        table.update(Action::Cancel).unwrap();
+        assert_eq!(table.rows.len(), 3);
+
+        let action = table.update(Action::ShowOnMap).unwrap();
+        assert_eq!(action, Some(Action::SelectTab(LayoutTab::Tab1 as usize)));
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            Action::SelectLocationByUid(Some(14))
+        );
+    }
+}
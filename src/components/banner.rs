@@ -0,0 +1,115 @@
+use ratatui::{prelude::*, widgets::*};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Result, WithPlacement};
+use crate::{action::Action, config::*, layout::*, tui::Frame};
+
+/// How long the banner stays on screen
+const BANNER_TIMEOUT: Duration = Duration::from_secs(15);
+/// Maximum number of messages shown at once, older are dropped
+const BANNER_MAX_MESSAGES: usize = 3;
+
+/// Transient banner on top of the screen with notifications, dismissed with `Action::Cancel`
+#[derive(Debug)]
+pub struct Banner {
+    command_tx: Option<UnboundedSender<Action>>,
+    placement: LayoutPoint,
+    config: Config,
+    messages: Vec<(String, Instant)>,
+}
+
+impl Banner {
+    pub fn new() -> Self {
+        Self {
+            command_tx: Option::default(),
+            placement: LayoutPoint(LayoutArea::Header, None),
+            config: Config::default(),
+            messages: Vec::new(),
+        }
+    }
+
+    pub fn messages(&self) -> impl Iterator<Item = &str> {
+        self.messages.iter().map(|(m, _)| m.as_str())
+    }
+
+    fn push(&mut self, message: String) {
+        self.messages.push((message, Instant::now()));
+        let overflow = self.messages.len().saturating_sub(BANNER_MAX_MESSAGES);
+        self.messages.drain(..overflow);
+    }
+
+    fn expire(&mut self) {
+        self.messages
+            .retain(|(_, shown_at)| shown_at.elapsed() < BANNER_TIMEOUT);
+    }
+}
+
+impl WithPlacement<'_> for Banner {
+    fn placement(&self) -> &LayoutPoint {
+        &self.placement
+    }
+}
+
+impl Component<'_> for Banner {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick => self.expire(),
+            Action::Banner(message) => self.push(message),
+            Action::Cancel => self.messages.clear(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>) -> Result<()> {
+        if self.messages.is_empty() {
+            return Ok(());
+        }
+        let size = f.size();
+        let height = (self.messages.len() as u16 + 2).min(size.height);
+        let width = size.width.saturating_sub(4).min(80);
+        let area = Rect::new((size.width - width) / 2, 1.min(size.height), width, height)
+            .intersection(size);
+        let theme = self.config.theme();
+        let lines: Vec<Line> = self.messages().map(Line::from).collect();
+        let widget = Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(theme.banner_fg).bg(theme.banner_bg))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title_bottom(Line::from("Esc").right_aligned()),
+            );
+        f.render_widget(Clear, area);
+        f.render_widget(widget, area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_banner_keeps_latest_messages() {
+        let mut banner = Banner::new();
+        for i in 0..5 {
+            banner.update(Action::Banner(i.to_string())).unwrap();
+        }
+        assert_eq!(banner.messages().collect::<Vec<_>>(), vec!["2", "3", "4"]);
+
+        banner.update(Action::Cancel).unwrap();
+        assert_eq!(banner.messages().count(), 0);
+    }
+}
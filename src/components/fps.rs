@@ -1,4 +1,6 @@
+use ralertsinua_http::Connectivity;
 use ratatui::{layout::Offset, prelude::*, widgets::*};
+use rust_i18n::t;
 use std::time::Instant;
 use throbber_widgets_tui::{Throbber, ThrobberState, WhichUse, BRAILLE_SIX_DOUBLE};
 use tokio::sync::mpsc::UnboundedSender;
@@ -24,6 +26,7 @@ pub struct FpsCounter<'a> {
     throbber_state: ThrobberState,
     #[allow(unused)]
     config: Config,
+    connectivity: Connectivity,
 }
 
 impl<'a> FpsCounter<'a> {
@@ -41,9 +44,19 @@ impl<'a> FpsCounter<'a> {
             title: Line::default(),
             throbber_state: ThrobberState::default(),
             config: Config::default(),
+            connectivity: Connectivity::default(),
         }
     }
 
+    fn refresh_title(&mut self) {
+        self.title = get_title_with_online_status(
+            t!("views.Fps.status"),
+            &self.connectivity,
+            self.config.theme(),
+        )
+        .alignment(Alignment::Left);
+    }
+
     fn app_tick(&mut self) -> Result<()> {
         self.app_frames += 1;
         let now = Instant::now();
@@ -102,14 +115,15 @@ impl<'a> Component<'a> for FpsCounter<'a> {
         match action {
             Action::Tick => {
                 self.app_tick()?;
+                self.refresh_title();
             }
             Action::Render => {
                 self.render_tick()?;
             }
             Action::Refresh => {}
-            Action::Online(online) => {
-                self.title = get_title_with_online_status("Satus", self.config.online())
-                    .alignment(Alignment::Left);
+            Action::Online(connectivity) => {
+                self.connectivity = connectivity;
+                self.refresh_title();
             }
             _ => {}
         }
@@ -138,7 +152,7 @@ impl<'a> Component<'a> for FpsCounter<'a> {
         let throb = Throbber::default()
             .throbber_style(
                 Style::default()
-                    .fg(Color::DarkGray)
+                    .fg(self.config.theme().muted)
                     .add_modifier(Modifier::BOLD),
             )
             .throbber_set(BRAILLE_SIX_DOUBLE)
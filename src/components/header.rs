@@ -7,7 +7,7 @@ use ratatui::{
 use tokio::sync::mpsc::UnboundedSender;
 
 use super::{Component, Result, WithPlacement};
-use crate::{action::Action, config::*, constants::*, layout::*, tui::Frame};
+use crate::{action::Action, config::*, layout::*, tui::Frame};
 
 #[derive(Debug)]
 pub struct Header<'a> {
@@ -63,15 +63,16 @@ impl<'a> Component<'a> for Header<'a> {
 
     fn draw(&mut self, f: &mut Frame) -> Result<()> {
         let area = self.get_area(f.size())?;
-        let titles = LayoutTab::into_iter().map(LayoutTab::title);
+        let theme = self.config.theme();
+        let titles = LayoutTab::into_iter().map(|tab| tab.title(theme));
         let selected_tab_index = self.selected_tab as usize;
         let widget = Tabs::new(titles)
-            .style(Style::default().fg(*DEFAULT_COLOR))
+            .style(Style::default().fg(theme.text))
             .highlight_style(
                 Style::default()
                     .add_modifier(Modifier::BOLD)
                     .add_modifier(Modifier::UNDERLINED)
-                    .fg(*SELECTED_STYLE_FG),
+                    .fg(theme.selected),
             )
             .select(selected_tab_index)
             .padding("", "")
@@ -0,0 +1,153 @@
+use ratatui::{prelude::*, widgets::*};
+use rust_i18n::t;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Result, WithPlacement};
+use crate::{
+    action::Action, config::Config, keymap::Keymap, layout::*, mode::Mode, theme::Theme,
+    tui::Frame, tui_helpers::centered_rect,
+};
+
+/// Modal popup listing every keybinding, toggled with `Action::Help`
+#[derive(Debug)]
+pub struct Help {
+    command_tx: Option<UnboundedSender<Action>>,
+    placement: LayoutPoint,
+    keymap: Keymap,
+    theme: Theme,
+    visible: bool,
+}
+
+impl Help {
+    pub fn new() -> Self {
+        Self {
+            command_tx: Option::default(),
+            placement: LayoutPoint(LayoutArea::Hidden, None),
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            visible: false,
+        }
+    }
+
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Rows of mode headers and localized descriptions of the actions with their chords
+    pub fn rows(&self) -> Vec<(Option<String>, String)> {
+        let mut rows: Vec<(Option<String>, String)> = Vec::new();
+        let mut last: Option<(Mode, &Action)> = None;
+        for (mode, chord, action) in self.keymap.bindings() {
+            if mode == Mode::Help {
+                continue;
+            }
+            match last {
+                Some((m, a)) if m == mode && a == action => {
+                    if let Some((Some(chords), _)) = rows.last_mut() {
+                        chords.push_str(&format!(", {}", chord));
+                    }
+                }
+                _ => {
+                    if last.map(|(m, _)| m) != Some(mode) {
+                        rows.push((None, t!(&format!("help.modes.{}", mode)).to_string()));
+                    }
+                    let description = t!(&format!("help.actions.{}", action)).to_string();
+                    rows.push((Some(chord.to_string()), description));
+                }
+            }
+            last = Some((mode, action));
+        }
+        rows
+    }
+}
+
+impl WithPlacement<'_> for Help {
+    fn placement(&self) -> &LayoutPoint {
+        &self.placement
+    }
+
+    fn is_modal(&self) -> bool {
+        true
+    }
+}
+
+impl Component<'_> for Help {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.keymap = config.keymap().clone();
+        self.theme = config.theme().clone();
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if action == Action::Help {
+            self.visible = !self.visible;
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let area = centered_rect(f.size(), 60, 80);
+        let accent = self.theme.accent;
+        let rows = self
+            .rows()
+            .into_iter()
+            .map(|(chords, description)| match chords {
+                Some(chords) => Row::new(vec![Cell::from(chords), Cell::from(description)]),
+                None => Row::new(vec![Cell::from(description.bold().fg(accent))]),
+            });
+        let close = self
+            .keymap
+            .chords(Mode::Help, &Action::Help)
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let widget = Table::new(rows, [Constraint::Percentage(35), Constraint::Fill(1)])
+            .column_spacing(2)
+            .block(
+                Block::bordered()
+                    .title(t!("help.title").to_string().bold())
+                    .title_bottom(
+                        Line::from(t!("help.close", key = close).to_string())
+                            .right_aligned(),
+                    )
+                    .padding(Padding::horizontal(1)),
+            );
+        f.render_widget(Clear, area);
+        f.render_widget(widget, area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_help_rows() {
+        rust_i18n::set_locale("en");
+        let mut help = Help::new();
+        assert!(!help.visible());
+        help.update(Action::Help).unwrap();
+        assert!(help.visible());
+
+        let rows = help.rows();
+        assert_eq!(rows[0], (None, "Everywhere".to_string()));
+        assert!(rows.contains(&(Some("<Ctrl-c>, q".to_string()), "Quit".to_string())));
+        assert!(rows.contains(&(None, "Regions list".to_string())));
+        assert!(rows.contains(&(
+            Some("<Down>, j".to_string()),
+            "Select next region".to_string()
+        )));
+        // bindings of the help itself are in the bottom title
+        assert!(!rows.iter().any(|(_, d)| d == "Help"));
+    }
+}
@@ -0,0 +1,298 @@
+use ralertsinua_geo::*;
+use ralertsinua_models::*;
+use ratatui::{
+    layout::Constraint::*,
+    prelude::*,
+    widgets::{Block, Paragraph, Sparkline},
+};
+use rust_i18n::t;
+use std::collections::HashMap;
+use time::OffsetDateTime;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame, Result, WithPlacement};
+use crate::{
+    action::Action, config::*, history::*, layout::*, theme::Theme, tui_helpers::*,
+};
+
+/// Days the API returns the history for, the least shown
+const HISTORY_DAYS: usize = 31;
+/// Days of the stored history shown at most
+const STORED_HISTORY_DAYS: usize = 366;
+
+/// Timeline of the alerts of the selected location by day, loaded on demand
+#[derive(Debug)]
+pub struct History {
+    command_tx: Option<UnboundedSender<Action>>,
+    placement: LayoutPoint,
+    config: Config,
+    /// Names of the locations on the map in Ukrainian and English, by uid
+    names: HashMap<i32, (String, String)>,
+    /// Location selected on the home tab
+    location_uid: Option<i32>,
+    /// History shown, with the uid of its location
+    history: Option<(i32, Alerts)>,
+    loading: bool,
+    selected_tab: LayoutTab,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            command_tx: None,
+            placement: LayoutPoint(LayoutArea::Inner, Some(LayoutTab::Tab3)),
+            config: Config::default(),
+            names: HashMap::new(),
+            location_uid: None,
+            history: None,
+            loading: false,
+            selected_tab: LayoutTab::default(),
+        }
+    }
+
+    /// Ask for the history of the selected location unless it is shown already
+    fn load(&mut self, force: bool) -> Option<Action> {
+        let uid = self.location_uid?;
+        let shown = self.history.as_ref().is_some_and(|(u, _)| *u == uid);
+        if shown && !force {
+            return None;
+        }
+        self.loading = true;
+        Some(Action::FetchAlertsHistory(uid))
+    }
+
+    fn location_name(&self, uid: i32) -> String {
+        let locale = self.config.get_locale();
+        self.names
+            .get(&uid)
+            .map(|(name, name_en)| match locale.as_str() {
+                "uk" => name.clone(),
+                _ => name_en.clone(),
+            })
+            .unwrap_or_else(|| uid.to_string())
+    }
+
+    fn title(&self) -> String {
+        let mut title = t!("views.History.title").to_string();
+        if let Some(uid) = self.location_uid {
+            title = format!("{}: {}", title, self.location_name(uid));
+        }
+        if self.loading {
+            title = format!("{} · {}", title, t!("views.History.loading"));
+        }
+        title
+    }
+
+    /// Days since the oldest alert, stored alerts extend the history beyond the month of the API
+    fn days(alerts: &Alerts, now: OffsetDateTime) -> usize {
+        let oldest = alerts.iter().map(|a| a.started_at).min();
+        let days = oldest.map_or(0, |t| (now - t).whole_days() as usize + 1);
+        days.clamp(HISTORY_DAYS, STORED_HISTORY_DAYS)
+    }
+
+    /// Rows of days with the hours of alerts marked, under a ruler of hours
+    fn timeline(
+        summary: &HistorySummary,
+        width: usize,
+        height: usize,
+        theme: &Theme,
+    ) -> Text<'static> {
+        let color = theme.alert;
+        // "MM-DD " before and " NN" after the slots
+        let slots = width.saturating_sub(9).max(1);
+        let mut ruler = vec![' '; slots];
+        (0..24).step_by(6).for_each(|h| ruler[h * slots / 24] = '|');
+        let ruler: String = ruler.into_iter().collect();
+        let mut lines = vec![Line::from(format!("      {}", ruler)).dim()];
+        let days = summary
+            .days
+            .iter()
+            .rev()
+            .take(height.saturating_sub(1))
+            .rev();
+        lines.extend(days.map(|day| {
+            let slots: String = day
+                .slots(slots)
+                .into_iter()
+                .map(|alert| if alert { '█' } else { '·' })
+                .collect();
+            Line::from(vec![
+                Span::raw(format!(
+                    "{:02}-{:02} ",
+                    day.date.month() as u8,
+                    day.date.day()
+                )),
+                Span::styled(slots, Style::default().fg(color)),
+                Span::raw(format!(" {:>2}", day.count)),
+            ])
+        }));
+        Text::from(lines)
+    }
+}
+
+impl WithPlacement<'_> for History {
+    fn placement(&self) -> &LayoutPoint {
+        &self.placement
+    }
+}
+
+impl Component<'_> for History {
+    fn init(&mut self, _area: Rect) -> Result<()> {
+        self.debug();
+        Ok(())
+    }
+
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::GetLocations(locations) => {
+                self.names = locations
+                    .iter()
+                    .map(|l| (l.location_uid, (l.name().into(), l.name_en().into())))
+                    .collect();
+            }
+            Action::SelectLocationByUid(uid) => {
+                self.location_uid = uid.map(|uid| uid as i32);
+            }
+            Action::SelectTab(tab) => {
+                self.selected_tab = LayoutTab::from_repr(tab).unwrap_or_default();
+                if self.is_visible(&self.selected_tab) {
+                    return Ok(self.load(false));
+                }
+            }
+            Action::LoadHistory => return Ok(self.load(true)),
+            Action::GetAlertsHistory(uid, alerts) if Some(uid) == self.location_uid => {
+                self.loading = false;
+                self.history = Some((uid, alerts));
+            }
+            Action::Error(_) => self.loading = false,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>) -> Result<()> {
+        let area = self.get_area(f.size())?;
+        let theme = self.config.theme();
+        let block = Block::bordered().title(self.title().fg(theme.text));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let history = self
+            .history
+            .as_ref()
+            .filter(|(uid, _)| Some(*uid) == self.location_uid);
+        let Some((_, alerts)) = history else {
+            let hint = match self.location_uid {
+                Some(_) => t!("views.History.load"),
+                None => t!("views.History.no_location"),
+            };
+            f.render_widget(Paragraph::new(hint.to_string()).dim(), inner);
+            return Ok(());
+        };
+
+        let now = OffsetDateTime::now_utc();
+        let summary = HistorySummary::new(alerts, now, Self::days(alerts, now));
+        let [timeline_area, totals_area, sparkline_area] =
+            Layout::vertical([Min(0), Length(1), Length(4)]).areas(inner);
+        let timeline = Self::timeline(
+            &summary,
+            usize::from(timeline_area.width),
+            usize::from(timeline_area.height),
+            theme,
+        );
+        f.render_widget(Paragraph::new(timeline), timeline_area);
+
+        let format = |d: std::time::Duration| format_seconds(d.as_secs() as i64);
+        let totals = t!(
+            "views.History.totals",
+            count = summary.count,
+            total = format(summary.total),
+            average = format(summary.average)
+        );
+        f.render_widget(Paragraph::new(totals.to_string()), totals_area);
+
+        // the latest days which fit
+        let skip = summary
+            .days
+            .len()
+            .saturating_sub(usize::from(sparkline_area.width));
+        let counts: Vec<u64> = summary
+            .days
+            .iter()
+            .skip(skip)
+            .map(|d| d.count as u64)
+            .collect();
+        let sparkline = Sparkline::default()
+            .data(&counts)
+            .style(Style::default().fg(theme.alert));
+        f.render_widget(sparkline, sparkline_area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_on_demand() {
+        let mut history = History::new();
+        let tab = LayoutTab::Tab3 as usize;
+        // nothing to load until a location is selected
+        assert_eq!(history.update(Action::SelectTab(tab)).unwrap(), None);
+
+        history
+            .update(Action::SelectLocationByUid(Some(31)))
+            .unwrap();
+        assert_eq!(
+            history.update(Action::SelectTab(tab)).unwrap(),
+            Some(Action::FetchAlertsHistory(31))
+        );
+        assert!(history.loading);
+        history
+            .update(Action::GetAlertsHistory(31, Alerts::default()))
+            .unwrap();
+        assert!(!history.loading);
+
+        // shown history is not fetched again when the tab is opened
+        assert_eq!(history.update(Action::SelectTab(0)).unwrap(), None);
+        assert_eq!(history.update(Action::SelectTab(tab)).unwrap(), None);
+        assert_eq!(
+            history.update(Action::LoadHistory).unwrap(),
+            Some(Action::FetchAlertsHistory(31))
+        );
+        // late response for another location is dropped
+        history
+            .update(Action::SelectLocationByUid(Some(14)))
+            .unwrap();
+        history
+            .update(Action::GetAlertsHistory(31, Alerts::default()))
+            .unwrap();
+        assert!(history.history.as_ref().is_some_and(|(uid, _)| *uid == 31));
+    }
+
+    #[test]
+    fn test_timeline() {
+        let now = OffsetDateTime::now_utc();
+        assert_eq!(History::days(&Alerts::default(), now), HISTORY_DAYS);
+        let summary = HistorySummary::new(&Alerts::default(), now, 31);
+        let text = History::timeline(&summary, 33, 5, &Theme::default());
+        // ruler and the last 4 days
+        assert_eq!(text.lines.len(), 5);
+        assert_eq!(text.lines[1].width(), 33);
+        assert_eq!(
+            text.lines[0].to_string().trim_end(),
+            "      |     |     |     |"
+        );
+    }
+}
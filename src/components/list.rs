@@ -1,9 +1,10 @@
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use getset::*;
+use ralertsinua_http::Connectivity;
 use ralertsinua_models::*;
 use ratatui::{
     prelude::*,
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     widgets::{Block, List, ListState},
 };
 use rust_i18n::t;
@@ -11,7 +12,7 @@ use tokio::sync::mpsc::UnboundedSender;
 use tracing::info;
 
 use super::{Component, Frame, Result, WithPlacement};
-use crate::{action::Action, config::*, constants::*, layout::*, tui_helpers::*};
+use crate::{action::Action, config::*, layout::*, tui_helpers::*};
 
 #[derive(Debug, Getters, MutGetters, Setters)]
 pub struct LocationsList<'a> {
@@ -20,6 +21,7 @@ pub struct LocationsList<'a> {
     #[allow(unused)]
     title: Line<'a>,
     config: Config,
+    connectivity: Connectivity,
     #[getset(get = "pub")]
     oblast_statuses: AirRaidAlertOblastStatuses,
     #[getset(get = "pub with_prefix")]
@@ -29,6 +31,9 @@ pub struct LocationsList<'a> {
     #[getset(get = "pub", get_mut)]
     last_selected: Option<usize>,
     selected_location_uid: i32,
+    /// Area drawn last, to find the item under the mouse
+    area: Rect,
+    selected_tab: LayoutTab,
 }
 
 impl<'a> LocationsList<'a> {
@@ -38,14 +43,26 @@ impl<'a> LocationsList<'a> {
             placement: LayoutPoint(LayoutArea::Right, Some(LayoutTab::Tab1)),
             title: Line::default(),
             config: Config::default(),
+            connectivity: Connectivity::default(),
             oblast_statuses: AirRaidAlertOblastStatuses::default(),
             list: List::default(),
             state: ListState::default(),
             last_selected: None,
             selected_location_uid: -1,
+            area: Rect::default(),
+            selected_tab: LayoutTab::default(),
         }
     }
 
+    fn refresh_title(&mut self) {
+        self.title = get_title_with_online_status(
+            t!("views.List.title"),
+            &self.connectivity,
+            self.config.theme(),
+        )
+        .alignment(Alignment::Left);
+    }
+
     /// Generate List Widget with ListItems of locations
     fn generate_list(&mut self, is_loading: bool) -> List<'a> {
         let locale = self.config.get_locale();
@@ -57,7 +74,17 @@ impl<'a> LocationsList<'a> {
                 item.location_title_en()
             };
             let is_selected = (item.location_uid) == self.selected_location_uid;
-            get_styled_line_by_status(text, item.status(), &is_selected)
+            match self.config.accessible() {
+                true => {
+                    get_accessible_line_by_status(text, item.status(), self.config.theme())
+                }
+                false => get_styled_line_by_status(
+                    text,
+                    item.status(),
+                    &is_selected,
+                    self.config.theme(),
+                ),
+            }
         });
 
         List::new(items)
@@ -107,6 +134,43 @@ impl<'a> LocationsList<'a> {
         // drop(lock);
     }
 
+    /// Move the selection and select the location on the map
+    fn select_with(&mut self, move_selection: impl FnOnce(&mut Self)) -> Option<Action> {
+        if self.oblast_statuses().is_empty() {
+            return None;
+        }
+        move_selection(self);
+        let selected = self.selected()?;
+        self.selected_location_uid = selected.location_uid;
+        Some(Action::SelectLocationByUid(Some(
+            selected.location_uid as usize,
+        )))
+    }
+
+    /// Select the location picked elsewhere, e.g. on the map
+    fn select_location_uid(&mut self, location_uid: Option<usize>) {
+        let uid = location_uid.map_or(-1, |uid| uid as i32);
+        match self
+            .oblast_statuses
+            .iter()
+            .position(|s| s.location_uid == uid)
+        {
+            Some(i) => {
+                self.state.select(Some(i));
+                self.selected_location_uid = uid;
+            }
+            None if self.state.selected().is_some() => self.unselect(),
+            None => {}
+        }
+    }
+
+    /// Index of the item in the row of the terminal, if any
+    fn index_at(&self, column: u16, row: u16) -> Option<usize> {
+        let (_, row) = get_inner_position(self.area, column, row)?;
+        let index = self.state.offset() + row as usize;
+        (index < self.oblast_statuses().len()).then_some(index)
+    }
+
     pub fn selected(&self) -> Option<AirRaidAlertOblastStatus> {
         match self.state.selected() {
             Some(i) => self.oblast_statuses().get(i),
@@ -140,29 +204,70 @@ impl<'a> Component<'a> for LocationsList<'a> {
     #[tracing::instrument(skip(self))]
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         match action {
-            Action::Tick => {}
-            Action::GetAirRaidAlertOblastStatuses(data) => {
+            Action::Tick => self.refresh_title(),
+            Action::GetAirRaidAlertOblastStatuses(mut data) => {
+                // locations under alert on top, the selection follows the location
+                if *self.config.accessible() {
+                    data.sort_by_status();
+                }
                 self.oblast_statuses = data;
                 self.list = self.generate_list(true);
+                if *self.config.accessible() && self.selected_location_uid >= 0 {
+                    self.select_location_uid(Some(self.selected_location_uid as usize));
+                }
             }
             Action::Refresh => {
                 self.list = self.generate_list(false);
                 info!("List->update->Action::Refresh: {}", action);
             }
-            Action::Online(online) => {
-                self.title = get_title_with_online_status(
-                    t!("views.List.title"),
-                    self.config.online(),
-                )
-                .alignment(Alignment::Left);
+            Action::Online(connectivity) => {
+                self.connectivity = connectivity;
+                self.refresh_title();
+            }
+            Action::SelectTab(tab) => {
+                self.selected_tab = LayoutTab::from_repr(tab).unwrap_or_default();
+            }
+            // navigation and cancel keys of other tabs
+            Action::SelectNext
+            | Action::SelectPrevious
+            | Action::SelectFirst
+            | Action::SelectLast
+            | Action::Cancel
+                if !self.is_visible(&self.selected_tab) => {}
+            Action::SelectNext => return Ok(self.select_with(Self::next)),
+            Action::SelectPrevious => return Ok(self.select_with(Self::previous)),
+            Action::SelectFirst => return Ok(self.select_with(Self::go_top)),
+            Action::SelectLast => return Ok(self.select_with(Self::go_bottom)),
+            Action::SelectLocationByUid(location_uid) => {
+                self.select_location_uid(location_uid)
+            }
+            Action::Cancel => {
+                self.unselect();
+                return Ok(Some(Action::SelectLocationByUid(None)));
             }
             _ => {}
         }
         Ok(None)
     }
 
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        if get_inner_position(self.area, mouse.column, mouse.row).is_none() {
+            return Ok(None);
+        }
+        let action = match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => self
+                .index_at(mouse.column, mouse.row)
+                .and_then(|i| self.select_with(|list| list.state.select(Some(i)))),
+            MouseEventKind::ScrollDown => self.select_with(Self::next),
+            MouseEventKind::ScrollUp => self.select_with(Self::previous),
+            _ => None,
+        };
+        Ok(action)
+    }
+
     fn draw(&mut self, f: &mut Frame) -> Result<()> {
         let area = self.get_area(f.size())?;
+        self.area = area;
         let title = self.title.clone();
         let widget: List<'a> = self
             .list
@@ -171,8 +276,8 @@ impl<'a> Component<'a> for LocationsList<'a> {
             .highlight_style(
                 Style::default()
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Reset)
-                    .fg(*SELECTED_STYLE_FG),
+                    .bg(self.config.theme().background)
+                    .fg(self.config.theme().selected),
             )
             .highlight_symbol(">>")
             .repeat_highlight_symbol(true);
@@ -180,34 +285,6 @@ impl<'a> Component<'a> for LocationsList<'a> {
         f.render_stateful_widget(widget, area, self.state_mut());
         Ok(())
     }
-
-    fn handle_key_events(&mut self, key_event: KeyEvent) -> Result<Option<Action>> {
-        match key_event.code {
-            KeyCode::Down => {
-                self.next();
-                let selected = self.selected().unwrap();
-                self.selected_location_uid = selected.location_uid;
-                let action =
-                    Action::SelectLocationByUid(Some(selected.location_uid as usize));
-                Ok(Some(action))
-            }
-            KeyCode::Up => {
-                self.previous();
-                let selected = self.selected().unwrap();
-                self.selected_location_uid = selected.location_uid;
-                let action =
-                    Action::SelectLocationByUid(Some(selected.location_uid as usize));
-                Ok(Some(action))
-            }
-            KeyCode::Esc => {
-                self.unselect();
-                let action = Action::SelectLocationByUid(None);
-                Ok(Some(action))
-            }
-            // Other handlers you could add here.
-            _ => Ok(None),
-        }
-    }
 }
 
 #[cfg(test)]
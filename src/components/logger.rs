@@ -1,11 +1,11 @@
 use std::fmt;
 
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, MouseEvent, MouseEventKind};
 use log::LevelFilter;
 use ratatui::{prelude::*, widgets::Block};
 use rust_i18n::t;
 use tokio::sync::mpsc::UnboundedSender;
-use tui_logger::{TuiLoggerLevelOutput, TuiLoggerWidget, TuiWidgetState};
+use tui_logger::{TuiLoggerLevelOutput, TuiLoggerWidget, TuiWidgetEvent, TuiWidgetState};
 
 use super::{Component, Result, WithPlacement};
 use crate::{action::Action, config::*, layout::*, tui::Frame};
@@ -37,7 +37,7 @@ impl<'a> Logger<'a> {
     pub fn new() -> Self {
         Self {
             command_tx: Option::default(),
-            placement: LayoutPoint(LayoutArea::Inner, Some(LayoutTab::Tab2)),
+            placement: LayoutPoint(LayoutArea::Inner, Some(LayoutTab::Tab5)),
             config: Config::default(),
             title: Line::default(),
             state: TuiWidgetState::new().set_default_display_level(LevelFilter::Trace),
@@ -65,17 +65,30 @@ impl<'a> Component<'a> for Logger<'a> {
         }
     }
 
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.state.transition(TuiWidgetEvent::PrevPageKey),
+            MouseEventKind::ScrollDown => {
+                self.state.transition(TuiWidgetEvent::NextPageKey)
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
     fn draw(&mut self, f: &mut Frame) -> Result<()> {
         let area = self.get_area(f.size())?;
+        let theme = self.config.theme();
         let widget = TuiLoggerWidget::default()
             .block(
-                Block::bordered().title(t!("views.Logger.title").to_string().light_blue()),
+                Block::bordered()
+                    .title(t!("views.Logger.title").to_string().fg(theme.accent)),
             )
-            .style_error(Style::default().fg(Color::Red))
-            .style_debug(Style::default().fg(Color::Green))
-            .style_warn(Style::default().fg(Color::Yellow))
-            .style_trace(Style::default().fg(Color::Reset))
-            .style_info(Style::default().fg(Color::Cyan))
+            .style_error(Style::default().fg(theme.error))
+            .style_debug(Style::default().fg(theme.success))
+            .style_warn(Style::default().fg(theme.warning))
+            .style_trace(Style::default().fg(theme.text))
+            .style_info(Style::default().fg(theme.info))
             .output_separator(':')
             .output_timestamp(Some("%H:%M:%S".to_string()))
             .output_level(Some(TuiLoggerLevelOutput::Long))
@@ -1,5 +1,7 @@
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use geo::Rect as GeoRect;
 use ralertsinua_geo::*;
+use ralertsinua_http::Connectivity;
 use ralertsinua_models::*;
 use ratatui::widgets::canvas::Canvas;
 use ratatui::{prelude::*, widgets::*};
@@ -11,7 +13,14 @@ use tracing::debug;
 // use tui_popup::Popup;
 
 use super::{Component, Frame, Result, WithPlacement};
-use crate::{action::*, config::*, layout::*, tui_helpers::*};
+use crate::{action::*, config::*, layout::*, mode::Mode, tui_helpers::*};
+
+/// View is zoomed in and out by the factor
+const ZOOM_STEP: f64 = 1.5;
+/// Smallest view in degrees, a few times the size of Kyiv
+const MIN_VIEW_SIZE: f64 = 0.1;
+/// View is moved by the part of its size
+const PAN_STEP: f64 = 0.25;
 
 #[derive(Debug)]
 pub struct Map<'a> {
@@ -21,6 +30,7 @@ pub struct Map<'a> {
     title: Line<'a>,
     #[allow(unused)]
     config: Config,
+    connectivity: Connectivity,
     bounding_rect: GeoRect,
     boundary: CountryBoundary,
     locations: [Location; 27],
@@ -31,6 +41,13 @@ pub struct Map<'a> {
     width: u16,
     height: u16,
     resolution: (f64, f64),
+    /// Area drawn last, to convert mouse position to lon/lat
+    area: Rect,
+    /// Rect to show, extended to the aspect ratio of the grid when drawn
+    view: GeoRect,
+    /// Cell where the mouse button was pressed or dragged to last
+    drag_from: Option<(u16, u16)>,
+    dragged: bool,
 }
 
 impl<'a> Map<'a> {
@@ -41,6 +58,7 @@ impl<'a> Map<'a> {
             placement: LayoutPoint(LayoutArea::Left, Some(LayoutTab::Tab1)),
             title: Line::default(),
             config: Config::default(),
+            connectivity: Connectivity::default(),
             boundary: CountryBoundary::default(),
             bounding_rect: *UKRAINE_BBOX,
             locations: core::array::from_fn(|_| Location::default()),
@@ -51,6 +69,76 @@ impl<'a> Map<'a> {
             width: 0,
             height: 0,
             resolution: (0.0, 0.0),
+            area: Rect::default(),
+            view: *UKRAINE_BBOX,
+            drag_from: None,
+            dragged: false,
+        }
+        .with_full_view()
+    }
+
+    /// Show the whole country
+    fn with_full_view(mut self) -> Self {
+        self.view = self.full_view();
+        self
+    }
+
+    /// Padded bounding rect of the country
+    #[inline]
+    fn full_view(&self) -> GeoRect {
+        self.get_viewport((0.0, 0.0)).rect()
+    }
+
+    /// Current view fitted to the grid
+    #[inline]
+    pub fn viewport(&self) -> GeoViewport {
+        GeoViewport::fit(self.view, self.resolution)
+    }
+
+    /// Show the rect around the center, not larger than the country and not smaller than
+    /// the minimum, moved inside the country if needed
+    pub fn set_view(&mut self, center: (f64, f64), width: f64, height: f64) {
+        let full = self.full_view();
+        let width = width.clamp(MIN_VIEW_SIZE, full.width());
+        let height = height.clamp(MIN_VIEW_SIZE, full.height());
+        let x = center
+            .0
+            .clamp(full.min().x + width / 2.0, full.max().x - width / 2.0);
+        let y = center
+            .1
+            .clamp(full.min().y + height / 2.0, full.max().y - height / 2.0);
+        self.view = GeoRect::new(
+            geo::Coord::from((x - width / 2.0, y - height / 2.0)),
+            geo::Coord::from((x + width / 2.0, y + height / 2.0)),
+        );
+    }
+
+    /// Zoom in with the factor above 1, out below 1
+    pub fn zoom(&mut self, factor: f64) {
+        let rect = self.viewport().rect();
+        let center = rect.center().x_y();
+        self.set_view(center, rect.width() / factor, rect.height() / factor);
+    }
+
+    /// Move the view by degrees of longitude and latitude
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        let rect = self.viewport().rect();
+        let (x, y) = rect.center().x_y();
+        self.set_view((x + dx, y + dy), rect.width(), rect.height());
+    }
+
+    /// Move the view by the part of its size
+    fn pan_step(&mut self, x: f64, y: f64) {
+        let rect = self.viewport().rect();
+        self.pan(x * PAN_STEP * rect.width(), y * PAN_STEP * rect.height());
+    }
+
+    /// Fit the view to the selected location
+    pub fn focus_selected(&mut self) {
+        if let Some(location) = self.get_selected_location() {
+            let rect = location.bounding_rect();
+            let center = rect.center().x_y();
+            self.set_view(center, rect.width() * 1.2, rect.height() * 1.2);
         }
     }
 
@@ -62,6 +150,15 @@ impl<'a> Map<'a> {
         debug!(target:"app", "Map grid size: width: {}, height: {}, x_Y_bounds: {:?}, resolution: {:?}", width, height, self.get_x_y_bounds(), self.resolution);
     }
 
+    fn refresh_title(&mut self) {
+        self.title = get_title_with_online_status(
+            t!("views.Map.title"),
+            &self.connectivity,
+            self.config.theme(),
+        )
+        .alignment(Alignment::Left);
+    }
+
     #[inline]
     pub fn get_location_by<P>(&self, mut predicate: P) -> Option<Location>
     where
@@ -88,6 +185,96 @@ impl<'a> Map<'a> {
             .first()
             .cloned()
     }
+
+    /// Status of the location, loading until statuses are fetched
+    #[inline]
+    fn get_alert_status(&self, location_uid: i32) -> AlertStatus {
+        self.oblast_statuses
+            .get_by_location_uid(location_uid)
+            .map(|s| s.status().clone())
+            .unwrap_or(AlertStatus::L)
+    }
+
+    /// Labels with the status and the name of visible locations, placed without overlaps in
+    /// the order of priority: the selected one, active alerts, partial ones, then larger
+    /// locations. Names are abbreviated or left out where there is no room
+    fn place_labels<'b>(
+        &self,
+        locations: &[&Location],
+        viewport: &GeoViewport,
+    ) -> Vec<(u16, u16, Line<'b>)> {
+        if self.width == 0 || self.height == 0 {
+            return Vec::new();
+        }
+        let ([left, right], [bottom, top]) = viewport.bounds();
+        let locale = self.config.get_locale();
+        let theme = self.config.theme();
+        let priority = |l: &Location| {
+            let status = self.get_alert_status(l.location_uid);
+            (
+                l.location_uid != self.selected_location_uid,
+                status != AlertStatus::A,
+                status != AlertStatus::P,
+            )
+        };
+        // locations are sorted by area already, the sort is stable
+        let mut locations = locations.to_vec();
+        locations.sort_by_key(|l| priority(l));
+        let labels = locations
+            .into_iter()
+            .filter_map(|l| {
+                let (x, y) = l.label_point();
+                if !(left..right).contains(&x) || !(bottom..top).contains(&y) {
+                    return None;
+                }
+                let status = self.get_alert_status(l.location_uid);
+                let is_selected = l.location_uid == self.selected_location_uid;
+                let name = l.get_name_by_locale(locale).split(' ').next().unwrap_or("");
+                let short: String = name.chars().take(3).collect();
+                Some(MapLabel {
+                    column: ((x - left) / (right - left) * f64::from(self.width)) as u16,
+                    row: ((top - y) / (top - bottom) * f64::from(self.height)) as u16,
+                    variants: vec![
+                        get_styled_line_by_status(name, &status, &is_selected, theme),
+                        get_styled_line_by_status(
+                            format!("{}.", short),
+                            &status,
+                            &is_selected,
+                            theme,
+                        ),
+                        get_styled_line_icon_by_status(&status, &is_selected, theme),
+                    ],
+                })
+            })
+            .collect();
+        place_labels(labels, (self.width, self.height))
+    }
+
+    /// Convert the terminal cell to lon/lat of the center of its braille dots, the inverse
+    /// of [`Painter::get_point`](ratatui::widgets::canvas::Painter::get_point)
+    pub fn get_point_at(&self, column: u16, row: u16) -> Option<(f64, f64)> {
+        let (x, y) = get_inner_position(self.area, column, row)?;
+        let ([left, right], [bottom, top]) = self.viewport().bounds();
+        let (res_x, res_y) = self.resolution;
+        if res_x < 2.0 || res_y < 2.0 {
+            return None;
+        }
+        let dot_x = f64::from(x) * 2.0 + 0.5;
+        let dot_y = f64::from(y) * 4.0 + 1.5;
+        Some((
+            left + dot_x * (right - left) / (res_x - 1.0),
+            top - dot_y * (top - bottom) / (res_y - 1.0),
+        ))
+    }
+
+    /// Location containing the point, the smallest one for cities inside oblasts
+    pub fn get_location_at(&self, x: f64, y: f64) -> Option<&Location> {
+        let area = |l: &Location| l.bounding_rect().width() * l.bounding_rect().height();
+        self.locations
+            .iter()
+            .filter(|l| l.contains(x, y))
+            .min_by(|a, b| area(a).total_cmp(&area(b)))
+    }
 }
 
 impl WithPlacement<'_> for Map<'_> {
@@ -105,11 +292,6 @@ impl WithBoundingRect for Map<'_> {
 }
 
 impl<'a> Component<'a> for Map<'a> {
-    fn init(&mut self, r: Rect) -> Result<()> {
-        self.set_grid_size(r.width, r.height);
-        Ok(())
-    }
-
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
         self.command_tx = Some(tx);
         Ok(())
@@ -122,13 +304,12 @@ impl<'a> Component<'a> for Map<'a> {
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         match action {
-            Action::Tick => {}
-            Action::Resize(width, heith) => self.set_grid_size(width, heith),
+            Action::Tick => self.refresh_title(),
             Action::GetBoundaries(boundary) => {
                 self.boundary = boundary;
             }
             Action::GetLocations(locations) => {
-                self.locations = locations;
+                self.locations = *locations;
             }
             Action::GetAirRaidAlertOblastStatuses(data) => {
                 self.oblast_statuses = data;
@@ -145,72 +326,155 @@ impl<'a> Component<'a> for Map<'a> {
                     self.selected_location_uid = -1;
                 }
             },
-            Action::Online(online) => {
-                self.title = get_title_with_online_status(
-                    t!("views.Map.title"),
-                    self.config.online(),
-                )
-                .alignment(Alignment::Left);
+            Action::Online(connectivity) => {
+                self.connectivity = connectivity;
+                self.refresh_title();
             }
+            Action::ZoomIn => self.zoom(ZOOM_STEP),
+            Action::ZoomOut => self.zoom(1.0 / ZOOM_STEP),
+            Action::PanLeft => self.pan_step(-1.0, 0.0),
+            Action::PanRight => self.pan_step(1.0, 0.0),
+            Action::PanUp => self.pan_step(0.0, 1.0),
+            Action::PanDown => self.pan_step(0.0, -1.0),
+            Action::FocusSelected => self.focus_selected(),
+            Action::ResetZoom => self.view = self.full_view(),
             _ => {}
         }
         Ok(None)
     }
 
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        let (column, row) = (mouse.column, mouse.row);
+        if get_inner_position(self.area, column, row).is_none() {
+            self.drag_from = None;
+            return Ok(None);
+        }
+        let action = match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.drag_from = Some((column, row));
+                self.dragged = false;
+                None
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some((from_column, from_row)) = self.drag_from {
+                    // the map follows the mouse, a cell is 2 x 4 dots
+                    let (dot_x, dot_y) = self.viewport().dot_size();
+                    let dx = (f64::from(from_column) - f64::from(column)) * 2.0 * dot_x;
+                    let dy = (f64::from(row) - f64::from(from_row)) * 4.0 * dot_y;
+                    self.pan(dx, dy);
+                    self.drag_from = Some((column, row));
+                    self.dragged = true;
+                }
+                None
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                let clicked = self.drag_from.take().is_some() && !self.dragged;
+                match self.get_point_at(column, row).filter(|_| clicked) {
+                    Some((x, y)) => {
+                        let location_uid =
+                            self.get_location_at(x, y).map(|l| l.location_uid as usize);
+                        debug!(target:"app", "Map: click at {:.3}, {:.3}: {:?}", x, y, location_uid);
+                        Some(Action::SelectLocationByUid(location_uid))
+                    }
+                    None => None,
+                }
+            }
+            MouseEventKind::ScrollUp => Some(Action::ZoomIn),
+            MouseEventKind::ScrollDown => Some(Action::ZoomOut),
+            _ => None,
+        };
+        Ok(action)
+    }
+
     fn draw(&mut self, f: &mut Frame) -> Result<()> {
         let size: Rect = f.size();
         let area: Rect = self.get_area(size)?;
-        let (x_bounds, y_bounds) = self.get_x_y_bounds();
+        if area != self.area {
+            // canvas is inside the border
+            self.area = area;
+            let inner = area.inner(&Margin::new(1, 1));
+            self.set_grid_size(inner.width, inner.height);
+        }
+        let viewport = self.viewport();
+        let (x_bounds, y_bounds) = viewport.bounds();
         let selected_location = self.get_selected_location();
         let selected_alert_status = self.get_selected_alert_status();
         let selected_alert = self.get_selected_alert();
         let title = self.title.clone();
+        let help_key = self
+            .config
+            .keymap()
+            .chords(Mode::Global, &Action::Help)
+            .first()
+            .map(|c| c.to_string())
+            .unwrap_or_default();
+        let map = &*self;
+        let theme = map.config.theme();
+        // larger first, cities are drawn over the oblasts around them
+        let mut locations: Vec<&Location> = map.locations.iter().collect();
+        locations.sort_by(|a, b| {
+            let area =
+                |l: &Location| l.bounding_rect().width() * l.bounding_rect().height();
+            area(b).total_cmp(&area(a))
+        });
+        let labels = map.place_labels(&locations, &viewport);
         let widget = Canvas::default()
             .block(Block::bordered().title(title))
             .marker(Marker::Braille)
             .x_bounds(x_bounds)
             .y_bounds(y_bounds)
             .paint(move |ctx| {
-                //  Draw country borders with ctx
-                ctx.draw(&self.boundary);
-
-                // Draw & Print selected location with ctx
-                self.locations.iter().for_each(|l| {
-                    // Draw location
-                    ctx.draw(l);
-                    // Print location name
-                    let (x, y) = l.center();
-                    let text = l
-                        .get_name_by_locale(self.config.get_locale())
-                        .split(' ')
-                        .next()
-                        .unwrap_or("");
-                    let status: &AlertStatus = self
-                        .oblast_statuses
-                        .iter()
-                        .find(|&os| os.location_uid == l.location_uid)
-                        .unwrap()
-                        .status();
-                    let is_selected = (l.location_uid) == self.selected_location_uid;
-                    let line = get_styled_line_icon_by_status(status, &is_selected);
-                    ctx.print(x, y, line);
+                // Fill locations with the color of their status
+                locations.iter().for_each(|l| {
+                    let status = map.get_alert_status(l.location_uid);
+                    if let Some(pattern) = theme.fill_pattern(&status) {
+                        ctx.draw(&Fill {
+                            geometry: l.geometry(),
+                            viewport,
+                            color: theme.status(&status),
+                            pattern,
+                        });
+                    }
+                });
+                //  Draw country and location borders with ctx
+                ctx.draw(&Outline {
+                    rings: vec![map.boundary.0.exterior()],
+                    viewport,
+                    color: theme.outline,
+                });
+                locations.iter().for_each(|l| {
+                    ctx.draw(&Outline::new(l.geometry(), viewport, theme.outline))
+                });
+                if let Some(selected) = &selected_location {
+                    let color = theme.selected_outline;
+                    ctx.draw(&Outline::new(selected.geometry(), viewport, color));
+                }
+
+                // Print labels at the middle of their cells
+                let ([left, right], [bottom, top]) = viewport.bounds();
+                let (cell_x, cell_y) = (
+                    (right - left) / f64::from(map.width),
+                    (top - bottom) / f64::from(map.height),
+                );
+                labels.iter().for_each(|(column, row, line)| {
+                    let x = left + (f64::from(*column) + 0.5) * cell_x;
+                    let y = top - (f64::from(*row) + 0.5) * cell_y;
+                    ctx.print(x, y, line.clone());
                 });
             })
-            .background_color(Color::Reset);
+            .background_color(theme.background);
         f.render_widget(widget, area);
 
         let popup_area = get_bottom_left_rect(area, 30, 20);
-        let mut popup_bg = Color::Reset;
+        let (mut popup_fg, mut popup_bg) = (theme.text, theme.background);
         let mut lines: Vec<Line> = vec![
-            "No details".into(),
-            "To view details, select a location on the map using Up/Down arrow keys".into(),
-            "↓: move down".into(),
-            "↑: move up".into(),
+            t!("views.Map.no_details").to_string().into(),
+            t!("views.Map.hint", key = help_key).to_string().into(),
         ];
 
         // popup
         if let Some(sas) = selected_alert_status {
-            popup_bg = get_color_by_status(sas.status());
+            (popup_fg, popup_bg) = (theme.popup_fg, theme.status(sas.status()));
             lines = vec![
                 sas.location_title_en().to_string().into(),
                 sas.status().to_string().into(),
@@ -226,16 +490,182 @@ impl<'a> Component<'a> for Map<'a> {
             };
         };
         let paragraph = Paragraph::new(Text::from(lines))
-            .white()
+            .fg(popup_fg)
             .alignment(Alignment::Left);
         let block = Block::bordered()
             .bg(popup_bg)
-            .title("Alert Details:".white().bold().italic());
+            .title("Alert Details:".fg(popup_fg).bold().italic());
         f.render_widget(paragraph.block(block), popup_area);
         Ok(())
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn map() -> Map<'static> {
+        let mut map = Map::new();
+        map.locations = AlertsInUaGeoClient::default().locations();
+        map.area = Rect::new(0, 0, 102, 52);
+        map.set_grid_size(100, 50);
+        map
+    }
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    fn click(map: &mut Map, column: u16, row: u16) -> Option<Action> {
+        let down = mouse(MouseEventKind::Down(MouseButton::Left), column, row);
+        assert_eq!(map.handle_mouse_events(down).unwrap(), None);
+        let up = mouse(MouseEventKind::Up(MouseButton::Left), column, row);
+        map.handle_mouse_events(up).unwrap()
+    }
+
+    fn assert_close(a: GeoRect, b: GeoRect) {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        assert!(
+            close(a.min().x, b.min().x)
+                && close(a.min().y, b.min().y)
+                && close(a.max().x, b.max().x)
+                && close(a.max().y, b.max().y),
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    /// Cell of the point, the forward conversion of the canvas painter
+    fn cell_of(map: &Map, x: f64, y: f64) -> (u16, u16) {
+        let ([left, right], [bottom, top]) = map.viewport().bounds();
+        (
+            ((x - left) / (right - left) * 100.0) as u16 + 1,
+            ((top - y) / (top - bottom) * 50.0) as u16 + 1,
+        )
+    }
+
+    #[test]
+    fn test_draw_choropleth() {
+        let mut map = map();
+        map.oblast_statuses = AirRaidAlertOblastStatuses::new("A".repeat(27), Some(false));
+        map.selected_location_uid = 27;
+        let mut terminal = Terminal::new(backend::TestBackend::new(120, 30)).unwrap();
+
+        terminal.draw(|f| map.draw(f).unwrap()).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let cells = |color| buffer.content().iter().filter(|c| c.fg == color).count();
+        // regions are filled with the color of the active alert, not only the icons
+        let theme = map.config.theme();
+        assert!(cells(theme.alert) > (map.width as usize * map.height as usize) / 6);
+        assert!(cells(theme.selected_outline) > 0);
+    }
+
+    #[test]
+    fn test_get_point_at() {
+        let map = map();
+        let ([left, right], [bottom, top]) = map.viewport().bounds();
+        let (x, y) = map.get_point_at(1, 1).unwrap();
+        assert!(x > left && x - left < (right - left) / 100.0);
+        assert!(y < top && top - y < (top - bottom) / 50.0);
+        let (x, y) = map.get_point_at(100, 50).unwrap();
+        assert!(x < right && right - x < (right - left) / 100.0);
+        assert!(y > bottom && y - bottom < (top - bottom) / 50.0);
+        // border
+        assert!(map.get_point_at(0, 10).is_none());
+        assert!(map.get_point_at(101, 10).is_none());
+    }
+
+    #[test]
+    fn test_get_location_at() {
+        let map = map();
+        let uid = |x, y| map.get_location_at(x, y).map(|l| l.location_uid);
+        // Kyiv city inside Kyiv oblast
+        assert_eq!(uid(30.52, 50.45), Some(31));
+        assert_eq!(uid(30.0, 50.45), Some(14));
+        assert_eq!(uid(20.0, 50.0), None);
+    }
+
+    #[test]
+    fn test_click_selects_location() {
+        let mut map = map();
+        let (column, row) = cell_of(&map, 24.03, 49.84);
+
+        let action = click(&mut map, column, row);
+        assert_eq!(action, Some(Action::SelectLocationByUid(Some(27))));
+        let action = click(&mut map, 1, 1);
+        assert_eq!(action, Some(Action::SelectLocationByUid(None)));
+    }
+
+    #[test]
+    fn test_zoom_pan_and_focus() {
+        let mut map = map();
+        let full = map.viewport().rect();
+
+        map.update(Action::ZoomIn).unwrap();
+        let zoomed = map.viewport().rect();
+        assert!((full.width() / zoomed.width() - ZOOM_STEP).abs() < 1e-9);
+        assert!((zoomed.center().x - full.center().x).abs() < 1e-9);
+
+        map.update(Action::PanLeft).unwrap();
+        let panned = map.viewport().rect();
+        assert!(panned.center().x < zoomed.center().x);
+        assert!((panned.width() - zoomed.width()).abs() < 1e-9);
+
+        // not larger than the country and kept inside it
+        for _ in 0..10 {
+            map.update(Action::ZoomOut).unwrap();
+            map.update(Action::PanUp).unwrap();
+        }
+        assert_close(map.viewport().rect(), full);
+
+        map.selected_location_uid = 31;
+        map.update(Action::FocusSelected).unwrap();
+        let focused = map.viewport().rect();
+        let kyiv = map.get_selected_location().unwrap().bounding_rect();
+        assert!(focused.width() < 1.0 && focused.width() >= kyiv.width());
+        assert!(focused.height() >= kyiv.height());
+        assert!((focused.center().x - kyiv.center().x).abs() < 1e-9);
+        // aspect ratio of the grid is kept
+        let (dot_x, dot_y) = map.viewport().dot_size();
+        assert!((dot_x * focused.center().y.to_radians().cos() - dot_y).abs() < 1e-9);
+
+        map.update(Action::ResetZoom).unwrap();
+        assert_close(map.viewport().rect(), full);
+    }
+
+    #[test]
+    fn test_drag_pans_without_selecting() {
+        let mut map = map();
+        map.update(Action::ZoomIn).unwrap();
+        let before = map.viewport().rect().center();
+        let (dot_x, _) = map.viewport().dot_size();
+
+        let down = mouse(MouseEventKind::Down(MouseButton::Left), 50, 25);
+        map.handle_mouse_events(down).unwrap();
+        let drag = mouse(MouseEventKind::Drag(MouseButton::Left), 45, 25);
+        map.handle_mouse_events(drag).unwrap();
+        let up = mouse(MouseEventKind::Up(MouseButton::Left), 45, 25);
+
+        assert_eq!(map.handle_mouse_events(up).unwrap(), None);
+        let after = map.viewport().rect().center();
+        assert!((after.x - before.x - 10.0 * dot_x).abs() < 1e-9);
+        assert!((after.y - before.y).abs() < 1e-9);
+        let scroll = mouse(MouseEventKind::ScrollUp, 50, 25);
+        assert_eq!(
+            map.handle_mouse_events(scroll).unwrap(),
+            Some(Action::ZoomIn)
+        );
+    }
+}
+
 /* #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,247 @@
+use ralertsinua_http::Connectivity;
+use ralertsinua_models::*;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Paragraph, Wrap},
+};
+use rust_i18n::t;
+use std::collections::VecDeque;
+use time::OffsetDateTime;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame, Result, WithPlacement};
+use crate::{
+    action::Action, config::*, layout::*, notifications::*, tui_helpers::*,
+    utils::now_local,
+};
+
+/// Status changes kept, the latest first
+const ANNOUNCEMENTS_KEPT: usize = 10;
+
+/// Text-only layout of the statuses in place of the map, for screen readers. Locations are
+/// listed by the alert state, status changes are announced as sentences
+#[derive(Debug)]
+pub struct StatusReport {
+    command_tx: Option<UnboundedSender<Action>>,
+    placement: LayoutPoint,
+    config: Config,
+    connectivity: Connectivity,
+    oblast_statuses: AirRaidAlertOblastStatuses,
+    selected_location_uid: i32,
+    announcements: VecDeque<String>,
+}
+
+impl StatusReport {
+    pub fn new() -> Self {
+        Self {
+            command_tx: None,
+            placement: LayoutPoint(LayoutArea::Left, Some(LayoutTab::Tab1)),
+            config: Config::default(),
+            connectivity: Connectivity::default(),
+            oblast_statuses: AirRaidAlertOblastStatuses::default(),
+            selected_location_uid: -1,
+            announcements: VecDeque::new(),
+        }
+    }
+
+    fn name<'b>(status: &'b AirRaidAlertOblastStatus, locale: &str) -> &'b str {
+        match locale {
+            "uk" => status.location_title(),
+            _ => status.location_title_en(),
+        }
+    }
+
+    fn announce(
+        &mut self,
+        changes: &[AirRaidAlertOblastStatusChange],
+        at: OffsetDateTime,
+        locale: &str,
+    ) {
+        for change in changes {
+            let Some(transition) = TransitionType::of(change) else {
+                continue;
+            };
+            let title = match locale {
+                "uk" => change.location_title(),
+                _ => change.location_title_en(),
+            };
+            self.announcements
+                .push_front(Notifications::announcement(transition, title, at, locale));
+        }
+        self.announcements.truncate(ANNOUNCEMENTS_KEPT);
+    }
+
+    /// Summary, the selected location, the locations under alert and the latest changes, a
+    /// sentence or an item per line
+    fn lines(&self, locale: &str) -> Vec<Line<'static>> {
+        let theme = self.config.theme();
+        let alerts = self.oblast_statuses.get_active_alert_oblasts();
+        let partial = self.oblast_statuses.get_partly_active_alert_oblasts();
+        let mut lines: Vec<Line> = vec![t!(
+            "views.Report.summary",
+            locale = locale,
+            alerts = alerts.len(),
+            partial = partial.len(),
+            total = self.oblast_statuses.len()
+        )
+        .to_string()
+        .into()];
+        if let Some(selected) = self
+            .oblast_statuses
+            .get_by_location_uid(self.selected_location_uid)
+        {
+            let key = format!("statuses.{}", char::from(selected.status()));
+            let status = t!(&key, locale = locale);
+            let line = t!(
+                "views.Report.selected",
+                locale = locale,
+                location = Self::name(&selected, locale),
+                status = status
+            );
+            lines.push(Line::from(line.to_string()).fg(theme.status(selected.status())));
+        }
+
+        lines.push(Line::default());
+        match alerts.is_empty() {
+            true => lines.push(
+                t!("views.Report.no_alerts", locale = locale)
+                    .to_string()
+                    .into(),
+            ),
+            false => {
+                lines.push(
+                    Line::from(t!("views.Report.alerts", locale = locale).to_string())
+                        .bold(),
+                );
+                lines.extend(alerts.iter().map(|s| {
+                    Line::from(format!("- {}", Self::name(s, locale))).fg(theme.alert)
+                }));
+            }
+        }
+        if !partial.is_empty() {
+            lines.push(
+                Line::from(t!("views.Report.partial", locale = locale).to_string()).bold(),
+            );
+            lines.extend(partial.iter().map(|s| {
+                Line::from(format!("- {}", Self::name(s, locale))).fg(theme.partial)
+            }));
+        }
+
+        lines.push(Line::default());
+        match self.announcements.is_empty() {
+            true => lines.push(
+                t!("views.Report.no_changes", locale = locale)
+                    .to_string()
+                    .into(),
+            ),
+            false => {
+                lines.push(
+                    Line::from(t!("views.Report.changes", locale = locale).to_string())
+                        .bold(),
+                );
+                lines.extend(
+                    self.announcements
+                        .iter()
+                        .map(|a| Line::from(format!("- {}", a))),
+                );
+            }
+        }
+        lines
+    }
+}
+
+impl WithPlacement<'_> for StatusReport {
+    fn placement(&self) -> &LayoutPoint {
+        &self.placement
+    }
+}
+
+impl Component<'_> for StatusReport {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::GetAirRaidAlertOblastStatuses(data) => self.oblast_statuses = data,
+            Action::GetStatusChanges(changes) => {
+                let locale = self.config.get_locale();
+                self.announce(&changes, now_local(), locale.as_str())
+            }
+            Action::SelectLocationByUid(location_uid) => {
+                self.selected_location_uid = location_uid.map_or(-1, |uid| uid as i32);
+            }
+            Action::Online(connectivity) => self.connectivity = connectivity,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>) -> Result<()> {
+        let area = self.get_area(f.size())?;
+        let locale = self.config.get_locale();
+        let title = get_title_with_online_status(
+            t!("views.Report.title", locale = locale.as_str()),
+            &self.connectivity,
+            self.config.theme(),
+        );
+        // no borders, box drawing characters are read out
+        let widget = Paragraph::new(self.lines(locale.as_str()))
+            .block(Block::default().title(title))
+            .wrap(Wrap { trim: false });
+        f.render_widget(widget, area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let previous = AirRaidAlertOblastStatuses::new(
+            "ANNNNNNNNNNNNNNNNNNNNNNNNNN".to_string(),
+            Some(false),
+        );
+        let current = AirRaidAlertOblastStatuses::new(
+            "NAPNNNNNNNNNNNNNNNNNNNNNNNN".to_string(),
+            Some(false),
+        );
+        let mut report = StatusReport::new();
+        report
+            .update(Action::GetAirRaidAlertOblastStatuses(current.clone()))
+            .unwrap();
+        // 14:22 UTC, summer time in Kyiv
+        let at = OffsetDateTime::from_unix_timestamp(1_714_918_920)
+            .unwrap()
+            .to_offset(time::macros::offset!(+3));
+        report.announce(&current.diff(&previous), at, "en");
+
+        let text: Vec<String> = report.lines("en").iter().map(|l| l.to_string()).collect();
+        assert_eq!(
+            text[0],
+            "Air raid alerts in 1 of 27 regions, partial alerts in 1"
+        );
+        assert_eq!(
+            text.last().unwrap(),
+            "- Autonomous Republic of Crimea: air raid alert ended 17:22"
+        );
+        assert!(text.contains(&"- Vinnytsia Oblast: air raid alert started 17:22".into()));
+        assert!(text.iter().all(|l| l.is_ascii()));
+        // the local offset is unknown
+        let announcement = Notifications::announcement(
+            TransitionType::End,
+            "Kyiv",
+            at.to_offset(time::UtcOffset::UTC),
+            "en",
+        );
+        assert_eq!(announcement, "Kyiv: air raid alert ended 14:22 UTC");
+    }
+}
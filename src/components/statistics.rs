@@ -0,0 +1,256 @@
+use ralertsinua_geo::*;
+use ralertsinua_models::*;
+use ratatui::{
+    layout::Constraint::*,
+    prelude::*,
+    widgets::{Bar, BarChart, BarGroup, Block, Cell, Paragraph, Row, Table},
+};
+use rust_i18n::t;
+use std::collections::HashMap;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame, Result, WithPlacement};
+use crate::{action::Action, config::*, layout::*, tui_helpers::*, utils::now_local};
+
+/// Pairs of oblasts listed under the charts
+const CO_OCCURRENCES_SHOWN: usize = 5;
+
+/// Statistics of every alert seen, the active ones and the histories loaded so far
+#[derive(Debug)]
+pub struct Statistics {
+    command_tx: Option<UnboundedSender<Action>>,
+    placement: LayoutPoint,
+    config: Config,
+    /// English names of the locations on the map, by uid
+    names_en: HashMap<i32, String>,
+    alerts: HashMap<i32, Alert>,
+    stats: AlertStats,
+}
+
+impl Statistics {
+    pub fn new() -> Self {
+        Self {
+            command_tx: None,
+            placement: LayoutPoint(LayoutArea::Inner, Some(LayoutTab::Tab4)),
+            config: Config::default(),
+            names_en: HashMap::new(),
+            alerts: HashMap::new(),
+            stats: AlertStats::default(),
+        }
+    }
+
+    /// Add the alerts, replacing the known ones with their updates
+    fn extend(&mut self, alerts: &Alerts) {
+        self.alerts.extend(alerts.iter().map(|a| (a.id, a.clone())));
+        self.stats = AlertStats::new(self.alerts.values(), now_local());
+    }
+
+    fn name<'b>(&'b self, uid: i32, title: &'b str) -> &'b str {
+        match self.names_en.get(&uid) {
+            Some(name) if self.config.get_locale().as_str() != "uk" => name,
+            _ => title,
+        }
+    }
+
+    fn locations_table(&self) -> Table<'static> {
+        let header = Row::new(
+            [
+                "location", "count", "total", "average", "longest", "shortest",
+            ]
+            .map(|c| t!(&format!("views.Stats.columns.{}", c)).to_string()),
+        )
+        .add_modifier(Modifier::BOLD);
+        let rows = self.stats.locations.iter().map(|l| {
+            Row::new(vec![
+                Cell::from(self.name(l.location_uid, &l.location_title).to_string()),
+                Cell::from(l.count.to_string()),
+                Cell::from(format_seconds(l.total_seconds)),
+                Cell::from(format_seconds(l.average_seconds)),
+                Cell::from(format_seconds(l.longest_seconds)),
+                Cell::from(format_seconds(l.shortest_seconds)),
+            ])
+        });
+        let widths = [
+            Fill(1),
+            Length(6),
+            Length(12),
+            Length(10),
+            Length(10),
+            Length(10),
+        ];
+        Table::new(rows, widths).header(header)
+    }
+
+    fn bar_chart<'b>(
+        &self,
+        title: String,
+        bars: Vec<Bar<'b>>,
+        bar_width: u16,
+    ) -> BarChart<'b> {
+        BarChart::default()
+            .block(Block::bordered().title(title))
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(self.config.theme().alert))
+    }
+
+    fn co_occurrences(&self) -> Text<'static> {
+        let lines = self
+            .stats
+            .co_occurrences
+            .iter()
+            .take(CO_OCCURRENCES_SHOWN)
+            .map(|c| {
+                Line::from(format!(
+                    "{} + {}: {} × {}",
+                    self.name(c.oblast_uid, &c.oblast_title),
+                    self.name(c.other_oblast_uid, &c.other_oblast_title),
+                    c.count,
+                    format_seconds(c.overlap_seconds)
+                ))
+            });
+        Text::from(lines.collect::<Vec<_>>())
+    }
+}
+
+impl WithPlacement<'_> for Statistics {
+    fn placement(&self) -> &LayoutPoint {
+        &self.placement
+    }
+}
+
+impl Component<'_> for Statistics {
+    fn init(&mut self, _area: Rect) -> Result<()> {
+        self.debug();
+        Ok(())
+    }
+
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::GetLocations(locations) => {
+                self.names_en = locations
+                    .iter()
+                    .map(|l| (l.location_uid, l.name_en().to_string()))
+                    .collect();
+            }
+            Action::GetActiveAlerts(alerts)
+            | Action::GetAlertsHistory(_, alerts)
+            | Action::GetStoredAlerts(alerts) => self.extend(&alerts),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>) -> Result<()> {
+        let area = self.get_area(f.size())?;
+        let title = t!(
+            "views.Stats.title",
+            count = self.stats.count,
+            total = format_seconds(self.stats.total_seconds)
+        );
+        let block = Block::bordered().title(title.to_string().fg(self.config.theme().text));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let [left, right] =
+            Layout::horizontal([Percentage(50), Percentage(50)]).areas(inner);
+        let [table_area, hint_area] = Layout::vertical([Min(0), Length(1)]).areas(left);
+        f.render_widget(self.locations_table(), table_area);
+        f.render_widget(
+            Paragraph::new(t!("views.Stats.hint").to_string()).dim(),
+            hint_area,
+        );
+
+        let [hours_area, weekdays_area, co_area] =
+            Layout::vertical([Percentage(40), Percentage(30), Percentage(30)]).areas(right);
+        let hours: Vec<Bar> = self
+            .stats
+            .by_hour
+            .iter()
+            .enumerate()
+            .map(|(hour, count)| {
+                let label = if hour % 3 == 0 {
+                    format!("{:02}", hour)
+                } else {
+                    String::new()
+                };
+                Bar::default().value(*count as u64).label(label.into())
+            })
+            .collect();
+        let by_hour = self.bar_chart(t!("views.Stats.by_hour").to_string(), hours, 1);
+        f.render_widget(by_hour, hours_area);
+
+        let weekday_names = t!("views.Stats.weekdays").to_string();
+        let weekdays: Vec<Bar> = self
+            .stats
+            .by_weekday
+            .iter()
+            .zip(weekday_names.split(' '))
+            .map(|(count, name)| {
+                Bar::default()
+                    .value(*count as u64)
+                    .label(name.to_string().into())
+            })
+            .collect();
+        let by_weekday =
+            self.bar_chart(t!("views.Stats.by_weekday").to_string(), weekdays, 3);
+        f.render_widget(by_weekday, weekdays_area);
+
+        let co_occurrences = Paragraph::new(self.co_occurrences())
+            .block(Block::bordered().title(t!("views.Stats.co_occurrences").to_string()));
+        f.render_widget(co_occurrences, co_area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alerts(ids: &[i32]) -> Alerts {
+        let alerts: Vec<serde_json::Value> = ids
+            .iter()
+            .map(|id| {
+                serde_json::json!({
+                    "alert_type": "air_raid",
+                    "finished_at": "2024-05-05T16:48:31.000Z",
+                    "id": id,
+                    "location_oblast": "Сумська область",
+                    "location_oblast_uid": 20,
+                    "location_title": "Сумська область",
+                    "location_type": "oblast",
+                    "location_uid": "20",
+                    "notes": null,
+                    "started_at": "2024-05-05T15:48:31.000Z",
+                    "updated_at": "2024-05-05T15:48:31.818Z"
+                })
+            })
+            .collect();
+        Alerts::new(serde_json::from_value(alerts.into()).unwrap())
+    }
+
+    #[test]
+    fn test_alerts_are_merged() {
+        let mut statistics = Statistics::new();
+        statistics
+            .update(Action::GetActiveAlerts(alerts(&[1, 2])))
+            .unwrap();
+        statistics
+            .update(Action::GetAlertsHistory(20, alerts(&[2, 3])))
+            .unwrap();
+        assert_eq!(statistics.stats.count, 3);
+        assert_eq!(statistics.stats.total_seconds, 3 * 3600);
+        assert_eq!(statistics.stats.locations[0].location_uid, 20);
+    }
+}
@@ -3,24 +3,215 @@ use dotenv_config::EnvConfig;
 use getset::{Getters, Setters};
 use icu_locid::subtags::{language, Language};
 #[allow(unused_imports)]
-use miette::{miette, Error, IntoDiagnostic, Result};
+use miette::{miette, Error, IntoDiagnostic, NamedSource, Result, SourceSpan, WrapErr};
 use serde::{Deserialize, Serialize};
-use std::{str::FromStr, string::ToString};
-use tracing::warn;
+use std::{collections::BTreeMap, path::Path, str::FromStr, string::ToString};
+use strum::{Display, VariantNames};
+use toml::Spanned;
+use tracing::{debug, warn};
 
 #[allow(unused_imports)]
 use crate::error::*;
+use crate::{
+    action::Action,
+    cli::Cli,
+    hooks::HooksConfig,
+    keymap::{find_conflict, KeyChord, Keymap},
+    mode::Mode,
+    theme::{Theme, ThemeName},
+};
 
-#[derive(Debug, Clone, EnvConfig, Getters, Setters)]
+/// Config file in the config directory
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+/// Template written by `config init`, every setting commented out with its default
+pub const CONFIG_TEMPLATE: &str = include_str!("../.config/config.toml");
+
+/// Environment variable of every setting, the key is the name in the config file
+const SETTINGS_ENV: &[(&str, &str)] = &[
+    ("base_url", "ALERTSINUA_BASE_URL"),
+    ("token", "ALERTSINUA_TOKEN"),
+    ("polling_interval", "ALERTSINUA_POLLING_INTERVAL_SEC"),
+    ("log_file", "LOG_FILE"),
+    ("locale", "SETTINGS_LOCALE"),
+    ("tick_rate", "TICK_RATE"),
+    ("frame_rate", "FRAME_RATE"),
+    ("mouse", "ALERTSINUA_MOUSE"),
+    ("watchlist", "ALERTSINUA_WATCHLIST"),
+    ("notify_bell", "ALERTSINUA_NOTIFY_BELL"),
+    ("notify_osc", "ALERTSINUA_NOTIFY_OSC"),
+    ("notify_osc_protocol", "ALERTSINUA_NOTIFY_OSC_PROTOCOL"),
+    ("notify_banner", "ALERTSINUA_NOTIFY_BANNER"),
+    ("theme", "ALERTSINUA_THEME"),
+    ("accessible", "ALERTSINUA_ACCESSIBLE"),
+];
+
+/// Layer the effective value of a setting comes from
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum SettingSource {
+    #[default]
+    Default,
+    File,
+    Env,
+    Cli,
+}
+
+#[derive(Debug, Clone, Getters, Setters)]
 pub struct Config {
-    // pub keybindings: HashMap<String, String>, // FIXME: fails with new EnvConfig derive
     #[getset(get = "pub")]
     settings: Settings,
-    #[env_config(default = false)]
-    #[getset(get = "pub", set = "pub")]
-    online: bool,
+    sources: BTreeMap<String, SettingSource>,
+    #[getset(get = "pub")]
+    keymap: Keymap,
+    #[getset(get = "pub")]
+    theme: Theme,
+    #[getset(get = "pub")]
+    hooks: Option<HooksConfig>,
+}
+
+/// Settings present in the config file, with spans to point at invalid values
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct SettingsFile {
+    base_url: Option<Spanned<String>>,
+    token: Option<Spanned<String>>,
+    polling_interval: Option<Spanned<u64>>,
+    log_file: Option<Spanned<String>>,
+    locale: Option<Spanned<String>>,
+    tick_rate: Option<Spanned<f64>>,
+    frame_rate: Option<Spanned<f64>>,
+    mouse: Option<Spanned<bool>>,
+    watchlist: Option<Spanned<String>>,
+    notify_bell: Option<Spanned<String>>,
+    notify_osc: Option<Spanned<String>>,
+    notify_osc_protocol: Option<Spanned<String>>,
+    notify_banner: Option<Spanned<String>>,
+    theme: Option<Spanned<String>>,
+    accessible: Option<Spanned<bool>>,
+    /// Colors of the theme overridden by name, e.g. `alert = "#ff0000"`
+    #[serde(default, skip_serializing)]
+    colors: BTreeMap<Spanned<String>, Spanned<String>>,
+    /// Key chords mapped to actions per mode, e.g. `[keybindings.list]`
+    #[serde(default, skip_serializing)]
+    keybindings: BTreeMap<Mode, BTreeMap<Spanned<String>, Spanned<Action>>>,
+    /// Shell commands run on alert transitions
+    #[serde(default, skip_serializing)]
+    hooks: Option<HooksConfig>,
+}
+
+impl SettingsFile {
+    /// Parse and validate the file, errors point at the offending key or value.
+    /// Returns settings present in the file, the keymap with its bindings applied, the
+    /// color overrides and the hooks
+    fn parse(content: &str, name: &str) -> Result<SettingsFileParts, ConfigFileError> {
+        let error =
+            |message: String, span: Option<std::ops::Range<usize>>| ConfigFileError {
+                message,
+                src: NamedSource::new(name, content.to_string()),
+                span: span.map(SourceSpan::from),
+            };
+        let file: SettingsFile = toml::from_str(content)
+            .map_err(|e| error(e.message().to_string(), e.span()))?;
+
+        if let Some(url) = &file.base_url {
+            if !url.get_ref().starts_with("http://")
+                && !url.get_ref().starts_with("https://")
+            {
+                return Err(error(
+                    "base_url must be http(s) url".into(),
+                    Some(url.span()),
+                ));
+            }
+        }
+        if let Some(token) = &file.token {
+            if !token.get_ref().is_empty() && !Config::validate_token(token.get_ref()) {
+                let message = "token is invalid, must be 46 characters long".into();
+                return Err(error(message, Some(token.span())));
+            }
+        }
+        if let Some(locale) = &file.locale {
+            if !rust_i18n::available_locales!().contains(&locale.get_ref().as_str()) {
+                let message = "locale is not available, expected en or uk".into();
+                return Err(error(message, Some(locale.span())));
+            }
+        }
+        if let Some(theme) = &file.theme {
+            if ThemeName::from_str(theme.get_ref()).is_err() {
+                let message = format!(
+                    "theme is not available, expected one of: {}",
+                    ThemeName::VARIANTS.join(", ")
+                );
+                return Err(error(message, Some(theme.span())));
+            }
+        }
+        let mut colors = Vec::new();
+        for (key, value) in &file.colors {
+            if !Theme::COLORS.contains(&key.get_ref().as_str()) {
+                let message = format!(
+                    "unknown color '{}', expected one of: {}",
+                    key.get_ref(),
+                    Theme::COLORS.join(", ")
+                );
+                return Err(error(message, Some(key.span())));
+            }
+            Theme::default()
+                .set(key.get_ref(), value.get_ref())
+                .map_err(|e| error(e, Some(value.span())))?;
+            colors.push((key.get_ref().clone(), value.get_ref().clone()));
+        }
+
+        let mut keymap = Keymap::default();
+        let mut bound = Vec::new();
+        for (mode, bindings) in &file.keybindings {
+            let mut chords = Vec::new();
+            for (chord, action) in bindings {
+                let parsed: KeyChord = chord
+                    .get_ref()
+                    .parse()
+                    .map_err(|e| error(e, Some(chord.span())))?;
+                chords.push((parsed, action.get_ref().clone(), chord.span()));
+            }
+            let refs: Vec<&KeyChord> = chords.iter().map(|(c, _, _)| c).collect();
+            if let Some((i, j)) = find_conflict(&refs) {
+                let message = format!(
+                    "'{}' conflicts with '{}' in {} keybindings",
+                    refs[i], refs[j], mode
+                );
+                return Err(error(message, Some(chords[i].2.clone())));
+            }
+            for (chord, action, span) in chords {
+                for (replaced, _) in keymap.bind(*mode, chord.clone(), action) {
+                    debug!("keybinding '{}' replaces default '{}'", chord, replaced);
+                }
+                bound.push((*mode, chord, span));
+            }
+        }
+        // global chords are shadowed by the longer or shorter ones of a mode and the
+        // other way round
+        for (mode, chord, span) in &bound {
+            if let Some((other, conflict)) = keymap.find_global_conflict(*mode, chord) {
+                let message = format!(
+                    "'{}' in {} keybindings conflicts with '{}' in {} keybindings",
+                    chord, mode, conflict, other
+                );
+                return Err(error(message, Some(span.clone())));
+            }
+        }
+
+        let settings =
+            toml::Table::try_from(&file).map_err(|e| error(e.to_string(), None))?;
+        Ok((settings, keymap, colors, file.hooks))
+    }
 }
 
+/// Settings, keymap, color overrides and hooks of the config file
+type SettingsFileParts = (
+    toml::Table,
+    Keymap,
+    Vec<(String, String)>,
+    Option<HooksConfig>,
+);
+
 #[derive(Debug, Deserialize, Clone, EnvConfig, Getters, Setters, Serialize)]
 pub struct Settings {
     #[env_config(name = "ALERTSINUA_BASE_URL", default = "https://api.alerts.in.ua")]
@@ -37,7 +228,12 @@ pub struct Settings {
     pub log_file: String,
     /// [`Language`] represents a Unicode base language code conformant to the
     /// [`unicode_language_id`] field of the Language and Locale Identifier.
-    #[env_config(default = "en", help = "Available locales: en, uk", parse(true))]
+    #[env_config(
+        name = "SETTINGS_LOCALE",
+        default = "en",
+        help = "Available locales: en, uk",
+        parse(true)
+    )]
     #[getset(get = "pub with_prefix")]
     pub locale: String, // Language, // FIXME: fails with new EnvConfig derive
     #[env_config(name = "TICK_RATE", default = 1.0)]
@@ -46,6 +242,58 @@ pub struct Settings {
     #[env_config(name = "FRAME_RATE", default = 1.0)]
     #[getset(get = "pub")]
     pub frame_rate: f64,
+    /// Capture mouse to select locations by click and scroll with the wheel
+    #[env_config(name = "ALERTSINUA_MOUSE", default = false)]
+    #[getset(get = "pub")]
+    pub mouse: bool,
+    /// Locations to notify about, comma separated uids, uk/en names or ISO 3166-2 codes
+    #[env_config(name = "ALERTSINUA_WATCHLIST", default = "")]
+    #[getset(get = "pub")]
+    pub watchlist: String,
+    /// Transitions (start, end, partial) to ring the terminal bell on
+    #[env_config(name = "ALERTSINUA_NOTIFY_BELL", default = "start,end")]
+    #[getset(get = "pub")]
+    pub notify_bell: String,
+    /// Transitions to send desktop notification on, via OSC escape sequence
+    #[env_config(name = "ALERTSINUA_NOTIFY_OSC", default = "")]
+    #[getset(get = "pub")]
+    pub notify_osc: String,
+    /// OSC escape sequence supported by the terminal: 9 or 777
+    #[env_config(name = "ALERTSINUA_NOTIFY_OSC_PROTOCOL", default = "9")]
+    #[getset(get = "pub")]
+    pub notify_osc_protocol: String,
+    /// Transitions to show a banner on
+    #[env_config(name = "ALERTSINUA_NOTIFY_BANNER", default = "start,end,partial")]
+    #[getset(get = "pub")]
+    pub notify_banner: String,
+    /// Built-in theme: dark, light, high-contrast or colorblind
+    #[env_config(name = "ALERTSINUA_THEME", default = "dark")]
+    #[getset(get = "pub")]
+    pub theme: String,
+    /// Text-only layout for screen readers, status changes are announced as sentences
+    #[env_config(name = "ALERTSINUA_ACCESSIBLE", default = false)]
+    #[getset(get = "pub")]
+    pub accessible: bool,
+}
+
+impl Settings {
+    /// Defaults of every setting typed as in the config file, the environment is not read
+    fn defaults() -> toml::Table {
+        let help = Settings::get_help();
+        SETTINGS_ENV
+            .iter()
+            .filter_map(|(key, name)| {
+                let (default, _) = help.get(*name)?;
+                // defaults which are not valid values of the file are strings, e.g. urls
+                let typed = format!("{} = {}", key, default);
+                let value = match toml::from_str::<SettingsFile>(&typed) {
+                    Ok(_) => typed.parse::<toml::Table>().ok()?.remove(*key)?,
+                    Err(_) => default.clone().into(),
+                };
+                Some((key.to_string(), value))
+            })
+            .collect()
+    }
 }
 
 impl Default for Config {
@@ -55,6 +303,203 @@ impl Default for Config {
 }
 
 impl Config {
+    /// Settings from the environment only, with defaults for the missing ones
+    pub fn init() -> std::result::Result<Self, &'static str> {
+        let settings = Settings::init()?;
+        let sources = SETTINGS_ENV
+            .iter()
+            .map(|(key, env)| {
+                let source = match std::env::var_os(env) {
+                    Some(_) => SettingSource::Env,
+                    None => SettingSource::Default,
+                };
+                (key.to_string(), source)
+            })
+            .collect();
+        let theme = Theme::load(settings.theme.parse().unwrap_or_default());
+        Ok(Self {
+            settings,
+            sources,
+            keymap: Keymap::default(),
+            theme,
+            hooks: None,
+        })
+    }
+
+    /// Layer settings: defaults < config file < environment < command line flags
+    pub fn load(path: &Path, cli: &Cli) -> Result<Self> {
+        Self::load_with_env(path, cli, |name| std::env::var(name).ok())
+    }
+
+    /// [`Config::load`] with environment variables looked up by `env`
+    fn load_with_env<F>(path: &Path, cli: &Cli, env: F) -> Result<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut table = Settings::defaults();
+        let mut config = Self {
+            settings: table.clone().try_into().into_diagnostic()?,
+            sources: BTreeMap::new(),
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            hooks: None,
+        };
+        let mut colors = Vec::new();
+
+        for (key, name) in SETTINGS_ENV {
+            let Some(value) = env(name) else {
+                config
+                    .sources
+                    .insert(key.to_string(), SettingSource::Default);
+                continue;
+            };
+            let invalid = |e: String| miette!("{} is invalid: {}", name, e);
+            let value = match table.get(*key) {
+                Some(toml::Value::Integer(_)) => toml::Value::Integer(
+                    value.parse::<i64>().map_err(|e| invalid(e.to_string()))?,
+                ),
+                Some(toml::Value::Float(_)) => toml::Value::Float(
+                    value.parse::<f64>().map_err(|e| invalid(e.to_string()))?,
+                ),
+                Some(toml::Value::Boolean(_)) => toml::Value::Boolean(
+                    value.parse::<bool>().map_err(|e| invalid(e.to_string()))?,
+                ),
+                _ => toml::Value::String(value),
+            };
+            config.sources.insert(key.to_string(), SettingSource::Env);
+            table.insert(key.to_string(), value);
+        }
+
+        if config.source("locale") == SettingSource::Default {
+            let locale = get_system_locale();
+            if rust_i18n::available_locales!().contains(&locale.as_str()) {
+                table.insert("locale".into(), locale.into());
+            }
+        }
+        if path.exists() {
+            let content = std::fs::read_to_string(path)
+                .into_diagnostic()
+                .wrap_err(format!("Error opening file, {}", path.display()))?;
+            let (file, keymap, file_colors, hooks) =
+                SettingsFile::parse(&content, &path.display().to_string())?;
+            config.keymap = keymap;
+            config.hooks = hooks;
+            colors = file_colors;
+            for (key, value) in file {
+                if config.source(&key) != SettingSource::Env {
+                    config.sources.insert(key.clone(), SettingSource::File);
+                    table.insert(key, value);
+                }
+            }
+        }
+        let flags: [(&str, Option<toml::Value>); 6] = [
+            (
+                "token",
+                Some(cli.token.clone())
+                    .filter(|t| !t.is_empty())
+                    .map(Into::into),
+            ),
+            (
+                "log_file",
+                Some(cli.log_file.clone())
+                    .filter(|f| !f.is_empty())
+                    .map(Into::into),
+            ),
+            ("locale", cli.locale.clone().map(Into::into)),
+            ("tick_rate", cli.tick_rate.map(Into::into)),
+            ("frame_rate", cli.frame_rate.map(Into::into)),
+            ("accessible", cli.accessible.then_some(true.into())),
+        ];
+        for (key, value) in flags {
+            if let Some(value) = value {
+                config.sources.insert(key.to_string(), SettingSource::Cli);
+                table.insert(key.to_string(), value);
+            }
+        }
+
+        config.settings = table.try_into().into_diagnostic()?;
+        if !config.token().is_empty() && !Self::validate_token(config.token()) {
+            return Err(miette!(
+                "token from {} is invalid, must be 46 characters long",
+                config.source("token")
+            ));
+        }
+        config.validate()?;
+        let theme = ThemeName::from_str(&config.settings.theme).map_err(|_| {
+            miette!(
+                "theme from {} is not available, expected one of: {}",
+                config.source("theme"),
+                ThemeName::VARIANTS.join(", ")
+            )
+        })?;
+        config.theme = Theme::load_with_env(theme, &env);
+        for (key, value) in &colors {
+            config.theme.set(key, value).map_err(|e| miette!(e))?;
+        }
+        let locale = config.settings.locale.clone();
+        if let Ok(lang) = Language::from_str(&locale) {
+            config.set_locale(lang);
+        }
+        Ok(config)
+    }
+
+    /// Check the settings once every layer is applied, so that values of the environment
+    /// and flags are checked along with the ones of the file
+    fn validate(&self) -> std::result::Result<(), SettingError> {
+        let error = |key: &'static str, message: &str| SettingError {
+            key,
+            origin: self.source(key).to_string(),
+            message: message.to_string(),
+        };
+        if self.settings.polling_interval == 0 {
+            return Err(error("polling_interval", "must be at least 1 second"));
+        }
+        let rates = [
+            ("tick_rate", self.settings.tick_rate),
+            ("frame_rate", self.settings.frame_rate),
+        ];
+        for (key, rate) in rates {
+            // the period is the delay of an interval, it must be positive and fit Duration
+            let period = std::time::Duration::try_from_secs_f64(1.0 / rate);
+            if !period.is_ok_and(|p| !p.is_zero()) {
+                return Err(error(key, "must be a positive number per second"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Layer the effective value of the setting comes from
+    pub fn source(&self, key: &str) -> SettingSource {
+        self.sources.get(key).copied().unwrap_or_default()
+    }
+
+    /// Effective settings and keybindings as TOML, with the source of each setting and
+    /// the token masked
+    pub fn to_annotated_toml(&self) -> Result<String> {
+        let mut settings = self.settings.clone();
+        if !settings.token.is_empty() {
+            settings.token =
+                format!("{}...", &settings.token[..4.min(settings.token.len())]);
+        }
+        let table = toml::Table::try_from(&settings).into_diagnostic()?;
+        let mut out = String::new();
+        for (key, _) in SETTINGS_ENV {
+            if let Some(value) = table.get(*key) {
+                out.push_str(&format!("{} = {}  # {}\n", key, value, self.source(key)));
+            }
+        }
+        let mut mode = None;
+        for (binding_mode, chord, action) in self.keymap.bindings() {
+            if mode != Some(binding_mode) {
+                out.push_str(&format!("\n[keybindings.{}]\n", binding_mode));
+                mode = Some(binding_mode);
+            }
+            let chord = toml::Value::from(chord.to_string());
+            out.push_str(&format!("{} = \"{}\"\n", chord, action));
+        }
+        Ok(out)
+    }
+
     delegate! {
         to self.settings {
             pub fn base_url(&self) -> &str;
@@ -65,6 +510,13 @@ impl Config {
             pub fn polling_interval(&self) -> &u64;
             pub fn tick_rate(&self) -> &f64;
             pub fn frame_rate(&self) -> &f64;
+            pub fn mouse(&self) -> &bool;
+            pub fn watchlist(&self) -> &str;
+            pub fn notify_bell(&self) -> &str;
+            pub fn notify_osc(&self) -> &str;
+            pub fn notify_osc_protocol(&self) -> &str;
+            pub fn notify_banner(&self) -> &str;
+            pub fn accessible(&self) -> &bool;
         }
     }
 
@@ -116,6 +568,240 @@ impl Config {
             return &mut self.settings;
         }
         rust_i18n::set_locale(lang.as_str());
+        self.settings.locale = lang.to_string();
         &mut self.settings
     }
 }
+
+/// Language of the system locale, e.g. `uk` for `uk-UA`
+fn get_system_locale() -> String {
+    sys_locale::get_locale()
+        .and_then(|l| l.get(..2).map(str::to_lowercase))
+        .unwrap_or_else(|| "en".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::KeyLookup;
+    use clap::Parser;
+    use std::collections::HashMap;
+
+    fn parse(content: &str) -> std::result::Result<toml::Table, ConfigFileError> {
+        SettingsFile::parse(content, "config.toml").map(|(settings, ..)| settings)
+    }
+
+    #[test]
+    fn test_settings_env_covers_settings() {
+        let settings = toml::Table::try_from(Settings::init().unwrap()).unwrap();
+        let mut keys: Vec<&str> = SETTINGS_ENV.iter().map(|(key, _)| *key).collect();
+        keys.sort();
+        assert_eq!(settings.keys().collect::<Vec<_>>(), keys);
+        assert_eq!(Settings::defaults().keys().collect::<Vec<_>>(), keys);
+    }
+
+    #[test]
+    fn test_defaults() {
+        let settings: Settings = Settings::defaults().try_into().unwrap();
+        assert_eq!(settings.base_url, "https://api.alerts.in.ua");
+        assert_eq!(settings.polling_interval, 30);
+        assert_eq!(settings.locale, "en");
+        assert_eq!(settings.tick_rate, 1.0);
+        assert_eq!(settings.notify_osc_protocol, "9");
+        assert!(!settings.accessible);
+    }
+
+    #[test]
+    fn test_template_has_every_setting() {
+        let uncommented: String = CONFIG_TEMPLATE
+            .lines()
+            .filter_map(|l| {
+                l.strip_prefix("# ")
+                    .filter(|l| l.contains(" = ") || l.starts_with('['))
+            })
+            .map(|l| format!("{}\n", l))
+            .collect();
+        let table = parse(&uncommented).unwrap();
+        assert_eq!(table.len(), SETTINGS_ENV.len());
+    }
+
+    #[test]
+    fn test_invalid_file_points_at_value() {
+        let content = "tick_rate = 2.0\nbase_url = \"ftp://\"\n";
+        let error = parse(content).unwrap_err();
+        assert_eq!(error.message, "base_url must be http(s) url");
+        assert_eq!(error.span, Some(SourceSpan::from(27..35)));
+
+        let error = parse("polling_interval = \"30\"").unwrap_err();
+        assert_eq!(error.span, Some(SourceSpan::from(19..23)));
+        let error = parse("tick_rat = 2.0").unwrap_err();
+        assert!(error.message.contains("unknown field `tick_rat`"));
+        assert_eq!(error.span, Some(SourceSpan::from(0..8)));
+        assert!(parse("locale = \"de\"").is_err());
+    }
+
+    #[test]
+    fn test_keybindings() {
+        let content =
+            "[keybindings.list]\n\"<Ctrl-n>\" = \"SelectNext\"\n\"g\" = \"SelectLast\"\n";
+        let (_, keymap, ..) = SettingsFile::parse(content, "config.toml").unwrap();
+        let keys: KeyChord = "<Ctrl-n>".parse().unwrap();
+        assert_eq!(
+            keymap.lookup(&[Mode::List], keys.keys()),
+            KeyLookup::Action(Action::SelectNext)
+        );
+
+        let content =
+            "[keybindings.list]\n\"g\" = \"SelectFirst\"\n\"gx\" = \"SelectLast\"\n";
+        let error = SettingsFile::parse(content, "config.toml").unwrap_err();
+        assert_eq!(error.message, "'gx' conflicts with 'g' in list keybindings");
+        assert_eq!(error.span, Some(SourceSpan::from(39..43)));
+
+        let error = parse("[keybindings.global]\n\"g\" = \"Refresh\"").unwrap_err();
+        assert_eq!(
+            error.message,
+            "'g' in global keybindings conflicts with 'gg' in list keybindings"
+        );
+        assert_eq!(error.span, Some(SourceSpan::from(21..24)));
+
+        let error = parse("[keybindings.list]\n\"<Hyper-x>\" = \"Quit\"").unwrap_err();
+        assert_eq!(error.message, "unknown modifier 'Hyper'");
+        assert!(parse("[keybindings.list]\n\"j\" = \"Jump\"").is_err());
+        assert!(parse("[keybindings.popup]\n\"j\" = \"Quit\"").is_err());
+    }
+
+    #[test]
+    fn test_theme_colors() {
+        let content = "theme = \"colorblind\"\n[colors]\nalert = \"#ff0000\"\n";
+        let (settings, _, colors, _) = SettingsFile::parse(content, "config.toml").unwrap();
+        assert_eq!(settings["theme"].as_str(), Some("colorblind"));
+        assert_eq!(colors, [("alert".to_string(), "#ff0000".to_string())]);
+
+        let error = parse("theme = \"solarized\"").unwrap_err();
+        assert!(error.message.starts_with("theme is not available"));
+        let error = parse("[colors]\nalarm = \"red\"").unwrap_err();
+        assert_eq!(error.span, Some(SourceSpan::from(9..14)));
+        let error = parse("[colors]\nalert = \"reddish\"").unwrap_err();
+        assert_eq!(error.message, "invalid color 'reddish'");
+        assert_eq!(error.span, Some(SourceSpan::from(17..26)));
+    }
+
+    #[test]
+    fn test_hooks() {
+        let content = "[hooks]\non_alert_start = \"true\"\nlocations = [31]\n";
+        let (_, _, _, hooks) = SettingsFile::parse(content, "config.toml").unwrap();
+        let hooks = hooks.unwrap();
+        assert_eq!(hooks.on_alert_start.as_deref(), Some("true"));
+        assert_eq!(hooks.locations, vec![31]);
+        assert_eq!(hooks.max_concurrent, 4);
+
+        let error = parse("[hooks]\non_alert = \"true\"").unwrap_err();
+        assert_eq!(error.span, Some(SourceSpan::from(8..16)));
+    }
+
+    #[test]
+    fn test_load_precedence() {
+        let path = std::env::temp_dir().join(format!(
+            "ralertsinua-config-load-{}.toml",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            "tick_rate = 2.0\nframe_rate = 3.0\nnotify_osc = \"end\"\n",
+        )
+        .unwrap();
+        let env = HashMap::from([
+            ("ALERTSINUA_NOTIFY_OSC", "partial"),
+            ("ALERTSINUA_POLLING_INTERVAL_SEC", "60"),
+        ]);
+        let cli = Cli::parse_from(["ralertsinua", "--locale", "en", "--frame-rate", "10"]);
+
+        let config = Config::load_with_env(&path, &cli, |name| {
+            env.get(name).map(ToString::to_string)
+        })
+        .unwrap();
+        let invalid = Config::load_with_env(&path, &cli, |name| {
+            (name == "ALERTSINUA_MOUSE").then(|| "maybe".to_string())
+        });
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(*config.tick_rate(), 2.0);
+        assert_eq!(config.source("tick_rate"), SettingSource::File);
+        assert_eq!(*config.frame_rate(), 10.0);
+        assert_eq!(config.source("frame_rate"), SettingSource::Cli);
+        assert_eq!(config.notify_osc(), "partial");
+        assert_eq!(config.source("notify_osc"), SettingSource::Env);
+        assert_eq!(*config.polling_interval(), 60);
+        assert_eq!(config.source("notify_banner"), SettingSource::Default);
+        assert!(invalid
+            .unwrap_err()
+            .to_string()
+            .starts_with("ALERTSINUA_MOUSE is invalid"));
+    }
+
+    #[test]
+    fn test_load_rejects_zero_polling_interval() {
+        let path = std::env::temp_dir().join("ralertsinua-config-missing.toml");
+        let cli = Cli::parse_from(["ralertsinua"]);
+        let error = Config::load_with_env(&path, &cli, |name| {
+            (name == "ALERTSINUA_POLLING_INTERVAL_SEC").then(|| "0".to_string())
+        })
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "polling_interval from env must be at least 1 second"
+        );
+        assert!(Cli::try_parse_from(["ralertsinua", "watch", "--interval", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_load_validates_layered_settings() {
+        let path = std::env::temp_dir().join(format!(
+            "ralertsinua-config-rates-{}.toml",
+            std::process::id()
+        ));
+        std::fs::write(&path, "polling_interval = 0\n").unwrap();
+        let load = |args: &[&str], env: &[(&str, &str)]| {
+            let cli = Cli::parse_from([&["ralertsinua"], args].concat());
+            let env: HashMap<&str, &str> = env.iter().copied().collect();
+            Config::load_with_env(&path, &cli, |name| env.get(name).map(|v| v.to_string()))
+                .map_err(|e| e.to_string())
+        };
+        let file = load(&[], &[]);
+        let env = load(
+            &[],
+            &[
+                ("ALERTSINUA_POLLING_INTERVAL_SEC", "10"),
+                ("TICK_RATE", "0"),
+            ],
+        );
+        let infinite = load(
+            &["--frame-rate", "inf"],
+            &[("ALERTSINUA_POLLING_INTERVAL_SEC", "10")],
+        );
+        let negative = load(
+            &["--frame-rate=-1"],
+            &[("ALERTSINUA_POLLING_INTERVAL_SEC", "10")],
+        );
+        let valid = load(
+            &["--tick-rate", "4"],
+            &[("ALERTSINUA_POLLING_INTERVAL_SEC", "10")],
+        );
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(
+            file.unwrap_err(),
+            "polling_interval from file must be at least 1 second"
+        );
+        assert_eq!(
+            env.unwrap_err(),
+            "tick_rate from env must be a positive number per second"
+        );
+        assert_eq!(
+            infinite.unwrap_err(),
+            "frame_rate from cli must be a positive number per second"
+        );
+        assert!(negative.is_err());
+        assert_eq!(*valid.unwrap().tick_rate(), 4.0);
+    }
+}
@@ -20,6 +20,10 @@ pub enum AppError {
     // Decode(#[from] rmp_serde::decode::Error),
     #[error("json parse error: {0}")]
     ParseJson(#[from] serde_json::Error),
+    #[error("export error: {0}")]
+    Export(#[from] ralertsinua_models::ModelError),
+    #[error("alert store error: {0}")]
+    Store(#[from] rusqlite::Error),
     #[error("component error")]
     #[diagnostic(code(ralertsinua::component))]
     ComponentError,
@@ -29,3 +33,32 @@ pub enum AppError {
     #[error("unknown error")]
     Unknown,
 }
+
+/// Invalid config file, the span points at the offending key or value
+#[derive(Error, Diagnostic, Debug)]
+#[error("invalid config file: {message}")]
+#[diagnostic(
+    code(ralertsinua::config),
+    help("run `ralertsinua config init` for a template with every setting")
+)]
+pub struct ConfigFileError {
+    pub message: String,
+    #[source_code]
+    pub src: miette::NamedSource<String>,
+    #[label("here")]
+    pub span: Option<SourceSpan>,
+}
+
+/// Invalid effective setting, checked once the config file, environment and flags are
+/// layered
+#[derive(Error, Diagnostic, Debug)]
+#[error("{key} from {origin} {message}")]
+#[diagnostic(
+    code(ralertsinua::config),
+    help("run `ralertsinua config show` for the layer every setting comes from")
+)]
+pub struct SettingError {
+    pub key: &'static str,
+    pub origin: String,
+    pub message: String,
+}
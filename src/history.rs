@@ -0,0 +1,242 @@
+use ralertsinua_models::Alerts;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+use time::{Date, OffsetDateTime, Time};
+
+/// The history endpoint allows 2 requests per minute
+pub const HISTORY_REQUESTS_PER_MINUTE: usize = 2;
+/// How long the history of a location is shown without asking the API again
+pub const HISTORY_TTL: Duration = Duration::from_secs(600);
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+/// Alerts history by location uid, with the rate limit of the endpoint
+#[derive(Debug, Default)]
+pub struct HistoryCache {
+    entries: HashMap<i32, (Instant, Alerts)>,
+    /// Times of the requests made within the last minute
+    requests: VecDeque<Instant>,
+    /// Location to fetch once the limit allows, the latest one asked for
+    pending: Option<i32>,
+    /// A retry of the pending location is scheduled
+    retry_scheduled: bool,
+}
+
+impl HistoryCache {
+    /// History of the location, if it was fetched within `HISTORY_TTL`
+    pub fn get(&self, uid: i32, now: Instant) -> Option<&Alerts> {
+        self.entries
+            .get(&uid)
+            .filter(|(fetched_at, _)| now.duration_since(*fetched_at) < HISTORY_TTL)
+            .map(|(_, alerts)| alerts)
+    }
+
+    /// History of the location fetched last, however old
+    pub fn get_stale(&self, uid: i32) -> Option<&Alerts> {
+        self.entries.get(&uid).map(|(_, alerts)| alerts)
+    }
+
+    pub fn insert(&mut self, uid: i32, alerts: Alerts, now: Instant) {
+        self.entries.insert(uid, (now, alerts));
+    }
+
+    /// Take a request from the limit, or return how long to wait for one
+    pub fn acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        while let Some(&oldest) = self.requests.front() {
+            if now.duration_since(oldest) < MINUTE {
+                break;
+            }
+            self.requests.pop_front();
+        }
+        if self.requests.len() >= HISTORY_REQUESTS_PER_MINUTE {
+            let oldest = self.requests.front().copied().unwrap_or(now);
+            return Err(MINUTE - now.duration_since(oldest));
+        }
+        self.requests.push_back(now);
+        Ok(())
+    }
+
+    /// Fetch the location once the limit allows, instead of the one deferred before.
+    /// Returns whether a retry has to be scheduled, none is yet
+    pub fn defer(&mut self, uid: i32) -> bool {
+        self.pending = Some(uid);
+        !std::mem::replace(&mut self.retry_scheduled, true)
+    }
+
+    /// Location deferred last, when the scheduled retry is due
+    pub fn take_pending(&mut self) -> Option<i32> {
+        self.retry_scheduled = false;
+        self.pending.take()
+    }
+
+    /// Forget the deferred location, a later one was shown already
+    pub fn cancel_pending(&mut self) {
+        self.pending = None;
+    }
+}
+
+/// Alerts of a single day (UTC), clipped to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryDay {
+    pub date: Date,
+    /// Alerts started that day
+    pub count: usize,
+    /// Parts of the day with an alert, as fractions of the day from 0 to 1
+    pub intervals: Vec<(f64, f64)>,
+}
+
+impl HistoryDay {
+    /// Whether each of `n` equal parts of the day has an alert
+    pub fn slots(&self, n: usize) -> Vec<bool> {
+        (0..n)
+            .map(|i| {
+                let (from, to) = (i as f64 / n as f64, (i + 1) as f64 / n as f64);
+                self.intervals.iter().any(|&(a, b)| a < to && b > from)
+            })
+            .collect()
+    }
+}
+
+/// Alerts history of a location aggregated by day
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistorySummary {
+    /// The last days up to today, the oldest first
+    pub days: Vec<HistoryDay>,
+    pub count: usize,
+    pub total: Duration,
+    pub average: Duration,
+}
+
+impl HistorySummary {
+    pub fn new(alerts: &Alerts, now: OffsetDateTime, days: usize) -> Self {
+        let now = now.to_offset(time::UtcOffset::UTC);
+        let day = time::Duration::DAY;
+        let today = now.date();
+        let days: Vec<HistoryDay> = (0..days)
+            .rev()
+            .filter_map(|i| today.checked_sub(day * i as u32))
+            .map(|date| {
+                let start = date.with_time(Time::MIDNIGHT).assume_utc();
+                let end = start + day;
+                let mut count = 0;
+                let intervals = alerts
+                    .iter()
+                    .map(|a| a.get_interval(now))
+                    .filter(|&(from, to)| from < end && to > start)
+                    .map(|(from, to)| {
+                        if from >= start {
+                            count += 1;
+                        }
+                        let fraction =
+                            |t: OffsetDateTime| (t.clamp(start, end) - start) / day;
+                        (fraction(from), fraction(to))
+                    })
+                    .collect();
+                HistoryDay {
+                    date,
+                    count,
+                    intervals,
+                }
+            })
+            .collect();
+        let durations: Vec<Duration> = alerts
+            .iter()
+            .map(|a| a.get_interval(now))
+            .map(|(from, to)| (to - from).try_into().unwrap_or_default())
+            .collect();
+        let total: Duration = durations.iter().sum();
+        let average = match durations.len() {
+            0 => Duration::ZERO,
+            n => total / n as u32,
+        };
+        Self {
+            days,
+            count: durations.len(),
+            total,
+            average,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ralertsinua_models::{
+        test_util::{at, AlertBuilder, HOUR},
+        Alert, LocationType,
+    };
+
+    fn alert(id: i32, from_hours: i64, to_hours: Option<i64>) -> Alert {
+        AlertBuilder::new(id)
+            .location(31, 31)
+            .title("м. Київ")
+            .location_type(LocationType::City)
+            .hours(from_hours, to_hours)
+            .build()
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let mut cache = HistoryCache::default();
+        let now = Instant::now();
+        assert_eq!(cache.acquire(now), Ok(()));
+        assert_eq!(cache.acquire(now + Duration::from_secs(10)), Ok(()));
+        assert_eq!(
+            cache.acquire(now + Duration::from_secs(20)),
+            Err(Duration::from_secs(40))
+        );
+        assert_eq!(cache.acquire(now + Duration::from_secs(60)), Ok(()));
+
+        cache.insert(31, Alerts::default(), now);
+        assert!(cache.get(31, now + Duration::from_secs(60)).is_some());
+        assert!(cache.get(31, now + HISTORY_TTL).is_none());
+        assert!(cache.get_stale(31).is_some());
+        assert!(cache.get(14, now).is_none());
+    }
+
+    #[test]
+    fn test_defer() {
+        let mut cache = HistoryCache::default();
+        assert!(cache.defer(31));
+        // the retry scheduled for 31 fetches 14
+        assert!(!cache.defer(14));
+        assert_eq!(cache.take_pending(), Some(14));
+        assert_eq!(cache.take_pending(), None);
+
+        assert!(cache.defer(31));
+        cache.cancel_pending();
+        assert_eq!(cache.take_pending(), None);
+        assert!(cache.defer(14));
+    }
+
+    #[test]
+    fn test_summary() {
+        // 22:00 of the day before until 02:00, 12:00 - 13:00, 23:00 until now
+        let alerts = Alerts::new(vec![
+            alert(1, -2, Some(2)),
+            alert(2, 12, Some(13)),
+            alert(3, 23, None),
+        ]);
+        let now = at(25);
+        let summary = HistorySummary::new(&alerts, now, 3);
+
+        let counts: Vec<usize> = summary.days.iter().map(|d| d.count).collect();
+        assert_eq!(counts, vec![1, 2, 0]);
+        let day = &summary.days[1];
+        assert_eq!(day.date.to_string(), "2024-05-05");
+        assert_eq!(day.count, 2);
+        assert_eq!(day.intervals.len(), 3);
+        assert_eq!(
+            day.slots(4),
+            vec![true, false, true, true],
+            "0-6h, 12-18h and 18-24h have alerts"
+        );
+        assert_eq!(summary.days[2].slots(24)[..2], [true, false]);
+
+        assert_eq!(summary.count, 3);
+        assert_eq!(summary.total, Duration::from_secs(7 * HOUR as u64));
+        assert_eq!(summary.average, Duration::from_secs(7 * HOUR as u64 / 3));
+    }
+}
@@ -0,0 +1,359 @@
+use ralertsinua_models::*;
+use serde::{de::Error as _, Deserialize, Deserializer};
+use std::{
+    collections::HashMap,
+    process::{ExitStatus, Stdio},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tokio::{process::Command, sync::Semaphore, task::JoinHandle};
+use tracing::{debug, error, info, warn};
+
+use crate::transitions::TransitionKind;
+
+/// Shell the hook commands run in
+#[cfg(not(windows))]
+const SHELL: [&str; 2] = ["sh", "-c"];
+#[cfg(windows)]
+const SHELL: [&str; 2] = ["cmd", "/C"];
+
+fn default_timeout_sec() -> f64 {
+    30.0
+}
+
+/// Timeout must fit into [`Duration`], e.g. `inf` which TOML accepts does not
+fn deserialize_timeout_sec<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let seconds = f64::deserialize(deserializer)?;
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| D::Error::custom("timeout_sec must be a finite number of seconds"))?;
+    Ok(seconds)
+}
+
+fn default_max_concurrent() -> usize {
+    4
+}
+
+/// Shell hooks run on alert transitions, the `[hooks]` table of the config file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HooksConfig {
+    pub on_alert_start: Option<String>,
+    pub on_alert_end: Option<String>,
+    /// Hook is killed after the timeout
+    #[serde(
+        default = "default_timeout_sec",
+        deserialize_with = "deserialize_timeout_sec"
+    )]
+    pub timeout_sec: f64,
+    /// Maximum number of hooks running at once, others wait for their turn
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: usize,
+    /// Location uids to run hooks for, all when empty
+    #[serde(default)]
+    pub locations: Vec<i32>,
+}
+
+/// Alert transition of a location, passed to the hook in environment variables
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookEvent {
+    pub kind: TransitionKind,
+    pub alert_type: AlertType,
+    pub location_uid: i32,
+    pub location_title: String,
+    pub location_title_en: String,
+    pub started_at: Option<OffsetDateTime>,
+    pub observed_at: OffsetDateTime,
+}
+
+impl HookEvent {
+    /// Event for the start or end of the air raid alert in the oblast, the start time is
+    /// taken from active alerts if available
+    pub fn from_change(
+        change: &AirRaidAlertOblastStatusChange,
+        alerts: Option<&Alerts>,
+    ) -> Option<Self> {
+        let kind = TransitionKind::of(change)?;
+        let started_at = alerts.and_then(|alerts| {
+            alerts
+                .iter()
+                .filter(|a| {
+                    a.location_uid == change.location_uid
+                        && a.alert_type == AlertType::AirRaid
+                })
+                .map(|a| a.started_at)
+                .min()
+        });
+        Some(Self {
+            kind,
+            alert_type: AlertType::AirRaid,
+            location_uid: change.location_uid,
+            location_title: change.location_title().to_string(),
+            location_title_en: change.location_title_en().to_string(),
+            started_at,
+            observed_at: OffsetDateTime::now_utc(),
+        })
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.started_at
+            .and_then(|started_at| (self.observed_at - started_at).try_into().ok())
+    }
+
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("ALERTSINUA_EVENT", self.kind.to_string()),
+            ("ALERTSINUA_ALERT_TYPE", self.alert_type.to_string()),
+            ("ALERTSINUA_UID", self.location_uid.to_string()),
+            ("ALERTSINUA_TITLE", self.location_title.clone()),
+            ("ALERTSINUA_TITLE_EN", self.location_title_en.clone()),
+            (
+                "ALERTSINUA_STARTED_AT",
+                self.started_at
+                    .and_then(|t| t.format(&Rfc3339).ok())
+                    .unwrap_or_default(),
+            ),
+            (
+                "ALERTSINUA_DURATION_SEC",
+                self.duration()
+                    .map(|d| d.as_secs().to_string())
+                    .unwrap_or_default(),
+            ),
+        ]
+    }
+}
+
+/// Runs hooks with a timeout and a limit of concurrently running ones
+#[derive(Debug, Clone)]
+pub struct HookRunner {
+    config: HooksConfig,
+    permits: Arc<Semaphore>,
+    /// Start of the alerts seen by the runner, for the duration when the API has none
+    started: Arc<Mutex<HashMap<i32, OffsetDateTime>>>,
+}
+
+impl HookRunner {
+    pub fn new(config: HooksConfig) -> Self {
+        let permits = Arc::new(Semaphore::new(config.max_concurrent.max(1)));
+        Self {
+            config,
+            permits,
+            started: Arc::default(),
+        }
+    }
+
+    fn command(&self, kind: TransitionKind) -> Option<&str> {
+        match kind {
+            TransitionKind::Started => self.config.on_alert_start.as_deref(),
+            TransitionKind::Ended => self.config.on_alert_end.as_deref(),
+        }
+    }
+
+    /// Fill in the start of the alert from the previously seen start event
+    fn track(&self, event: &mut HookEvent) {
+        let Ok(mut started) = self.started.lock() else {
+            return;
+        };
+        match event.kind {
+            TransitionKind::Started => {
+                let started_at = *event.started_at.get_or_insert(event.observed_at);
+                started.insert(event.location_uid, started_at);
+            }
+            TransitionKind::Ended => {
+                let seen = started.remove(&event.location_uid);
+                event.started_at = event.started_at.or(seen);
+            }
+        }
+    }
+
+    /// Spawn the hook configured for the event, if any
+    pub fn run(&self, mut event: HookEvent) -> Option<JoinHandle<Option<ExitStatus>>> {
+        let locations = &self.config.locations;
+        if !locations.is_empty() && !locations.contains(&event.location_uid) {
+            return None;
+        }
+        self.track(&mut event);
+        let command = self.command(event.kind)?.to_string();
+        let permits = self.permits.clone();
+        let timeout = Duration::try_from_secs_f64(self.config.timeout_sec)
+            .unwrap_or_else(|_| Duration::from_secs_f64(default_timeout_sec()));
+        Some(tokio::spawn(async move {
+            let _permit = permits.acquire_owned().await.ok()?;
+            execute(&command, &event, timeout).await
+        }))
+    }
+}
+
+/// Run the command in the shell, `cmd` on Windows, it is killed if it does not finish
+/// in time
+async fn execute(
+    command: &str,
+    event: &HookEvent,
+    timeout: Duration,
+) -> Option<ExitStatus> {
+    debug!(target: "app", "running hook '{}' for {} {}", command, event.kind, event.location_uid);
+    let child = Command::new(SHELL[0])
+        .arg(SHELL[1])
+        .arg(command)
+        .envs(event.env())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+    let child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            error!(target: "app", "failed to run hook '{}': {}", command, e);
+            return None;
+        }
+    };
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) if output.status.success() => {
+            info!(target: "app", "hook '{}' finished", command);
+            Some(output.status)
+        }
+        Ok(Ok(output)) => {
+            warn!(
+                target: "app",
+                "hook '{}' failed with {}: {}",
+                command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            Some(output.status)
+        }
+        Ok(Err(e)) => {
+            error!(target: "app", "hook '{}' failed: {}", command, e);
+            None
+        }
+        Err(_) => {
+            warn!(target: "app", "hook '{}' timed out after {:?}, killed", command, timeout);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn config(on_alert_start: &str) -> HooksConfig {
+        toml::from_str(&format!("on_alert_start = {:?}", on_alert_start)).unwrap()
+    }
+
+    fn event(kind: TransitionKind) -> HookEvent {
+        HookEvent {
+            kind,
+            alert_type: AlertType::AirRaid,
+            location_uid: 31,
+            location_title: "м. Київ".to_string(),
+            location_title_en: "Kyiv".to_string(),
+            started_at: None,
+            observed_at: OffsetDateTime::now_utc(),
+        }
+    }
+
+    #[test]
+    fn test_from_change() {
+        let previous = AirRaidAlertOblastStatuses::new(
+            "ANNNNNNNNNNNNNNNNNNNNNNNNNN".to_string(),
+            Some(true),
+        );
+        let current = AirRaidAlertOblastStatuses::new(
+            "NNNNNNNNNNNNNNNNNNNNNNNNNNN".to_string(),
+            Some(true),
+        );
+        let change = &current.diff(&previous)[0];
+
+        let event = HookEvent::from_change(change, None).unwrap();
+
+        assert_eq!(event.kind, TransitionKind::Ended);
+        assert_eq!(event.location_title_en, "Autonomous Republic of Crimea");
+        assert!(event.duration().is_none());
+    }
+
+    // commands of the test are in sh syntax
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_with_env() {
+        let path =
+            std::env::temp_dir().join(format!("ralertsinua-hook-{}", std::process::id()));
+        let command = format!(
+            r#"echo "$ALERTSINUA_EVENT $ALERTSINUA_UID $ALERTSINUA_TITLE_EN $ALERTSINUA_ALERT_TYPE" > {}"#,
+            path.display()
+        );
+        let runner = HookRunner::new(config(&command));
+
+        let status = runner
+            .run(event(TransitionKind::Started))
+            .unwrap()
+            .await
+            .unwrap();
+
+        assert!(status.unwrap().success());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "started 31 Kyiv air_raid\n"
+        );
+        std::fs::remove_file(path).ok();
+        // no hook for the end configured
+        assert!(runner.run(event(TransitionKind::Ended)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_duration_from_tracked_start() {
+        let runner = HookRunner::new(config("true"));
+        let mut start = event(TransitionKind::Started);
+        runner.track(&mut start);
+        let mut end = event(TransitionKind::Ended);
+        end.observed_at = start.observed_at + Duration::from_secs(90);
+        runner.track(&mut end);
+
+        assert_eq!(end.duration(), Some(Duration::from_secs(90)));
+        assert!(end
+            .env()
+            .contains(&("ALERTSINUA_DURATION_SEC", "90".to_string())));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout_and_concurrency() {
+        let mut hooks = config("sleep 5");
+        hooks.timeout_sec = 0.2;
+        hooks.max_concurrent = 1;
+        let runner = HookRunner::new(hooks);
+        let started = Instant::now();
+
+        let first = runner.run(event(TransitionKind::Started)).unwrap();
+        let second = runner.run(event(TransitionKind::Started)).unwrap();
+
+        assert!(first.await.unwrap().is_none());
+        assert!(second.await.unwrap().is_none());
+        // the second hook waited for the first one to be killed
+        assert!(started.elapsed() >= Duration::from_millis(400));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_timeout_must_fit_duration() {
+        let parse =
+            |timeout| toml::from_str::<HooksConfig>(&format!("timeout_sec = {}", timeout));
+        assert_eq!(parse("0.5").unwrap().timeout_sec, 0.5);
+        assert!(parse("inf").is_err());
+        assert!(parse("1e300").is_err());
+        assert!(parse("-1.0").is_err());
+    }
+
+    #[test]
+    fn test_locations_filter() {
+        let mut hooks = config("true");
+        hooks.locations = vec![14];
+        let runner = HookRunner::new(hooks);
+        assert!(runner.run(event(TransitionKind::Started)).is_none());
+    }
+}
@@ -0,0 +1,433 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::{action::Action, mode::Mode};
+
+/// Default bindings, vim-style keys next to the arrows
+const DEFAULT_BINDINGS: &[(Mode, &str, Action)] = &[
+    (Mode::Global, "q", Action::Quit),
+    (Mode::Global, "<Ctrl-c>", Action::Quit),
+    (Mode::Global, "z", Action::Suspend),
+    (Mode::Global, "r", Action::Refresh),
+    (Mode::Global, "l", Action::Locale),
+    (Mode::Global, "?", Action::Help),
+    (Mode::Global, "<Esc>", Action::Cancel),
+    (Mode::Global, "<Right>", Action::NextTab),
+    (Mode::Global, "<Tab>", Action::NextTab),
+    (Mode::Global, "<Left>", Action::PreviousTab),
+    (Mode::Global, "<BackTab>", Action::PreviousTab),
+    (Mode::Global, "<Ctrl-s>", Action::SaveSnapshot),
+    (Mode::List, "<Down>", Action::SelectNext),
+    (Mode::List, "j", Action::SelectNext),
+    (Mode::List, "<Up>", Action::SelectPrevious),
+    (Mode::List, "k", Action::SelectPrevious),
+    (Mode::List, "gg", Action::SelectFirst),
+    (Mode::List, "<Home>", Action::SelectFirst),
+    (Mode::List, "G", Action::SelectLast),
+    (Mode::List, "<End>", Action::SelectLast),
+    (Mode::Map, "+", Action::ZoomIn),
+    (Mode::Map, "=", Action::ZoomIn),
+    (Mode::Map, "-", Action::ZoomOut),
+    (Mode::Map, "H", Action::PanLeft),
+    (Mode::Map, "<Shift-Left>", Action::PanLeft),
+    (Mode::Map, "L", Action::PanRight),
+    (Mode::Map, "<Shift-Right>", Action::PanRight),
+    (Mode::Map, "K", Action::PanUp),
+    (Mode::Map, "<Shift-Up>", Action::PanUp),
+    (Mode::Map, "J", Action::PanDown),
+    (Mode::Map, "<Shift-Down>", Action::PanDown),
+    (Mode::Map, "f", Action::FocusSelected),
+    (Mode::Map, "0", Action::ResetZoom),
+    (Mode::Table, "<Down>", Action::SelectNext),
+    (Mode::Table, "j", Action::SelectNext),
+    (Mode::Table, "<Up>", Action::SelectPrevious),
+    (Mode::Table, "k", Action::SelectPrevious),
+    (Mode::Table, "gg", Action::SelectFirst),
+    (Mode::Table, "<Home>", Action::SelectFirst),
+    (Mode::Table, "G", Action::SelectLast),
+    (Mode::Table, "<End>", Action::SelectLast),
+    (Mode::Table, "s", Action::SortNext),
+    (Mode::Table, "S", Action::SortReverse),
+    (Mode::Table, "t", Action::FilterAlertType),
+    (Mode::Table, "o", Action::FilterOblast),
+    (Mode::Table, "/", Action::Search),
+    (Mode::Table, "<Enter>", Action::ShowOnMap),
+    (Mode::History, "<Enter>", Action::LoadHistory),
+    (Mode::Search, "<Enter>", Action::SearchDone),
+    (Mode::Search, "<Esc>", Action::Cancel),
+    (Mode::Search, "<Ctrl-c>", Action::Quit),
+    (Mode::Help, "?", Action::Help),
+    (Mode::Help, "<Esc>", Action::Help),
+    (Mode::Help, "q", Action::Help),
+    (Mode::Help, "<Ctrl-c>", Action::Quit),
+];
+
+/// Named keys, written in angle brackets, e.g. `<Esc>`
+const KEY_NAMES: &[(&str, KeyCode)] = &[
+    ("Esc", KeyCode::Esc),
+    ("Enter", KeyCode::Enter),
+    ("Tab", KeyCode::Tab),
+    ("BackTab", KeyCode::BackTab),
+    ("Backspace", KeyCode::Backspace),
+    ("Delete", KeyCode::Delete),
+    ("Insert", KeyCode::Insert),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Space", KeyCode::Char(' ')),
+    ("lt", KeyCode::Char('<')),
+];
+
+/// Single key press, modifiers are normalized so `G` and `<Shift-g>` are the same key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let (code, modifiers) = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => (
+                KeyCode::Char(c.to_ascii_uppercase()),
+                modifiers - KeyModifiers::SHIFT,
+            ),
+            KeyCode::BackTab => (code, modifiers - KeyModifiers::SHIFT),
+            _ => (code, modifiers),
+        };
+        Self { code, modifiers }
+    }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    /// Parse a character or the contents of angle brackets, e.g. `Ctrl-c` or `F5`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Self::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        let mut parts: Vec<&str> = s.split('-').collect();
+        // `Ctrl--` binds the minus key
+        if s.ends_with("--") {
+            parts.truncate(parts.len() - 2);
+            parts.push("-");
+        }
+        let name = parts.pop().unwrap_or_default();
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in parts {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "c" => KeyModifiers::CONTROL,
+                "alt" | "a" | "m" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{}'", modifier)),
+            };
+        }
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => KEY_NAMES
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, code)| *code)
+                .or_else(|| {
+                    let n = name.strip_prefix(['F', 'f'])?.parse().ok()?;
+                    (1..=12).contains(&n).then_some(KeyCode::F(n))
+                })
+                .ok_or_else(|| format!("unknown key '{}'", name))?,
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.code {
+            KeyCode::F(n) => format!("F{}", n),
+            KeyCode::Char(c) => KEY_NAMES
+                .iter()
+                .find(|(_, code)| *code == self.code)
+                .map_or_else(|| c.to_string(), |(n, _)| n.to_string()),
+            code => KEY_NAMES
+                .iter()
+                .find(|(_, c)| *c == code)
+                .map_or_else(|| format!("{:?}", code), |(n, _)| n.to_string()),
+        };
+        let plain_char = matches!(self.code, KeyCode::Char(_)) && name.chars().count() == 1;
+        if self.modifiers.is_empty() && plain_char {
+            return write!(f, "{}", name);
+        }
+        write!(f, "<")?;
+        for (modifier, prefix) in [
+            (KeyModifiers::CONTROL, "Ctrl-"),
+            (KeyModifiers::ALT, "Alt-"),
+            (KeyModifiers::SHIFT, "Shift-"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}", prefix)?;
+            }
+        }
+        write!(f, "{}>", name)
+    }
+}
+
+/// Keys pressed one after another, e.g. `gg` or `<Ctrl-c>`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyChord(pub Vec<Key>);
+
+impl KeyChord {
+    pub fn keys(&self) -> &[Key] {
+        &self.0
+    }
+
+    /// Whether pressing this chord also completes the start of the other one
+    pub fn overlaps(&self, other: &KeyChord) -> bool {
+        self.0.starts_with(&other.0) || other.0.starts_with(&self.0)
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut keys = Vec::new();
+        let mut rest = s;
+        while let Some(c) = rest.chars().next() {
+            let token = match (c, rest.find('>')) {
+                ('<', Some(end)) if end > 1 => {
+                    let token = &rest[1..end];
+                    rest = &rest[end + 1..];
+                    token
+                }
+                _ => {
+                    let token = &rest[..c.len_utf8()];
+                    rest = &rest[c.len_utf8()..];
+                    token
+                }
+            };
+            keys.push(token.parse()?);
+        }
+        if keys.is_empty() {
+            return Err("key chord is empty".to_string());
+        }
+        Ok(Self(keys))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|key| write!(f, "{}", key))
+    }
+}
+
+/// First pair of overlapping chords, as the index of the later one and the earlier one
+pub fn find_conflict(chords: &[&KeyChord]) -> Option<(usize, usize)> {
+    chords.iter().enumerate().find_map(|(i, chord)| {
+        chords[..i]
+            .iter()
+            .position(|other| other.overlaps(chord))
+            .map(|j| (i, j))
+    })
+}
+
+/// Result of looking up the keys pressed so far
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyLookup {
+    Action(Action),
+    /// Keys are the start of a longer chord, wait for the next key
+    Pending,
+    None,
+}
+
+/// Key chords mapped to actions per mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: HashMap<Mode, Vec<(KeyChord, Action)>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut keymap = Self {
+            bindings: HashMap::new(),
+        };
+        for (mode, chord, action) in DEFAULT_BINDINGS {
+            let chord = chord.parse().expect("default key chord is valid");
+            keymap.bind(*mode, chord, action.clone());
+        }
+        keymap
+    }
+}
+
+impl Keymap {
+    /// Bind the chord, replacing bindings of the mode it overlaps with.
+    /// Returns the replaced bindings
+    pub fn bind(
+        &mut self,
+        mode: Mode,
+        chord: KeyChord,
+        action: Action,
+    ) -> Vec<(KeyChord, Action)> {
+        let bindings = self.bindings.entry(mode).or_default();
+        let (replaced, kept) = bindings.drain(..).partition(|(c, _)| c.overlaps(&chord));
+        *bindings = kept;
+        bindings.push((chord, action));
+        replaced
+    }
+
+    /// Action bound to the keys in the first of the modes which knows them
+    pub fn lookup(&self, modes: &[Mode], keys: &[Key]) -> KeyLookup {
+        for mode in modes {
+            let Some(bindings) = self.bindings.get(mode) else {
+                continue;
+            };
+            if let Some((_, action)) = bindings.iter().find(|(c, _)| c.keys() == keys) {
+                return KeyLookup::Action(action.clone());
+            }
+            if bindings.iter().any(|(c, _)| c.keys().starts_with(keys)) {
+                return KeyLookup::Pending;
+            }
+        }
+        KeyLookup::None
+    }
+
+    /// Binding shadowed across modes: a chord of a mode overlapping with, but not equal to,
+    /// a global one, e.g. `gg` in the list and `g` globally. The other mode and its chord
+    pub fn find_global_conflict(
+        &self,
+        mode: Mode,
+        chord: &KeyChord,
+    ) -> Option<(Mode, &KeyChord)> {
+        self.bindings
+            .iter()
+            .filter(|(other, _)| (mode == Mode::Global) != (**other == Mode::Global))
+            .flat_map(|(other, b)| b.iter().map(move |(c, _)| (*other, c)))
+            .filter(|(_, c)| *c != chord && c.overlaps(chord))
+            .min_by_key(|(other, c)| (*other, c.to_string()))
+    }
+
+    /// Chords bound to the action in the mode
+    pub fn chords(&self, mode: Mode, action: &Action) -> Vec<&KeyChord> {
+        self.bindings
+            .get(&mode)
+            .into_iter()
+            .flatten()
+            .filter(|(_, a)| a == action)
+            .map(|(c, _)| c)
+            .collect()
+    }
+
+    /// Every binding, ordered by mode and action, for the help
+    pub fn bindings(&self) -> Vec<(Mode, &KeyChord, &Action)> {
+        let mut bindings: Vec<_> = self
+            .bindings
+            .iter()
+            .flat_map(|(mode, b)| b.iter().map(move |(c, a)| (*mode, c, a)))
+            .collect();
+        bindings.sort_by_key(|(mode, chord, action)| {
+            (*mode, action.to_string(), chord.to_string())
+        });
+        bindings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(chord: &str) -> Vec<Key> {
+        chord.parse::<KeyChord>().unwrap().0
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for chord in [
+            "q", "gg", "<Ctrl-c>", "<Esc>", "G", "<F5>", "<Space>", "<lt>", "?",
+        ] {
+            assert_eq!(chord.parse::<KeyChord>().unwrap().to_string(), chord);
+        }
+        assert_eq!(keys("<Shift-g>"), keys("G"));
+        assert_eq!(keys("<ctrl-C>"), keys("<C-C>"));
+        assert_eq!(keys("<Ctrl-->")[0].code, KeyCode::Char('-'));
+        assert!("<Hyper-x>".parse::<KeyChord>().is_err());
+        assert!("<Unknown>".parse::<KeyChord>().is_err());
+        assert!("".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn test_key_from_event() {
+        let event = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(Key::from(event), keys("G")[0]);
+        let event = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+        assert_eq!(Key::from(event), keys("<BackTab>")[0]);
+    }
+
+    #[test]
+    fn test_lookup() {
+        let keymap = Keymap::default();
+        let modes = [Mode::List, Mode::Global];
+        assert_eq!(
+            keymap.lookup(&modes, &keys("j")),
+            KeyLookup::Action(Action::SelectNext)
+        );
+        assert_eq!(keymap.lookup(&modes, &keys("g")), KeyLookup::Pending);
+        assert_eq!(
+            keymap.lookup(&modes, &keys("gg")),
+            KeyLookup::Action(Action::SelectFirst)
+        );
+        assert_eq!(
+            keymap.lookup(&modes, &keys("q")),
+            KeyLookup::Action(Action::Quit)
+        );
+        assert_eq!(keymap.lookup(&modes, &keys("x")), KeyLookup::None);
+        // list bindings are not active outside of the list
+        assert_eq!(keymap.lookup(&[Mode::Global], &keys("j")), KeyLookup::None);
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let chords: Vec<KeyChord> = ["j", "g", "<Ctrl-x>", "gx"]
+            .iter()
+            .map(|c| c.parse().unwrap())
+            .collect();
+        let chords: Vec<&KeyChord> = chords.iter().collect();
+        assert_eq!(find_conflict(&chords), Some((3, 1)));
+        assert_eq!(find_conflict(&chords[..3]), None);
+
+        // default `gg` is replaced by the overlapping `g`
+        let mut keymap = Keymap::default();
+        let replaced = keymap.bind(Mode::List, chords[1].clone(), Action::SelectLast);
+        assert_eq!(replaced, vec![("gg".parse().unwrap(), Action::SelectFirst)]);
+        let modes = [Mode::List];
+        assert_eq!(
+            keymap.lookup(&modes, &keys("g")),
+            KeyLookup::Action(Action::SelectLast)
+        );
+        assert_eq!(keymap.lookup(&modes, &keys("gg")), KeyLookup::None);
+
+        // global `r` is unreachable in the list with `rr` bound there
+        let rr: KeyChord = "rr".parse().unwrap();
+        keymap.bind(Mode::List, rr.clone(), Action::Refresh);
+        let r: KeyChord = "r".parse().unwrap();
+        assert_eq!(
+            keymap.find_global_conflict(Mode::List, &rr),
+            Some((Mode::Global, &r))
+        );
+        assert_eq!(
+            keymap.find_global_conflict(Mode::Global, &r),
+            Some((Mode::List, &rr))
+        );
+        assert_eq!(keymap.find_global_conflict(Mode::List, chords[1]), None);
+    }
+}
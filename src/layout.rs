@@ -2,15 +2,21 @@ use ratatui::prelude::*;
 use rust_i18n::t;
 use strum::{Display, EnumIter, FromRepr, IntoEnumIterator};
 
-use crate::constants::*;
+use crate::theme::Theme;
 
 #[derive(Debug, Default, Clone, Copy, Display, FromRepr, EnumIter, PartialEq)]
 pub enum LayoutTab {
     #[default]
     #[strum(to_string = "tabs.home")]
     Tab1 = 0,
-    #[strum(to_string = "tabs.logger")]
+    #[strum(to_string = "tabs.alerts")]
     Tab2 = 1,
+    #[strum(to_string = "tabs.history")]
+    Tab3 = 2,
+    #[strum(to_string = "tabs.stats")]
+    Tab4 = 3,
+    #[strum(to_string = "tabs.logger")]
+    Tab5 = 4,
 }
 
 impl LayoutTab {
@@ -31,10 +37,10 @@ impl LayoutTab {
         Self::from_repr(next_index).unwrap_or(self)
     }
     /// Return tab's name as a styled `Line`
-    pub fn title(self) -> Line<'static> {
+    pub fn title(self, theme: &Theme) -> Line<'static> {
         t!(&self.to_string())
             .to_string()
-            .fg(*DEFAULT_COLOR)
+            .fg(theme.text)
             // .bg(self.palette().c900)
             .into()
     }
@@ -3,16 +3,30 @@
 pub mod action;
 pub mod app;
 pub mod cli;
+pub mod commands;
 pub mod components;
 pub mod config;
 pub mod constants;
 pub mod error;
 pub mod fs;
+pub mod history;
+pub mod hooks;
+pub mod keymap;
 pub mod layout;
+pub mod metrics;
 pub mod mode;
+pub mod mqtt;
+pub mod notifications;
+pub mod poller;
+pub mod proxy;
+pub mod render;
+pub mod store;
+pub mod theme;
+pub mod transitions;
 pub mod tui;
 pub mod tui_helpers;
 pub mod utils;
+pub mod webhooks;
 
 rust_i18n::i18n!();
 
@@ -25,36 +39,44 @@ use ralertsinua_http::*;
 #[allow(unused_imports)]
 use std::{
     io::{stdin, stdout, Write},
+    process::ExitCode,
     time::Duration,
     {path::PathBuf, sync::Arc},
 };
 use tracing::{debug, error, warn};
 use tui_logger::set_level_for_target;
 
-use crate::{app::App, config::Config, utils::*};
+use crate::{
+    app::App,
+    commands::AlertExitCode,
+    config::{Config, CONFIG_FILE_NAME},
+    utils::*,
+};
 
-async fn tokio_main() -> Result<()> {
+async fn tokio_main() -> Result<ExitCode> {
     dotenvy::dotenv().ok();
+    initialize_panic_handler()?;
 
-    let mut log_file: Option<String> = None;
     let args = Cli::parse();
-    let mut config = Config::default();
-
-    if config.log_file().is_empty() {
-        if !args.log_file.is_empty() {
-            config.set_log_file(args.log_file.to_string());
-            log_file = Some(args.log_file);
-        }
-    } else {
-        log_file = Some(config.log_file().to_string());
+    let config_path = get_config_dir().join(CONFIG_FILE_NAME);
+    if let Some(cli::Command::Config(config_args)) = &args.command {
+        let code = commands::config(config_args, &config_path, &args, &mut stdout())?;
+        return Ok(code.into());
     }
+    let mut config = Config::load(&config_path, &args)?;
+    let log_file = Some(config.log_file().to_string()).filter(|f| !f.is_empty());
 
     initialize_logging(log_file)?;
     set_level_for_target("app", log::LevelFilter::Debug);
     debug!(target:"app", "initialized logging");
-    initialize_panic_handler()?;
 
-    if config.token().is_empty() {
+    if config.token().is_empty() && args.command.is_some() {
+        error!(target: "app", "token is empty, cannot ask for it in non-interactive mode");
+        return Err(miette!(
+            help = "provide it via environment variable 'ALERTSINUA_TOKEN', as a parameter '--token' or in the config file",
+            "token cannot be empty"
+        ));
+    } else if config.token().is_empty() {
         warn!(target: "app", "token is empty, asking user for token");
         print!("enter your 'alerts.in.ua' token: ");
         stdout().flush().into_diagnostic()?;
@@ -70,41 +92,34 @@ async fn tokio_main() -> Result<()> {
             debug!(target: "app", "token from user input accepted");
             config.set_token(token)?;
         }
-    } else if !args.token.is_empty() {
-        debug!(target: "app", "token from parameters accepted, ignore env");
-        config.set_token(args.token)?;
     }
 
-    // Replace with a reliable public server (e.g., 8.8.8.8:53)
-    match std::net::TcpStream::connect("8.8.8.8:53") {
-        Ok(_) => {
-            debug!(target: "app", "sucsessful ping 8.8.8.8:53, online=true");
-            config.set_online(true);
-        }
-        Err(e) => {
-            error!(target: "app", "failed to ping 8.8.8.8:53, online=false, error={}", e);
-            config.set_online(false);
-        }
-    };
-
     debug!(target: "app", "\n{:?} \n\n-----------", config.settings());
 
     let api_client: Arc<dyn AlertsInUaApi> =
         Arc::new(AlertsInUaClient::new(config.base_url(), config.token()));
     let geo_client: Arc<dyn AlertsInUaGeo> = Arc::new(AlertsInUaGeoClient::default());
 
+    if let Some(command) = args.command {
+        let code = commands::run(command, api_client, geo_client, &config).await?;
+        return Ok(code.into());
+    }
+
     let mut app = App::new(config, api_client.clone(), geo_client.clone())?;
     app.run().await?;
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    if let Err(e) = tokio_main().await {
-        eprintln!("{} panicked: Something went wrong", env!("CARGO_PKG_NAME"));
-        Err(e)
-    } else {
-        Ok(())
+async fn main() -> ExitCode {
+    match tokio_main().await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{} panicked: Something went wrong", env!("CARGO_PKG_NAME"));
+            eprintln!("{:?}", e);
+            // Same as UNKNOWN in monitoring plugins, as 1 and 2 are taken by alert states
+            AlertExitCode::Unknown.into()
+        }
     }
 }
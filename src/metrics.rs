@@ -0,0 +1,305 @@
+use ralertsinua_http::{Connectivity, RequestStats};
+use ralertsinua_models::*;
+use std::{collections::BTreeMap, fmt::Write, time::UNIX_EPOCH};
+use time::OffsetDateTime;
+
+const PREFIX: &str = "alertsinua";
+
+/// Latest data collected by the exporter, encoded on every scrape
+#[derive(Debug, Default, Clone)]
+pub struct MetricsSnapshot {
+    pub statuses: Option<AirRaidAlertOblastStatuses>,
+    pub alerts: Option<Alerts>,
+    pub stats: RequestStats,
+    pub connectivity: Connectivity,
+}
+
+/// Escape label value as required by the Prometheus text format
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+/// Writes metric families in the Prometheus text exposition format
+#[derive(Default)]
+struct Encoder {
+    buf: String,
+}
+
+impl Encoder {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.buf, "# HELP {PREFIX}_{name} {help}");
+        let _ = writeln!(self.buf, "# TYPE {PREFIX}_{name} {kind}");
+    }
+
+    fn sample<V: std::fmt::Display>(
+        &mut self,
+        name: &str,
+        labels: &[(&str, &str)],
+        value: V,
+    ) {
+        let _ = write!(self.buf, "{PREFIX}_{name}");
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+                .collect();
+            let _ = write!(self.buf, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.buf, " {value}");
+    }
+}
+
+/// Encode snapshot in the Prometheus text exposition format
+pub fn encode(snapshot: &MetricsSnapshot) -> String {
+    let mut e = Encoder::default();
+
+    if let Some(statuses) = &snapshot.statuses {
+        e.family(
+            "oblast_status",
+            "gauge",
+            "Air raid alert status of the oblast, 1 for the current status",
+        );
+        for s in statuses.iter() {
+            let uid = s.location_uid.to_string();
+            let current = char::from(s.status());
+            for status in ['A', 'P', 'N'] {
+                e.sample(
+                    "oblast_status",
+                    &[
+                        ("uid", &uid),
+                        ("oblast", s.location_title_en()),
+                        ("status", &status.to_string()),
+                    ],
+                    u8::from(current == status),
+                );
+            }
+        }
+    }
+
+    if let Some(alerts) = &snapshot.alerts {
+        let mut by_type: BTreeMap<(String, String), u64> = BTreeMap::new();
+        let mut durations: BTreeMap<(i32, String), i64> = BTreeMap::new();
+        let now = OffsetDateTime::now_utc();
+        for alert in alerts.iter() {
+            let key = (
+                alert.alert_type.to_string(),
+                alert.location_type.to_string(),
+            );
+            *by_type.entry(key).or_default() += 1;
+            let seconds = (now - alert.started_at).whole_seconds().max(0);
+            let duration = durations
+                .entry((alert.location_oblast_uid, alert.location_oblast.clone()))
+                .or_default();
+            *duration = (*duration).max(seconds);
+        }
+
+        e.family(
+            "active_alerts",
+            "gauge",
+            "Number of active alerts by alert and location type",
+        );
+        for ((alert_type, location_type), count) in &by_type {
+            e.sample(
+                "active_alerts",
+                &[("alert_type", alert_type), ("location_type", location_type)],
+                count,
+            );
+        }
+
+        e.family(
+            "alert_duration_seconds",
+            "gauge",
+            "Duration of the longest active alert in the oblast",
+        );
+        for ((uid, oblast), seconds) in &durations {
+            e.sample(
+                "alert_duration_seconds",
+                &[("oblast_uid", &uid.to_string()), ("oblast", oblast)],
+                seconds,
+            );
+        }
+    }
+
+    let endpoints = &snapshot.stats.endpoints;
+    e.family("api_requests_total", "counter", "Requests made to the API");
+    for (endpoint, s) in endpoints {
+        e.sample("api_requests_total", &[("endpoint", endpoint)], s.requests);
+    }
+    e.family(
+        "api_not_modified_total",
+        "counter",
+        "Responses '304 Not Modified' served from cache",
+    );
+    for (endpoint, s) in endpoints {
+        e.sample(
+            "api_not_modified_total",
+            &[("endpoint", endpoint)],
+            s.not_modified,
+        );
+    }
+    e.family(
+        "api_cache_hit_ratio",
+        "gauge",
+        "Share of responses served from cache",
+    );
+    for (endpoint, s) in endpoints {
+        e.sample(
+            "api_cache_hit_ratio",
+            &[("endpoint", endpoint)],
+            s.cache_hit_ratio(),
+        );
+    }
+    e.family(
+        "api_request_duration_seconds",
+        "summary",
+        "Duration of the API requests",
+    );
+    for (endpoint, s) in endpoints {
+        let labels = [("endpoint", endpoint.as_str())];
+        e.sample(
+            "api_request_duration_seconds_sum",
+            &labels,
+            s.latency_sum.as_secs_f64(),
+        );
+        e.sample("api_request_duration_seconds_count", &labels, s.requests);
+    }
+    e.family(
+        "api_last_request_duration_seconds",
+        "gauge",
+        "Duration of the last API request",
+    );
+    for (endpoint, s) in endpoints {
+        e.sample(
+            "api_last_request_duration_seconds",
+            &[("endpoint", endpoint)],
+            s.latency_last.as_secs_f64(),
+        );
+    }
+    e.family(
+        "api_errors_total",
+        "counter",
+        "Failed API requests by error kind",
+    );
+    for (endpoint, s) in endpoints {
+        for (kind, count) in &s.errors {
+            e.sample(
+                "api_errors_total",
+                &[("endpoint", endpoint), ("kind", kind)],
+                count,
+            );
+        }
+    }
+
+    let connectivity = &snapshot.connectivity;
+    e.family("api_up", "gauge", "Whether the last API request succeeded");
+    e.sample("api_up", &[], u8::from(connectivity.is_online()));
+    if let Some(last_success) = connectivity
+        .last_success()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+    {
+        e.family(
+            "api_last_success_timestamp_seconds",
+            "gauge",
+            "Unix time of the last successful API request",
+        );
+        e.sample(
+            "api_last_success_timestamp_seconds",
+            &[],
+            last_success.as_secs(),
+        );
+    }
+
+    e.buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ralertsinua_http::ApiError;
+    use std::time::Duration;
+
+    fn snapshot() -> MetricsSnapshot {
+        let alerts: Alerts = serde_json::from_value(serde_json::json!({
+            "alerts": [{
+                "alert_type": "air_raid",
+                "finished_at": null,
+                "id": 8757,
+                "location_oblast": "Луганська область",
+                "location_oblast_uid": 16,
+                "location_title": "Луганська область",
+                "location_type": "oblast",
+                "location_uid": "16",
+                "notes": null,
+                "started_at": "2022-04-04T16:45:39.000Z",
+                "updated_at": "2023-10-29T18:22:37.357Z"
+            }],
+            "disclaimer": "",
+            "meta": { "last_updated_at": "2024/05/06 10:02:45 +0000", "type": "full" }
+        }))
+        .unwrap();
+        let mut stats = RequestStats::default();
+        stats.record(
+            "/alerts/active.json",
+            Duration::from_millis(200),
+            true,
+            None,
+        );
+        stats.record(
+            "/alerts/active.json",
+            Duration::from_millis(100),
+            false,
+            Some(&ApiError::RateLimitError),
+        );
+        MetricsSnapshot {
+            statuses: Some(AirRaidAlertOblastStatuses::new(
+                "ANNAANNANNNPANANANNNNAANNNN".to_string(),
+                Some(false),
+            )),
+            alerts: Some(alerts),
+            stats,
+            connectivity: Connectivity::default(),
+        }
+    }
+
+    #[test]
+    fn test_encode() {
+        let text = encode(&snapshot());
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines.contains(
+            &r#"alertsinua_oblast_status{uid="29",oblast="Autonomous Republic of Crimea",status="A"} 1"#
+        ));
+        assert!(lines.contains(
+            &r#"alertsinua_oblast_status{uid="29",oblast="Autonomous Republic of Crimea",status="N"} 0"#
+        ));
+        assert!(lines.contains(
+            &r#"alertsinua_active_alerts{alert_type="air_raid",location_type="oblast"} 1"#
+        ));
+        assert!(lines.contains(
+            &r#"alertsinua_api_cache_hit_ratio{endpoint="/alerts/active.json"} 0.5"#
+        ));
+        assert!(lines.contains(
+            &r#"alertsinua_api_request_duration_seconds_sum{endpoint="/alerts/active.json"} 0.3"#
+        ));
+        assert!(lines.contains(
+            &r#"alertsinua_api_errors_total{endpoint="/alerts/active.json",kind="RateLimitError"} 1"#
+        ));
+        assert!(lines.contains(&"alertsinua_api_up 0"));
+        assert!(lines.contains(&"# TYPE alertsinua_oblast_status gauge"));
+        assert!(
+            lines
+                .iter()
+                .any(|l| l
+                    .starts_with(r#"alertsinua_alert_duration_seconds{oblast_uid="16""#))
+        );
+        assert!(!text.contains("api_last_success_timestamp_seconds"));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(escape_label("a\nb"), r"a\nb");
+    }
+}
@@ -1,7 +1,34 @@
 use serde::{Deserialize, Serialize};
+use strum::Display;
 
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Context of the keybindings, `Global` ones apply unless a more specific mode binds the keys
+#[derive(
+    Default,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Display,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
 pub enum Mode {
     #[default]
+    Global,
     Map,
+    List,
+    /// Table of the active alerts
+    Table,
+    /// Alerts history of the selected location
+    History,
+    /// Typing the text to filter by, keys which are not bound are the input
+    Search,
+    /// Help popup is open, it captures all keys
+    Help,
 }
@@ -0,0 +1,410 @@
+use miette::{miette, IntoDiagnostic, Result};
+use ralertsinua_models::*;
+use rumqttc::{
+    AsyncClient, ClientError, Event, EventLoop, LastWill, MqttOptions, Outgoing, Packet,
+    QoS,
+};
+use std::collections::HashMap;
+use tokio::time::{sleep, Duration};
+use tracing::{debug, info, warn};
+
+pub const DEFAULT_MQTT_PORT: u16 = 1883;
+pub const AVAILABILITY_ONLINE: &str = "online";
+pub const AVAILABILITY_OFFLINE: &str = "offline";
+/// Delay before reconnecting to the broker after a connection error
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Message to publish, all messages are retained so new subscribers get the latest state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttMessage {
+    pub topic: String,
+    pub payload: String,
+}
+
+/// Topics published under the prefix, e.g. `alerts/ua/31/status`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttTopics {
+    prefix: String,
+}
+
+impl MqttTopics {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// `online` while publishing, `offline` as the last will
+    pub fn availability(&self) -> String {
+        format!("{}/availability", self.prefix)
+    }
+
+    /// Air raid alert status of the oblast, `A`, `P` or `N`
+    pub fn status(&self, location_uid: i32) -> String {
+        format!("{}/{}/status", self.prefix, location_uid)
+    }
+
+    /// Active alerts of any type in the oblast, JSON array
+    pub fn alerts(&self, location_uid: i32) -> String {
+        format!("{}/{}/alerts", self.prefix, location_uid)
+    }
+}
+
+/// Builds messages from polled statuses and alerts, skipping ones unchanged since the
+/// previous publish
+#[derive(Debug, Clone)]
+pub struct MqttPublisher {
+    topics: MqttTopics,
+    /// Last published alerts payload by oblast uid
+    alerts: HashMap<i32, String>,
+}
+
+impl MqttPublisher {
+    pub fn new(topics: MqttTopics) -> Self {
+        Self {
+            topics,
+            alerts: HashMap::new(),
+        }
+    }
+
+    pub fn topics(&self) -> &MqttTopics {
+        &self.topics
+    }
+
+    fn status_message(&self, location_uid: i32, status: &AlertStatus) -> MqttMessage {
+        MqttMessage {
+            topic: self.topics.status(location_uid),
+            payload: char::from(status).to_string(),
+        }
+    }
+
+    /// Status of every oblast, published once the first poll sets the baseline
+    pub fn status_messages(
+        &self,
+        statuses: &AirRaidAlertOblastStatuses,
+    ) -> Vec<MqttMessage> {
+        statuses
+            .iter()
+            .map(|s| self.status_message(s.location_uid, s.status()))
+            .collect()
+    }
+
+    /// Status of the oblasts changed since the previous poll
+    pub fn change_messages(
+        &self,
+        changes: &[AirRaidAlertOblastStatusChange],
+    ) -> Vec<MqttMessage> {
+        changes
+            .iter()
+            .map(|c| self.status_message(c.location_uid, c.current()))
+            .collect()
+    }
+
+    /// Active alerts of every oblast in the statuses, only the ones which changed
+    pub fn alert_messages(
+        &mut self,
+        statuses: &AirRaidAlertOblastStatuses,
+        alerts: &Alerts,
+    ) -> Vec<MqttMessage> {
+        let mut messages = Vec::new();
+        for status in statuses.iter() {
+            let uid = status.location_uid;
+            let oblast_alerts = alerts.get_alerts_by_location_oblast_uid(uid);
+            let payload = serde_json::to_string(&oblast_alerts).unwrap_or_default();
+            if self.alerts.get(&uid) == Some(&payload) {
+                continue;
+            }
+            self.alerts.insert(uid, payload.clone());
+            messages.push(MqttMessage {
+                topic: self.topics.alerts(uid),
+                payload,
+            });
+        }
+        messages
+    }
+}
+
+/// Split `host[:port]` of the broker
+pub fn parse_broker(broker: &str) -> Result<(String, u16)> {
+    let (host, port) = match broker.rsplit_once(':') {
+        Some((host, port)) => (
+            host,
+            port.parse()
+                .map_err(|_| miette!("invalid broker port '{}'", port))?,
+        ),
+        None => (broker, DEFAULT_MQTT_PORT),
+    };
+    if host.is_empty() {
+        return Err(miette!("broker host is empty"));
+    }
+    Ok((host.to_string(), port))
+}
+
+/// Connection options with the `offline` last will on the availability topic
+pub fn mqtt_options(
+    client_id: &str,
+    broker: &str,
+    credentials: Option<(&str, &str)>,
+    topics: &MqttTopics,
+) -> Result<MqttOptions> {
+    let (host, port) = parse_broker(broker)?;
+    let mut options = MqttOptions::new(client_id, host, port);
+    options
+        .set_keep_alive(Duration::from_secs(30))
+        .set_max_packet_size(256 * 1024, 256 * 1024)
+        .set_last_will(LastWill::new(
+            topics.availability(),
+            AVAILABILITY_OFFLINE,
+            QoS::AtLeastOnce,
+            true,
+        ));
+    if let Some((username, password)) = credentials {
+        options.set_credentials(username, password);
+    }
+    Ok(options)
+}
+
+/// Publish retained messages with at least once delivery
+pub async fn publish(
+    client: &AsyncClient,
+    messages: &[MqttMessage],
+) -> std::result::Result<(), ClientError> {
+    for message in messages {
+        debug!(target: "app", "mqtt publish {} = {}", message.topic, message.payload);
+        client
+            .publish(
+                &message.topic,
+                QoS::AtLeastOnce,
+                true,
+                message.payload.as_bytes(),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Drive the connection until disconnected, reconnecting on errors. Availability is set
+/// `online` on every connection, as the broker publishes the last will when it is lost
+pub async fn drive(mut eventloop: EventLoop, client: AsyncClient, topics: MqttTopics) {
+    loop {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                info!(target: "app", "connected to mqtt broker");
+                let availability = client.try_publish(
+                    topics.availability(),
+                    QoS::AtLeastOnce,
+                    true,
+                    AVAILABILITY_ONLINE,
+                );
+                if let Err(e) = availability {
+                    warn!(target: "app", "failed to publish availability: {}", e);
+                }
+            }
+            Ok(Event::Outgoing(Outgoing::Disconnect)) => break,
+            Ok(_) => {}
+            Err(e) => {
+                warn!(target: "app", "mqtt connection error: {}", e);
+                sleep(RECONNECT_DELAY).await;
+            }
+        }
+    }
+}
+
+/// Publish `offline` and disconnect gracefully, so the last will is not needed
+pub async fn shutdown(client: &AsyncClient, topics: &MqttTopics) -> Result<()> {
+    client
+        .publish(
+            topics.availability(),
+            QoS::AtLeastOnce,
+            true,
+            AVAILABILITY_OFFLINE,
+        )
+        .await
+        .into_diagnostic()?;
+    client.disconnect().await.into_diagnostic()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+    use rumqttc::{ConnAck, ConnectReturnCode, PubAck, Publish};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::oneshot,
+    };
+
+    fn statuses(data: &str) -> AirRaidAlertOblastStatuses {
+        AirRaidAlertOblastStatuses::new(data.to_string(), Some(false))
+    }
+
+    /// Minimal broker accepting one client, returns its last will and publishes.
+    /// Signals once the client announced it is online
+    async fn broker(
+        listener: TcpListener,
+        online: oneshot::Sender<()>,
+    ) -> (Option<LastWill>, Vec<Publish>) {
+        let mut online = Some(online);
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = BytesMut::new();
+        let mut out = BytesMut::new();
+        let mut last_will = None;
+        let mut publishes = Vec::new();
+        loop {
+            let packet = match rumqttc::mqttbytes::v4::read(&mut buf, 1 << 20) {
+                Ok(packet) => packet,
+                Err(rumqttc::Error::InsufficientBytes(_)) => {
+                    if stream.read_buf(&mut buf).await.unwrap() == 0 {
+                        break;
+                    }
+                    continue;
+                }
+                Err(e) => panic!("invalid packet: {:?}", e),
+            };
+            match packet {
+                Packet::Connect(connect) => {
+                    last_will = connect.last_will;
+                    ConnAck::new(ConnectReturnCode::Success, false)
+                        .write(&mut out)
+                        .unwrap();
+                }
+                Packet::Publish(publish) => {
+                    PubAck::new(publish.pkid).write(&mut out).unwrap();
+                    if &publish.payload[..] == AVAILABILITY_ONLINE.as_bytes() {
+                        online.take().map(|tx| tx.send(()));
+                    }
+                    publishes.push(publish);
+                }
+                Packet::Disconnect => break,
+                _ => {}
+            }
+            // the client may close the connection right after the disconnect
+            let _ = stream.write_all(&out.split()).await;
+        }
+        (last_will, publishes)
+    }
+
+    #[test]
+    fn test_parse_broker() {
+        assert_eq!(
+            parse_broker("localhost").unwrap(),
+            ("localhost".to_string(), 1883)
+        );
+        assert_eq!(
+            parse_broker("10.0.0.2:8883").unwrap(),
+            ("10.0.0.2".to_string(), 8883)
+        );
+        assert!(parse_broker("localhost:port").is_err());
+        assert!(parse_broker(":1883").is_err());
+    }
+
+    #[test]
+    fn test_status_messages() {
+        let publisher = MqttPublisher::new(MqttTopics::new("alerts/ua/"));
+        let current = statuses("APNNNNNNNNNNNNNNNNNNNNNNNNN");
+
+        let messages = publisher.status_messages(&current);
+        assert_eq!(messages.len(), 27);
+        // Crimea and Volyn oblasts
+        assert_eq!(
+            messages[0],
+            MqttMessage {
+                topic: "alerts/ua/29/status".to_string(),
+                payload: "A".to_string()
+            }
+        );
+        assert_eq!(messages[1].payload, "P");
+
+        let changes = statuses("NPNNNNNNNNNNNNNNNNNNNNNNNNN").diff(&current);
+        let messages = publisher.change_messages(&changes);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].topic, "alerts/ua/29/status");
+        assert_eq!(messages[0].payload, "N");
+    }
+
+    #[test]
+    fn test_alert_messages_only_changed() {
+        let mut publisher = MqttPublisher::new(MqttTopics::new("alerts/ua"));
+        let current = statuses("NNNNNNNNNNNNNNNNNNNNNNNNNNN");
+        let alerts: Alerts = serde_json::from_value(serde_json::json!({
+            "alerts": [{
+                "alert_type": "artillery_shelling",
+                "finished_at": null,
+                "id": 1,
+                "location_oblast": "Дніпропетровська область",
+                "location_oblast_uid": 9,
+                "location_title": "Нікопольська територіальна громада",
+                "location_type": "hromada",
+                "location_uid": "351",
+                "notes": null,
+                "started_at": "2024-05-05T15:48:31.000Z",
+                "updated_at": "2024-05-05T15:48:31.818Z"
+            }],
+            "disclaimer": "",
+            "meta": { "last_updated_at": "2024/05/06 10:02:45 +0000", "type": "full" }
+        }))
+        .unwrap();
+
+        let messages = publisher.alert_messages(&current, &alerts);
+        assert_eq!(messages.len(), 27);
+        let dnipro = messages
+            .iter()
+            .find(|m| m.topic == "alerts/ua/9/alerts")
+            .unwrap();
+        let dnipro: Vec<Alert> = serde_json::from_str(&dnipro.payload).unwrap();
+        assert_eq!(dnipro, alerts.get_alerts()[..]);
+        assert!(messages
+            .iter()
+            .filter(|m| m.topic != "alerts/ua/9/alerts")
+            .all(|m| m.payload == "[]"));
+
+        // unchanged alerts are not published again
+        assert!(publisher.alert_messages(&current, &alerts).is_empty());
+        let messages = publisher.alert_messages(&current, &Alerts::default());
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].topic, "alerts/ua/9/alerts");
+        assert_eq!(messages[0].payload, "[]");
+    }
+
+    #[tokio::test]
+    async fn test_publish_to_broker() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let broker_addr = listener.local_addr().unwrap().to_string();
+        let (online_tx, online_rx) = oneshot::channel();
+        let broker = tokio::spawn(broker(listener, online_tx));
+        let topics = MqttTopics::new("alerts/ua");
+        let options = mqtt_options("test", &broker_addr, None, &topics).unwrap();
+        let (client, eventloop) = AsyncClient::new(options, 64);
+        let connection = tokio::spawn(drive(eventloop, client.clone(), topics.clone()));
+        online_rx.await.unwrap();
+
+        let publisher = MqttPublisher::new(topics.clone());
+        let messages = publisher.status_messages(&statuses("ANNNNNNNNNNNNNNNNNNNNNNNNNN"));
+        publish(&client, &messages).await.unwrap();
+        shutdown(&client, &topics).await.unwrap();
+        connection.await.unwrap();
+        let (last_will, publishes) = broker.await.unwrap();
+
+        let last_will = last_will.unwrap();
+        assert_eq!(last_will.topic, "alerts/ua/availability");
+        assert_eq!(&last_will.message[..], b"offline");
+        assert!(last_will.retain);
+        assert!(publishes.iter().all(|p| p.retain));
+        let payloads: Vec<(&str, &[u8])> = publishes
+            .iter()
+            .map(|p| (p.topic.as_str(), &p.payload[..]))
+            .collect();
+        assert_eq!(
+            payloads.first(),
+            Some(&("alerts/ua/availability", &b"online"[..]))
+        );
+        assert!(payloads.contains(&("alerts/ua/29/status", b"A")));
+        assert!(payloads.contains(&("alerts/ua/4/status", b"N")));
+        assert_eq!(
+            payloads.last(),
+            Some(&("alerts/ua/availability", &b"offline"[..]))
+        );
+        assert_eq!(payloads.len(), 27 + 2);
+    }
+}
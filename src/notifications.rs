@@ -0,0 +1,264 @@
+use ralertsinua_geo::AlertsInUaGeo;
+use ralertsinua_models::*;
+use rust_i18n::t;
+use std::{io::Write, str::FromStr};
+use strum::{Display, EnumString};
+use time::OffsetDateTime;
+use tracing::{debug, warn};
+
+use crate::config::Config;
+
+/// Kind of the status change of a location
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum TransitionType {
+    Start,
+    End,
+    Partial,
+}
+
+impl TransitionType {
+    pub fn of(change: &AirRaidAlertOblastStatusChange) -> Option<Self> {
+        if change.is_alert_start() {
+            Some(TransitionType::Start)
+        } else if change.is_alert_end() {
+            Some(TransitionType::End)
+        } else if change.is_partial() {
+            Some(TransitionType::Partial)
+        } else {
+            None
+        }
+    }
+}
+
+/// Parse comma separated list of transition types, e.g. `start,end`
+pub fn parse_transition_types(value: &str) -> Vec<TransitionType> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| match TransitionType::from_str(s) {
+            Ok(t) => Some(t),
+            Err(_) => {
+                warn!(target: "app", "unknown transition type '{}', expected start, end or partial", s);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Escape sequence used for desktop notifications, support depends on the terminal
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum OscProtocol {
+    /// `OSC 9`, supported by iTerm2, Windows Terminal, WezTerm, kitty
+    #[default]
+    #[strum(serialize = "9")]
+    Osc9,
+    /// `OSC 777`, supported by urxvt, foot, Ghostty, VTE based terminals
+    #[strum(serialize = "777")]
+    Osc777,
+}
+
+impl OscProtocol {
+    pub fn sequence(&self, title: &str, body: &str) -> String {
+        // control characters would terminate the sequence early
+        let clean = |s: &str| s.replace(|c: char| c.is_control() || c == ';', " ");
+        match self {
+            OscProtocol::Osc9 => format!("\x1b]9;{}: {}\x07", clean(title), clean(body)),
+            OscProtocol::Osc777 => {
+                format!("\x1b]777;notify;{};{}\x07", clean(title), clean(body))
+            }
+        }
+    }
+}
+
+/// Notifies about status changes of the watched locations, as configured per transition type
+#[derive(Debug, Clone, Default)]
+pub struct Notifications {
+    watchlist: Vec<i32>,
+    bell: Vec<TransitionType>,
+    osc: Vec<TransitionType>,
+    osc_protocol: OscProtocol,
+    banner: Vec<TransitionType>,
+}
+
+impl Notifications {
+    pub fn new(config: &Config, geo_client: &dyn AlertsInUaGeo) -> Self {
+        let watchlist = config
+            .watchlist()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .filter_map(|query| match geo_client.find_location(query) {
+                Some(location) => Some(location.location_uid),
+                None => {
+                    warn!(target: "app", "watchlist location '{}' not found", query);
+                    None
+                }
+            })
+            .collect();
+        let osc_protocol =
+            OscProtocol::from_str(config.notify_osc_protocol()).unwrap_or_else(|_| {
+                warn!(target: "app", "unknown OSC protocol '{}', expected 9 or 777", config.notify_osc_protocol());
+                OscProtocol::default()
+            });
+        Self {
+            watchlist,
+            bell: parse_transition_types(config.notify_bell()),
+            osc: parse_transition_types(config.notify_osc()),
+            osc_protocol,
+            banner: parse_transition_types(config.notify_banner()),
+        }
+    }
+
+    pub fn watchlist(&self) -> &[i32] {
+        &self.watchlist
+    }
+
+    /// Changes of the watched locations, with their transition type
+    pub fn watched<'a>(
+        &self,
+        changes: &'a [AirRaidAlertOblastStatusChange],
+    ) -> Vec<(TransitionType, &'a AirRaidAlertOblastStatusChange)> {
+        changes
+            .iter()
+            .filter(|c| self.watchlist.contains(&c.location_uid))
+            .filter_map(|c| TransitionType::of(c).map(|t| (t, c)))
+            .collect()
+    }
+
+    /// Notification text in the locale
+    pub fn message(
+        transition: TransitionType,
+        location_title: &str,
+        locale: &str,
+    ) -> String {
+        let key = format!("notifications.{}", transition);
+        t!(&key, locale = locale, location = location_title).to_string()
+    }
+
+    /// Sentence about the transition for screen readers, with the time it was observed at
+    /// in its offset, e.g. "Kharkiv Oblast: air raid alert started 14:02". The time in UTC,
+    /// when the local offset is unknown, is marked as such
+    pub fn announcement(
+        transition: TransitionType,
+        location_title: &str,
+        at: OffsetDateTime,
+        locale: &str,
+    ) -> String {
+        let mut time = format!("{:02}:{:02}", at.hour(), at.minute());
+        if at.offset().is_utc() {
+            time.push_str(" UTC");
+        }
+        let key = format!("announcements.{}", transition);
+        t!(
+            &key,
+            locale = locale,
+            location = location_title,
+            time = time
+        )
+        .to_string()
+    }
+
+    /// Ring the bell and write OSC sequences to the terminal, messages are in the locale.
+    /// Returns banner messages to show
+    pub fn notify<W: Write>(
+        &self,
+        changes: &[AirRaidAlertOblastStatusChange],
+        locale: &str,
+        out: &mut W,
+    ) -> Vec<String> {
+        let mut banners = Vec::new();
+        for (transition, change) in self.watched(changes) {
+            let title = match locale {
+                "uk" => change.location_title(),
+                _ => change.location_title_en(),
+            };
+            let message = Self::message(transition, title, locale);
+            debug!(target: "app", "notify: {}", message);
+            if self.bell.contains(&transition) {
+                let _ = out.write_all(b"\x07");
+            }
+            if self.osc.contains(&transition) {
+                let sequence = self.osc_protocol.sequence(env!("CARGO_PKG_NAME"), &message);
+                let _ = out.write_all(sequence.as_bytes());
+            }
+            if self.banner.contains(&transition) {
+                banners.push(message);
+            }
+        }
+        let _ = out.flush();
+        banners
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes() -> Vec<AirRaidAlertOblastStatusChange> {
+        let previous = AirRaidAlertOblastStatuses::new(
+            "ANNNNNNNNNNNNNNNNNNNNNNNNNN".to_string(),
+            Some(false),
+        );
+        let current = AirRaidAlertOblastStatuses::new(
+            "NAPNNNNNNNNNNNNNNNNNNNNNNNN".to_string(),
+            Some(false),
+        );
+        current.diff(&previous)
+    }
+
+    fn notifications() -> Notifications {
+        Notifications {
+            // Crimea, Vinnytsia and Volyn oblasts
+            watchlist: vec![29, 8, 4],
+            bell: vec![TransitionType::Start],
+            osc: vec![TransitionType::End],
+            osc_protocol: OscProtocol::Osc777,
+            banner: vec![TransitionType::Start, TransitionType::Partial],
+        }
+    }
+
+    #[test]
+    fn test_parse_transition_types() {
+        assert_eq!(
+            parse_transition_types("start, partial,,unknown"),
+            vec![TransitionType::Start, TransitionType::Partial]
+        );
+        assert!(parse_transition_types("").is_empty());
+    }
+
+    #[test]
+    fn test_watched() {
+        let changes = changes();
+        let mut notifications = notifications();
+        notifications.watchlist = vec![8];
+        let watched = notifications.watched(&changes);
+        assert_eq!(watched.len(), 1);
+        assert_eq!(watched[0].0, TransitionType::Start);
+    }
+
+    #[test]
+    fn test_notify() {
+        let mut out = Vec::new();
+        let banners = notifications().notify(&changes(), "en", &mut out);
+        let out = String::from_utf8(out).unwrap();
+
+        // OSC on the end in Crimea, then the bell on the start in Vinnytsia
+        assert_eq!(
+            out,
+            "\x1b]777;notify;ralertsinua;Air raid alert is over: Autonomous Republic of Crimea\x07\x07"
+        );
+        assert_eq!(banners.len(), 2);
+        assert_eq!(banners[0], "Air raid alert: Vinnytsia Oblast");
+        assert_eq!(banners[1], "Partial air raid alert: Volyn Oblast");
+    }
+
+    #[test]
+    fn test_osc_sequence() {
+        assert_eq!(
+            OscProtocol::Osc9.sequence("app", "a;b\x07c"),
+            "\x1b]9;app: a b c\x07"
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use ralertsinua_http::{AlertsInUaApi, ApiError};
+use ralertsinua_models::*;
+use std::sync::Arc;
+use tokio::time::{interval, Duration, Interval, MissedTickBehavior};
+use tracing::debug;
+
+/// Polls air raid alert statuses with a fixed interval and diffs them with the previous poll
+pub struct StatusPoller {
+    api_client: Arc<dyn AlertsInUaApi>,
+    interval: Interval,
+    current: Option<AirRaidAlertOblastStatuses>,
+    keep_partial: bool,
+}
+
+impl StatusPoller {
+    pub fn new(api_client: Arc<dyn AlertsInUaApi>, period: Duration) -> Self {
+        let mut interval = interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            api_client,
+            interval,
+            current: None,
+            keep_partial: false,
+        }
+    }
+
+    /// Keep partial alerts, which the API client hides on oblast level
+    pub fn keep_partial(mut self) -> Self {
+        self.keep_partial = true;
+        self
+    }
+
+    /// Latest successfully fetched statuses
+    pub fn current(&self) -> Option<&AirRaidAlertOblastStatuses> {
+        self.current.as_ref()
+    }
+
+    /// Wait for the next tick, fetch statuses and return changes since the previous poll.
+    /// The first successful poll only sets the baseline and returns no changes
+    pub async fn next(&mut self) -> Result<Vec<AirRaidAlertOblastStatusChange>, ApiError> {
+        self.interval.tick().await;
+        let statuses = self
+            .api_client
+            .get_air_raid_alert_statuses_by_location()
+            .await?;
+        let statuses = match self.keep_partial {
+            true => AirRaidAlertOblastStatuses::new(
+                statuses.raw_data().to_string(),
+                Some(false),
+            ),
+            false => statuses,
+        };
+        let changes = match &self.current {
+            Some(previous) => statuses.diff(previous),
+            None => Vec::new(),
+        };
+        debug!(target: "app", "polled statuses, {} changes", changes.len());
+        self.current = Some(statuses);
+        Ok(changes)
+    }
+}
@@ -0,0 +1,519 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::get,
+    Router,
+};
+use ralertsinua_http::{AlertsInUaApi, ApiError};
+use ralertsinua_models::*;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+use tracing::{debug, warn};
+
+/// Lower bound of the upstream polling interval, each poll makes two requests and the API
+/// allows about 8-10 requests per minute
+pub const MIN_PROXY_INTERVAL_SEC: u64 = 20;
+/// Upstream requests per minute shared by the poller and on-demand requests (history,
+/// single location status)
+pub const UPSTREAM_REQUESTS_PER_MINUTE: usize = 8;
+/// Upstream alerts history requests per minute, across all locations
+pub const HISTORY_REQUESTS_PER_MINUTE: usize = 2;
+/// How long alerts history of a location is served from cache
+pub const HISTORY_TTL: Duration = Duration::from_secs(300);
+/// How long air raid alert status of a single location is served from cache
+pub const LOCATION_STATUS_TTL: Duration = Duration::from_secs(30);
+
+/// Upstream requests made within the last minute
+#[derive(Debug, Default)]
+struct RequestWindow(VecDeque<Instant>);
+
+impl RequestWindow {
+    const PERIOD: Duration = Duration::from_secs(60);
+
+    fn len(&mut self) -> usize {
+        while self.0.front().is_some_and(|t| t.elapsed() >= Self::PERIOD) {
+            self.0.pop_front();
+        }
+        self.0.len()
+    }
+
+    fn record(&mut self) {
+        self.0.push_back(Instant::now());
+    }
+}
+
+/// Response body cached by the proxy
+#[derive(Debug, Clone)]
+struct Cached {
+    body: String,
+    fetched_at: Instant,
+    last_modified: SystemTime,
+}
+
+impl Cached {
+    fn new(body: String) -> Self {
+        // HTTP dates have a precision of one second
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| UNIX_EPOCH + Duration::from_secs(d.as_secs()))
+            .unwrap_or(UNIX_EPOCH);
+        Self {
+            body,
+            fetched_at: Instant::now(),
+            last_modified: now,
+        }
+    }
+
+    /// Replace body, `Last-Modified` only changes if the body does
+    fn update(previous: Option<&Cached>, body: String) -> Self {
+        match previous {
+            Some(prev) if prev.body == body => Self {
+                fetched_at: Instant::now(),
+                ..prev.clone()
+            },
+            _ => Self::new(body),
+        }
+    }
+
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        self.fetched_at.elapsed() < ttl
+    }
+
+    /// Respond with `304 Not Modified` if the client has the same data already
+    fn respond(&self, headers: &HeaderMap) -> Response {
+        let not_modified = headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| httpdate::parse_http_date(v).ok())
+            .is_some_and(|since| since >= self.last_modified);
+        let last_modified = httpdate::fmt_http_date(self.last_modified);
+        if not_modified {
+            return (
+                StatusCode::NOT_MODIFIED,
+                [(header::LAST_MODIFIED, last_modified)],
+            )
+                .into_response();
+        }
+        (
+            [
+                (header::CONTENT_TYPE, "application/json".to_string()),
+                (header::LAST_MODIFIED, last_modified),
+            ],
+            self.body.clone(),
+        )
+            .into_response()
+    }
+}
+
+fn to_json<T: Serialize>(data: &T) -> Result<String, StatusCode> {
+    serde_json::to_string(data).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn upstream_error_status(e: &ApiError) -> StatusCode {
+    match e {
+        ApiError::InvalidToken | ApiError::UnauthorizedError(_) => StatusCode::UNAUTHORIZED,
+        ApiError::RateLimitError => StatusCode::TOO_MANY_REQUESTS,
+        _ => StatusCode::BAD_GATEWAY,
+    }
+}
+
+/// Shared state of the proxy: the only holder of the API token.
+/// Statuses and active alerts are polled upstream, other endpoints are fetched on demand
+/// and cached, status changes are broadcast to the subscribers of `/v1/events`
+#[derive(Debug)]
+pub struct ProxyState {
+    api_client: Arc<dyn AlertsInUaApi>,
+    statuses: RwLock<Option<Cached>>,
+    alerts: RwLock<Option<Cached>>,
+    on_demand: Mutex<HashMap<String, Cached>>,
+    upstream_requests: Mutex<RequestWindow>,
+    history_requests: Mutex<RequestWindow>,
+    events: broadcast::Sender<AirRaidAlertOblastStatusChange>,
+}
+
+impl ProxyState {
+    pub fn new(api_client: Arc<dyn AlertsInUaApi>) -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self {
+            api_client,
+            statuses: RwLock::default(),
+            alerts: RwLock::default(),
+            on_demand: Mutex::default(),
+            upstream_requests: Mutex::default(),
+            history_requests: Mutex::default(),
+            events,
+        }
+    }
+
+    pub fn api_client(&self) -> Arc<dyn AlertsInUaApi> {
+        self.api_client.clone()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AirRaidAlertOblastStatusChange> {
+        self.events.subscribe()
+    }
+
+    /// Store freshly polled statuses and broadcast the changes to subscribers
+    pub async fn set_statuses(
+        &self,
+        statuses: &AirRaidAlertOblastStatuses,
+        changes: Vec<AirRaidAlertOblastStatusChange>,
+    ) {
+        let Ok(body) = to_json(&statuses.raw_data()) else {
+            return;
+        };
+        let mut cached = self.statuses.write().await;
+        *cached = Some(Cached::update(cached.as_ref(), body));
+        for change in changes {
+            // No subscribers is not an error
+            let _ = self.events.send(change);
+        }
+    }
+
+    pub async fn set_alerts(&self, alerts: &Alerts) {
+        let Ok(body) = to_json(alerts) else {
+            return;
+        };
+        let mut cached = self.alerts.write().await;
+        *cached = Some(Cached::update(cached.as_ref(), body));
+    }
+
+    /// Count a request made by the poller against the upstream budget. Polling is never
+    /// held back, on-demand requests get what is left of the budget
+    pub async fn record_upstream_request(&self) {
+        self.upstream_requests.lock().await.record();
+    }
+
+    /// Take a request from the upstream budget, and from the history budget for history
+    async fn acquire_upstream(&self, history: bool) -> bool {
+        let mut upstream = self.upstream_requests.lock().await;
+        if upstream.len() >= UPSTREAM_REQUESTS_PER_MINUTE {
+            return false;
+        }
+        if history {
+            let mut history = self.history_requests.lock().await;
+            if history.len() >= HISTORY_REQUESTS_PER_MINUTE {
+                return false;
+            }
+            history.record();
+        }
+        upstream.record();
+        true
+    }
+
+    /// Serve from cache while fresh, otherwise fetch upstream respecting the rate limit.
+    /// Stale data is served if upstream cannot be requested or fails
+    async fn on_demand<F, Fut>(
+        &self,
+        key: String,
+        ttl: Duration,
+        history: bool,
+        fetch: F,
+    ) -> Result<Cached, StatusCode>
+    where
+        F: FnOnce(Arc<dyn AlertsInUaApi>) -> Fut,
+        Fut: Future<Output = Result<String, ApiError>>,
+    {
+        let stale = match self.on_demand.lock().await.get(&key) {
+            Some(cached) if cached.is_fresh(ttl) => return Ok(cached.clone()),
+            Some(cached) => Some(cached.clone()),
+            None => None,
+        };
+
+        if !self.acquire_upstream(history).await {
+            debug!(target: "app", "upstream rate limit, {} not fetched", key);
+            return stale.ok_or(StatusCode::TOO_MANY_REQUESTS);
+        }
+
+        // the cache is not locked while waiting for upstream
+        match fetch(self.api_client.clone()).await {
+            Ok(body) => {
+                let mut cache = self.on_demand.lock().await;
+                let cached = Cached::update(cache.get(&key).or(stale.as_ref()), body);
+                cache.insert(key, cached.clone());
+                Ok(cached)
+            }
+            Err(e) => {
+                warn!(target: "app", "failed to fetch {}: {}", key, e);
+                stale.ok_or_else(|| upstream_error_status(&e))
+            }
+        }
+    }
+}
+
+type SharedState = Arc<ProxyState>;
+
+async fn active_alerts(State(state): State<SharedState>, headers: HeaderMap) -> Response {
+    match &*state.alerts.read().await {
+        Some(cached) => cached.respond(&headers),
+        None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+    }
+}
+
+async fn statuses_by_oblast(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> Response {
+    match &*state.statuses.read().await {
+        Some(cached) => cached.respond(&headers),
+        None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+    }
+}
+
+/// Parse path segment like `31.json`
+fn parse_json_segment(segment: &str) -> Option<&str> {
+    segment.strip_suffix(".json").filter(|s| !s.is_empty())
+}
+
+async fn location_status(
+    State(state): State<SharedState>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let uid: i8 = parse_json_segment(&file)
+        .and_then(|s| s.parse().ok())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let cached = state
+        .on_demand(
+            format!("status/{}", uid),
+            LOCATION_STATUS_TTL,
+            false,
+            |api| async move {
+                let status = api.get_air_raid_alert_status(&uid).await?;
+                Ok(serde_json::to_string(&status)?)
+            },
+        )
+        .await?;
+    Ok(cached.respond(&headers))
+}
+
+async fn alerts_history(
+    State(state): State<SharedState>,
+    Path((uid, file)): Path<(i8, String)>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let period = parse_json_segment(&file)
+        .ok_or(StatusCode::NOT_FOUND)?
+        .to_string();
+    let key = format!("history/{}/{}", uid, period);
+    let cached = state
+        .on_demand(key, HISTORY_TTL, true, |api| async move {
+            let alerts = api.get_alerts_history(&uid, &period).await?;
+            Ok(serde_json::to_string(&alerts)?)
+        })
+        .await?;
+    Ok(cached.respond(&headers))
+}
+
+/// Server-sent events: `status_change` with [`AirRaidAlertOblastStatusChange`] as JSON data
+async fn events(
+    State(state): State<SharedState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = BroadcastStream::new(state.subscribe()).filter_map(|change| {
+        // Lagged subscribers skip missed changes
+        let change = change.ok()?;
+        Event::default()
+            .event("status_change")
+            .json_data(change)
+            .ok()
+            .map(Ok)
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// Routes mirroring the `/v1/...` API, so `AlertsInUaClient` can use the proxy as `base_url`
+pub fn router(state: SharedState) -> Router {
+    Router::new()
+        .route("/v1/alerts/active.json", get(active_alerts))
+        .route(
+            "/v1/iot/active_air_raid_alerts_by_oblast.json",
+            get(statuses_by_oblast),
+        )
+        .route("/v1/iot/active_air_raid_alerts/:file", get(location_status))
+        .route("/v1/locations/:uid/alerts/:file", get(alerts_history))
+        .route("/v1/events", get(events))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server as MockServer;
+    use ralertsinua_http::AlertsInUaClient;
+
+    const ALERTS: &str = r#"{
+        "alerts": [{
+            "alert_type": "air_raid",
+            "finished_at": null,
+            "id": 8757,
+            "location_oblast": "Луганська область",
+            "location_oblast_uid": 16,
+            "location_title": "Луганська область",
+            "location_type": "oblast",
+            "location_uid": "16",
+            "notes": null,
+            "started_at": "2022-04-04T16:45:39.000Z",
+            "updated_at": "2023-10-29T18:22:37.357Z"
+        }],
+        "disclaimer": "",
+        "meta": { "last_updated_at": "2024/05/06 10:02:45 +0000", "type": "full" }
+    }"#;
+
+    async fn serve(state: SharedState) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_client_through_proxy() {
+        let mut upstream = MockServer::new_async().await;
+        let history = upstream
+            .mock("GET", "/v1/locations/31/alerts/month_ago.json")
+            .with_body(ALERTS)
+            .expect(1)
+            .create_async()
+            .await;
+        let upstream_client = Arc::new(AlertsInUaClient::new(&upstream.url(), "token"));
+        let state = Arc::new(ProxyState::new(upstream_client));
+        let statuses = AirRaidAlertOblastStatuses::new(
+            "ANNAANNANNNPANANANNNNAANNNN".to_string(),
+            Some(true),
+        );
+        state.set_statuses(&statuses, vec![]).await;
+        state
+            .set_alerts(&serde_json::from_str(ALERTS).unwrap())
+            .await;
+        let proxy_url = serve(state).await;
+
+        let client = AlertsInUaClient::new(&proxy_url, "");
+        let alerts = client.get_active_alerts().await.unwrap();
+        assert_eq!(alerts.len(), 1);
+        let received = client
+            .get_air_raid_alert_statuses_by_location()
+            .await
+            .unwrap();
+        assert_eq!(received.raw_data(), statuses.raw_data());
+        // second request is answered with 304 and served from the client cache
+        let received = client
+            .get_air_raid_alert_statuses_by_location()
+            .await
+            .unwrap();
+        assert_eq!(received.raw_data(), statuses.raw_data());
+        let stats = client.stats();
+        assert_eq!(
+            stats.endpoints["/iot/active_air_raid_alerts_by_oblast.json"].not_modified,
+            1
+        );
+
+        // history is fetched upstream once and then served from the proxy cache
+        for _ in 0..2 {
+            let alerts = client.get_alerts_history(&31, "month_ago").await.unwrap();
+            assert_eq!(alerts.len(), 1);
+        }
+        history.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_not_polled_yet() {
+        let upstream = MockServer::new_async().await;
+        let state = Arc::new(ProxyState::new(Arc::new(AlertsInUaClient::new(
+            &upstream.url(),
+            "token",
+        ))));
+        let proxy_url = serve(state).await;
+
+        let client = AlertsInUaClient::new(&proxy_url, "");
+        assert!(client.get_active_alerts().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_on_demand_rate_limit() {
+        let mut upstream = MockServer::new_async().await;
+        let _status = upstream
+            .mock(
+                "GET",
+                mockito::Matcher::Regex("^/v1/iot/active_air_raid_alerts/.*".into()),
+            )
+            .with_body(r#""A""#)
+            .create_async()
+            .await;
+        let state =
+            ProxyState::new(Arc::new(AlertsInUaClient::new(&upstream.url(), "token")));
+        let fetch = |uid: i8| {
+            move |api: Arc<dyn AlertsInUaApi>| async move {
+                api.get_air_raid_alert_status(&uid).await
+            }
+        };
+
+        // history requests are limited across locations
+        for uid in [31, 14] {
+            let history = state
+                .on_demand(format!("history/{}", uid), HISTORY_TTL, true, fetch(uid))
+                .await;
+            assert_eq!(history.unwrap().body, "A");
+        }
+        let history = state
+            .on_demand("history/9".into(), HISTORY_TTL, true, fetch(9))
+            .await;
+        assert_eq!(history.unwrap_err(), StatusCode::TOO_MANY_REQUESTS);
+
+        // polling shares the budget with on-demand requests
+        for _ in 2..UPSTREAM_REQUESTS_PER_MINUTE - 1 {
+            state.record_upstream_request().await;
+        }
+        let status = state
+            .on_demand("status/31".into(), LOCATION_STATUS_TTL, false, fetch(31))
+            .await;
+        assert_eq!(status.unwrap().body, "A");
+        let status = state
+            .on_demand("status/14".into(), LOCATION_STATUS_TTL, false, fetch(14))
+            .await;
+        assert_eq!(status.unwrap_err(), StatusCode::TOO_MANY_REQUESTS);
+        // fresh data is served from cache
+        let status = state
+            .on_demand("status/31".into(), LOCATION_STATUS_TTL, false, fetch(31))
+            .await;
+        assert!(status.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_status_changes_broadcast() {
+        let upstream = MockServer::new_async().await;
+        let state =
+            ProxyState::new(Arc::new(AlertsInUaClient::new(&upstream.url(), "token")));
+        let mut rx = state.subscribe();
+        let previous = AirRaidAlertOblastStatuses::new("N".repeat(27), Some(true));
+        let current = AirRaidAlertOblastStatuses::new(
+            "ANNAANNANNNPANANANNNNAANNNN".to_string(),
+            Some(true),
+        );
+
+        state.set_statuses(&current, current.diff(&previous)).await;
+
+        let change = rx.recv().await.unwrap();
+        assert_eq!(change.location_uid, 29);
+        assert!(change.is_alert_start());
+    }
+
+    #[test]
+    fn test_parse_json_segment() {
+        assert_eq!(parse_json_segment("31.json"), Some("31"));
+        assert_eq!(parse_json_segment("month_ago.json"), Some("month_ago"));
+        assert_eq!(parse_json_segment(".json"), None);
+        assert_eq!(parse_json_segment("31"), None);
+    }
+}
@@ -0,0 +1,472 @@
+use clap::ValueEnum;
+use miette::{miette, IntoDiagnostic, Result};
+use ralertsinua_geo::*;
+use ralertsinua_models::*;
+use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    layout::Constraint::{Length, Min},
+    prelude::*,
+    symbols::Marker,
+    widgets::canvas::Canvas,
+};
+use rust_i18n::t;
+use std::{fmt::Write as _, path::Path};
+use time::OffsetDateTime;
+
+use crate::theme::Theme;
+
+/// Default size of SVG and PNG in pixels
+pub const DEFAULT_IMAGE_SIZE: (u16, u16) = (1000, 700);
+/// Default size of the text snapshot in cells
+pub const DEFAULT_TEXT_SIZE: (u16, u16) = (100, 36);
+
+/// Height of the title and of the legend rows of the image in pixels
+const IMAGE_ROW: f64 = 36.0;
+/// Families found on most systems first, PNG has no font fallback
+const IMAGE_FONT: &str =
+    "font-family=\"DejaVu Sans, Arial, Helvetica, sans-serif\" font-size=\"18\"";
+const LEGEND: [AlertStatus; 3] = [AlertStatus::A, AlertStatus::P, AlertStatus::N];
+
+/// Output of the map renderer
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RenderFormat {
+    #[default]
+    Svg,
+    Png,
+    /// Braille canvas as plain text
+    Text,
+    /// Braille canvas with ANSI colors
+    Ansi,
+}
+
+impl RenderFormat {
+    /// Format by the extension of the file: svg, png, txt or ans
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "svg" => Some(Self::Svg),
+            "png" => Some(Self::Png),
+            "txt" => Some(Self::Text),
+            "ans" | "ansi" => Some(Self::Ansi),
+            _ => None,
+        }
+    }
+}
+
+/// Size is in pixels for images and in cells for text
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    pub width: u16,
+    pub height: u16,
+    pub legend: bool,
+    pub locale: String,
+    /// Time shown after the title, none to hide it
+    pub timestamp: Option<OffsetDateTime>,
+    pub theme: Theme,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_IMAGE_SIZE.0,
+            height: DEFAULT_IMAGE_SIZE.1,
+            legend: true,
+            locale: "en".to_string(),
+            timestamp: None,
+            theme: Theme::default(),
+        }
+    }
+}
+
+/// Renders the status map without a terminal
+#[derive(Debug)]
+pub struct MapRenderer<'a> {
+    geo_client: &'a dyn AlertsInUaGeo,
+    /// Larger first, cities are drawn over the oblasts around them
+    locations: Vec<Location>,
+    statuses: &'a AirRaidAlertOblastStatuses,
+    options: RenderOptions,
+}
+
+/// Hex of the color in SVG, none for the default and indexed colors of the terminal
+fn svg_color(color: Color) -> Option<String> {
+    let hex = match color {
+        Color::Black => "#000000",
+        Color::Red => "#d32f2f",
+        Color::Green => "#388e3c",
+        Color::Yellow => "#fbc02d",
+        Color::Blue => "#1976d2",
+        Color::Magenta => "#7b1fa2",
+        Color::Cyan => "#0097a7",
+        Color::Gray => "#9e9e9e",
+        Color::DarkGray => "#616161",
+        Color::LightRed => "#ef5350",
+        Color::LightGreen => "#66bb6a",
+        Color::LightYellow => "#fff176",
+        Color::LightBlue => "#42a5f5",
+        Color::LightMagenta => "#ba68c8",
+        Color::LightCyan => "#4dd0e1",
+        Color::White => "#ffffff",
+        Color::Rgb(r, g, b) => return Some(format!("#{:02x}{:02x}{:02x}", r, g, b)),
+        Color::Reset | Color::Indexed(_) => return None,
+    };
+    Some(hex.to_string())
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// SGR parameters of the foreground color
+fn ansi_color(color: Color) -> String {
+    match color {
+        Color::Reset => "39".to_string(),
+        Color::Black => "30".to_string(),
+        Color::Red => "31".to_string(),
+        Color::Green => "32".to_string(),
+        Color::Yellow => "33".to_string(),
+        Color::Blue => "34".to_string(),
+        Color::Magenta => "35".to_string(),
+        Color::Cyan => "36".to_string(),
+        Color::Gray => "37".to_string(),
+        Color::DarkGray => "90".to_string(),
+        Color::LightRed => "91".to_string(),
+        Color::LightGreen => "92".to_string(),
+        Color::LightYellow => "93".to_string(),
+        Color::LightBlue => "94".to_string(),
+        Color::LightMagenta => "95".to_string(),
+        Color::LightCyan => "96".to_string(),
+        Color::White => "97".to_string(),
+        Color::Rgb(r, g, b) => format!("38;2;{};{};{}", r, g, b),
+        Color::Indexed(i) => format!("38;5;{}", i),
+    }
+}
+
+/// Lines of the buffer without trailing spaces, with foreground colors as ANSI escapes
+pub fn buffer_to_string(buffer: &Buffer, ansi: bool) -> String {
+    let area = buffer.area;
+    let mut out = String::new();
+    for y in area.top()..area.bottom() {
+        let mut line = String::new();
+        let mut color = Color::Reset;
+        for x in area.left()..area.right() {
+            let cell = buffer.get(x, y);
+            if ansi && cell.fg != color {
+                color = cell.fg;
+                let _ = write!(line, "\x1b[{}m", ansi_color(color));
+            }
+            line.push_str(cell.symbol());
+        }
+        let mut line = line.trim_end().to_string();
+        if ansi && color != Color::Reset {
+            line.push_str("\x1b[0m");
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+impl<'a> MapRenderer<'a> {
+    pub fn new(
+        geo_client: &'a dyn AlertsInUaGeo,
+        statuses: &'a AirRaidAlertOblastStatuses,
+        options: RenderOptions,
+    ) -> Self {
+        let mut locations = geo_client.locations().to_vec();
+        locations.sort_by(|a, b| {
+            let area =
+                |l: &Location| l.bounding_rect().width() * l.bounding_rect().height();
+            area(b).total_cmp(&area(a))
+        });
+        Self {
+            geo_client,
+            locations,
+            statuses,
+            options,
+        }
+    }
+
+    fn status(&self, location_uid: i32) -> AlertStatus {
+        self.statuses
+            .get_by_location_uid(location_uid)
+            .map(|s| s.status().clone())
+            .unwrap_or(AlertStatus::O)
+    }
+
+    fn title(&self) -> String {
+        let locale = self.options.locale.as_str();
+        let title = t!("render.title", locale = locale).to_string();
+        match self.options.timestamp {
+            Some(time) => format!(
+                "{} · {}-{:02}-{:02} {:02}:{:02} UTC",
+                title,
+                time.year(),
+                time.month() as u8,
+                time.day(),
+                time.hour(),
+                time.minute()
+            ),
+            None => title,
+        }
+    }
+
+    /// Color of the status in SVG, the one of the default theme when the theme has none, e.g.
+    /// with `NO_COLOR`
+    fn status_svg_color(&self, status: &AlertStatus) -> String {
+        svg_color(self.options.theme.status(status))
+            .or_else(|| svg_color(Theme::default().status(status)))
+            .unwrap_or_default()
+    }
+
+    fn legend_label(&self, status: &AlertStatus) -> String {
+        let key = format!("render.legend.{}", char::from(status));
+        t!(&key, locale = self.options.locale.as_str()).to_string()
+    }
+
+    /// Map as SVG with the title at the top and the legend at the bottom
+    pub fn svg(&self) -> String {
+        let (width, height) = (
+            f64::from(self.options.width),
+            f64::from(self.options.height),
+        );
+        let legend_height = if self.options.legend { IMAGE_ROW } else { 0.0 };
+        let map_height = (height - IMAGE_ROW - legend_height).max(1.0);
+        let viewport = self.geo_client.get_viewport((width, map_height));
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            w = width,
+            h = height
+        );
+        let _ = writeln!(
+            svg,
+            "<defs><pattern id=\"partial\" patternUnits=\"userSpaceOnUse\" width=\"8\" height=\"8\" patternTransform=\"rotate(45)\"><rect width=\"8\" height=\"8\" fill=\"{}\"/><rect width=\"4\" height=\"8\" fill=\"{}\"/></pattern></defs>",
+            self.status_svg_color(&AlertStatus::N),
+            self.status_svg_color(&AlertStatus::P)
+        );
+        let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>");
+        let _ = writeln!(
+            svg,
+            "<text x=\"12\" y=\"{}\" {}>{}</text>",
+            IMAGE_ROW * 0.7,
+            IMAGE_FONT,
+            escape_xml(&self.title())
+        );
+
+        let fill = |status: &AlertStatus| match status {
+            AlertStatus::P => "url(#partial)".to_string(),
+            _ => self.status_svg_color(status),
+        };
+        let _ = writeln!(
+            svg,
+            "<g transform=\"translate(0 {})\" stroke=\"white\" stroke-width=\"1\" fill-rule=\"evenodd\">",
+            IMAGE_ROW
+        );
+        for location in &self.locations {
+            let mut path = String::new();
+            for ring in rings(location.geometry()) {
+                for (i, coord) in ring.coords().enumerate() {
+                    let (x, y) = viewport.to_grid(coord.x, coord.y);
+                    let command = if i == 0 { 'M' } else { 'L' };
+                    let _ = write!(path, "{}{:.1} {:.1}", command, x, y);
+                }
+                path.push('Z');
+            }
+            let status = self.status(location.location_uid);
+            let _ = writeln!(
+                svg,
+                "<path data-uid=\"{}\" fill=\"{}\" d=\"{}\"><title>{}</title></path>",
+                location.location_uid,
+                fill(&status),
+                path,
+                escape_xml(match self.options.locale.as_str() {
+                    "uk" => location.name(),
+                    _ => location.name_en(),
+                })
+            );
+        }
+        let _ = writeln!(svg, "</g>");
+
+        if self.options.legend {
+            let y = height - IMAGE_ROW;
+            for (i, status) in LEGEND.iter().enumerate() {
+                let x = 12.0 + i as f64 * width / LEGEND.len() as f64;
+                let _ = writeln!(
+                    svg,
+                    "<rect x=\"{}\" y=\"{}\" width=\"18\" height=\"18\" fill=\"{}\"/><text x=\"{}\" y=\"{}\" {}>{}</text>",
+                    x,
+                    y + 8.0,
+                    fill(status),
+                    x + 26.0,
+                    y + 23.0,
+                    IMAGE_FONT,
+                    escape_xml(&self.legend_label(status))
+                );
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Map as PNG, rasterized from the SVG with the system fonts
+    pub fn png(&self) -> Result<Vec<u8>> {
+        let mut options = resvg::usvg::Options::default();
+        options.fontdb_mut().load_system_fonts();
+        let tree = resvg::usvg::Tree::from_str(&self.svg(), &options).into_diagnostic()?;
+        let mut pixmap = resvg::tiny_skia::Pixmap::new(
+            self.options.width.into(),
+            self.options.height.into(),
+        )
+        .ok_or_else(|| miette!("invalid image size"))?;
+        resvg::render(
+            &tree,
+            resvg::tiny_skia::Transform::default(),
+            &mut pixmap.as_mut(),
+        );
+        pixmap.encode_png().into_diagnostic()
+    }
+
+    /// Map on the Braille canvas, drawn to an off-screen buffer
+    pub fn buffer(&self) -> Result<Buffer> {
+        let backend = TestBackend::new(self.options.width, self.options.height);
+        let mut terminal = Terminal::new(backend).into_diagnostic()?;
+        let theme = &self.options.theme;
+        terminal
+            .draw(|f| {
+                let legend_height = u16::from(self.options.legend);
+                let [title_area, map_area, legend_area] =
+                    Layout::vertical([Length(1), Min(0), Length(legend_height)])
+                        .areas(f.size());
+                f.render_widget(Line::from(self.title()).bold(), title_area);
+
+                let resolution = (
+                    f64::from(map_area.width) * 2.0,
+                    f64::from(map_area.height) * 4.0,
+                );
+                let viewport = self.geo_client.get_viewport(resolution);
+                let (x_bounds, y_bounds) = viewport.bounds();
+                let canvas = Canvas::default()
+                    .marker(Marker::Braille)
+                    .x_bounds(x_bounds)
+                    .y_bounds(y_bounds)
+                    .paint(|ctx| {
+                        self.locations.iter().for_each(|l| {
+                            let status = self.status(l.location_uid);
+                            if let Some(pattern) = theme.fill_pattern(&status) {
+                                ctx.draw(&Fill {
+                                    geometry: l.geometry(),
+                                    viewport,
+                                    color: theme.status(&status),
+                                    pattern,
+                                });
+                            }
+                        });
+                        self.locations.iter().for_each(|l| {
+                            ctx.draw(&Outline::new(l.geometry(), viewport, theme.outline))
+                        });
+                    });
+                f.render_widget(canvas, map_area);
+
+                if self.options.legend {
+                    let spans: Vec<Span> = LEGEND
+                        .iter()
+                        .flat_map(|status| {
+                            [
+                                Span::styled("■ ", theme.status(status)),
+                                Span::raw(format!("{}  ", self.legend_label(status))),
+                            ]
+                        })
+                        .collect();
+                    f.render_widget(Line::from(spans), legend_area);
+                }
+            })
+            .into_diagnostic()?;
+        Ok(terminal.backend().buffer().clone())
+    }
+
+    /// Map in the format
+    pub fn render(&self, format: RenderFormat) -> Result<Vec<u8>> {
+        match format {
+            RenderFormat::Svg => Ok(self.svg().into_bytes()),
+            RenderFormat::Png => self.png(),
+            RenderFormat::Text => Ok(buffer_to_string(&self.buffer()?, false).into_bytes()),
+            RenderFormat::Ansi => Ok(buffer_to_string(&self.buffer()?, true).into_bytes()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statuses() -> AirRaidAlertOblastStatuses {
+        AirRaidAlertOblastStatuses::new(
+            "ANNAANNANNNPANANANNNNAANNNN".to_string(),
+            Some(false),
+        )
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let geo_client = AlertsInUaGeoClient::default();
+        let statuses = statuses();
+        let options = RenderOptions {
+            locale: "uk".to_string(),
+            timestamp: OffsetDateTime::from_unix_timestamp(1_714_867_200).ok(),
+            ..Default::default()
+        };
+        let svg = MapRenderer::new(&geo_client, &statuses, options).svg();
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<path ").count(), 27);
+        assert_eq!(svg.matches("fill=\"url(#partial)\"").count(), 2);
+        assert!(svg.contains("2024-05-05 00:00 UTC"));
+        assert!(svg.contains("<title>Київ</title>"));
+
+        let png = MapRenderer::new(
+            &geo_client,
+            &statuses,
+            RenderOptions {
+                width: 200,
+                height: 150,
+                legend: false,
+                ..Default::default()
+            },
+        )
+        .png()
+        .unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+
+    #[test]
+    fn test_render_text() {
+        let geo_client = AlertsInUaGeoClient::default();
+        let statuses = statuses();
+        let options = RenderOptions {
+            width: 60,
+            height: 20,
+            ..Default::default()
+        };
+        let renderer = MapRenderer::new(&geo_client, &statuses, options);
+        let text = String::from_utf8(renderer.render(RenderFormat::Text).unwrap()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 20);
+        assert!(lines[0].starts_with("Air raid alerts in Ukraine"));
+        assert!(lines[1..19].iter().any(|l| l.contains('⣿')));
+        assert!(lines[19].contains("■ Air raid alert"));
+        assert!(!text.contains('\x1b'));
+
+        let ansi = String::from_utf8(renderer.render(RenderFormat::Ansi).unwrap()).unwrap();
+        assert!(ansi.contains("\x1b[31m"));
+        assert_eq!(
+            RenderFormat::from_path(Path::new("map.PNG")),
+            Some(RenderFormat::Png)
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use ralertsinua_models::*;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::{collections::HashSet, path::Path};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::{error::AppError, utils::get_data_dir};
+
+type Result<T> = miette::Result<T, AppError>;
+
+/// Version of the schema below, stored in `PRAGMA user_version`
+const SCHEMA_VERSION: i32 = 1;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS alerts (
+    id INTEGER PRIMARY KEY,
+    location_uid INTEGER NOT NULL,
+    location_oblast_uid INTEGER NOT NULL,
+    alert_type TEXT NOT NULL,
+    started_at INTEGER NOT NULL,
+    finished_at INTEGER,
+    updated_at INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS alerts_location ON alerts (location_uid, started_at);
+CREATE INDEX IF NOT EXISTS alerts_oblast ON alerts (location_oblast_uid, started_at);
+CREATE TABLE IF NOT EXISTS transitions (
+    location_uid INTEGER NOT NULL,
+    observed_at INTEGER NOT NULL,
+    current TEXT NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (location_uid, observed_at, current)
+);
+";
+
+/// Alerts to select from the store, empty conditions match any alert
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AlertQuery {
+    /// Alerts of the location or of the smaller locations inside of it
+    pub location_uid: Option<i32>,
+    pub alert_type: Option<AlertType>,
+    /// Alerts which were not over by that time
+    pub from: Option<OffsetDateTime>,
+    /// Alerts started before that time
+    pub to: Option<OffsetDateTime>,
+}
+
+/// Status transition of an oblast with the time it was noticed
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredTransition {
+    pub change: AirRaidAlertOblastStatusChange,
+    pub observed_at: OffsetDateTime,
+}
+
+/// Local SQLite database of every alert and status transition observed, it keeps the
+/// history beyond the month the API offers
+#[derive(Debug)]
+pub struct AlertStore {
+    conn: Connection,
+}
+
+impl AlertStore {
+    /// Open the database at `alerts.sqlite` in the data directory
+    pub fn open_default() -> Result<Self> {
+        let dir = get_data_dir();
+        std::fs::create_dir_all(&dir)?;
+        Self::open(&dir.join("alerts.sqlite"))
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        if version < SCHEMA_VERSION {
+            conn.execute_batch(SCHEMA)?;
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        }
+        Ok(Self { conn })
+    }
+
+    /// Insert new alerts and update the known ones whose end or update time changed.
+    /// Returns the number of alerts inserted or updated
+    pub fn save_alerts<'a, I>(&mut self, alerts: I) -> Result<usize>
+    where
+        I: IntoIterator<Item = &'a Alert>,
+    {
+        let tx = self.conn.transaction()?;
+        let mut changed = 0;
+        {
+            let mut statement = tx.prepare_cached(
+                "INSERT INTO alerts (id, location_uid, location_oblast_uid, alert_type,
+                    started_at, finished_at, updated_at, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT (id) DO UPDATE SET
+                    finished_at = excluded.finished_at,
+                    updated_at = excluded.updated_at,
+                    data = excluded.data
+                 WHERE finished_at IS NOT excluded.finished_at
+                    OR updated_at != excluded.updated_at",
+            )?;
+            for alert in alerts {
+                changed += statement.execute(params![
+                    alert.id,
+                    alert.location_uid,
+                    alert.location_oblast_uid,
+                    alert.alert_type.to_string(),
+                    alert.started_at.unix_timestamp(),
+                    alert.get_finished_at().map(|t| t.unix_timestamp()),
+                    alert.updated_at.unix_timestamp(),
+                    serde_json::to_string(alert)?,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    /// Mark the stored alerts which are not over, but are not active anymore, as finished at
+    /// the time. The history from the API corrects the time later.
+    /// Returns the number of alerts finished
+    pub fn finish_missing(&mut self, active: &Alerts, at: OffsetDateTime) -> Result<usize> {
+        let active: HashSet<i32> = active.iter().map(|a| a.id).collect();
+        let finished_at = at.format(&Rfc3339).map_err(|_| AppError::Unknown)?;
+        let mut statement = self
+            .conn
+            .prepare_cached("SELECT data FROM alerts WHERE finished_at IS NULL")?;
+        let unfinished = statement
+            .query_map([], |r| r.get::<_, String>(0))?
+            .map(|data| Ok(serde_json::from_str(&data?)?))
+            .collect::<Result<Vec<Alert>>>()?;
+        drop(statement);
+        let finished: Vec<Alert> = unfinished
+            .into_iter()
+            .filter(|a| !active.contains(&a.id))
+            .map(|a| Alert {
+                finished_at: Some(finished_at.clone()),
+                ..a
+            })
+            .collect();
+        self.save_alerts(&finished)
+    }
+
+    /// Alerts matching the query, the oldest first
+    pub fn query_alerts(&self, query: &AlertQuery) -> Result<Vec<Alert>> {
+        let mut statement = self.conn.prepare_cached(
+            "SELECT data FROM alerts
+             WHERE (?1 IS NULL OR location_uid = ?1 OR location_oblast_uid = ?1)
+                AND (?2 IS NULL OR alert_type = ?2)
+                AND (?3 IS NULL OR finished_at IS NULL OR finished_at >= ?3)
+                AND (?4 IS NULL OR started_at < ?4)
+             ORDER BY started_at, id",
+        )?;
+        let rows = statement.query_map(
+            params![
+                query.location_uid,
+                query.alert_type.as_ref().map(ToString::to_string),
+                query.from.map(OffsetDateTime::unix_timestamp),
+                query.to.map(OffsetDateTime::unix_timestamp),
+            ],
+            |r| r.get::<_, String>(0),
+        )?;
+        rows.map(|data| Ok(serde_json::from_str(&data?)?)).collect()
+    }
+
+    /// Alert by its id, if it was observed
+    pub fn get_alert(&self, id: i32) -> Result<Option<Alert>> {
+        let data: Option<String> = self
+            .conn
+            .query_row("SELECT data FROM alerts WHERE id = ?1", [id], |r| r.get(0))
+            .optional()?;
+        Ok(data.map(|d| serde_json::from_str(&d)).transpose()?)
+    }
+
+    /// Save the transitions, the same transition noticed at the same time is saved once.
+    /// Returns the number of transitions saved
+    pub fn save_transitions(
+        &mut self,
+        changes: &[AirRaidAlertOblastStatusChange],
+        observed_at: OffsetDateTime,
+    ) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut saved = 0;
+        {
+            let mut statement = tx.prepare_cached(
+                "INSERT OR IGNORE INTO transitions (location_uid, observed_at, current, data)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for change in changes {
+                saved += statement.execute(params![
+                    change.location_uid,
+                    observed_at.unix_timestamp(),
+                    change.current().to_string(),
+                    serde_json::to_string(change)?,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(saved)
+    }
+
+    /// Transitions of the location, or of every one, within the time range, the oldest first
+    pub fn query_transitions(
+        &self,
+        location_uid: Option<i32>,
+        from: Option<OffsetDateTime>,
+        to: Option<OffsetDateTime>,
+    ) -> Result<Vec<StoredTransition>> {
+        let mut statement = self.conn.prepare_cached(
+            "SELECT data, observed_at FROM transitions
+             WHERE (?1 IS NULL OR location_uid = ?1)
+                AND (?2 IS NULL OR observed_at >= ?2)
+                AND (?3 IS NULL OR observed_at < ?3)
+             ORDER BY observed_at, location_uid",
+        )?;
+        let rows = statement.query_map(
+            params![
+                location_uid,
+                from.map(OffsetDateTime::unix_timestamp),
+                to.map(OffsetDateTime::unix_timestamp),
+            ],
+            |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?)),
+        )?;
+        rows.map(|row| {
+            let (data, observed_at) = row?;
+            Ok(StoredTransition {
+                change: serde_json::from_str(&data)?,
+                observed_at: OffsetDateTime::from_unix_timestamp(observed_at)
+                    .unwrap_or(OffsetDateTime::UNIX_EPOCH),
+            })
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ralertsinua_models::test_util::{at, AlertBuilder};
+
+    fn alert(id: i32, uid: i32, oblast_uid: i32, from: i64, to: Option<i64>) -> Alert {
+        let alert_type = match id % 2 {
+            0 => AlertType::ArtilleryShelling,
+            _ => AlertType::AirRaid,
+        };
+        AlertBuilder::new(id)
+            .location(uid, oblast_uid)
+            .alert_type(alert_type)
+            .hours(from, to)
+            .build()
+    }
+
+    fn ids(alerts: &[Alert]) -> Vec<i32> {
+        alerts.iter().map(|a| a.id).collect()
+    }
+
+    #[test]
+    fn test_save_alerts() {
+        let mut store = AlertStore::open_in_memory().unwrap();
+        let active = alert(1, 14, 14, 0, None);
+        assert_eq!(
+            store
+                .save_alerts([&active, &alert(2, 1000, 14, 1, Some(2))])
+                .unwrap(),
+            2
+        );
+        // unchanged alerts are not written again
+        assert_eq!(store.save_alerts([&active]).unwrap(), 0);
+
+        let finished = alert(1, 14, 14, 0, Some(3));
+        assert_eq!(store.save_alerts([&finished]).unwrap(), 1);
+        assert_eq!(store.get_alert(1).unwrap(), Some(finished));
+        assert_eq!(store.get_alert(3).unwrap(), None);
+
+        // the alert is not active anymore
+        let active = alert(3, 31, 31, 2, None);
+        store.save_alerts([&active]).unwrap();
+        assert_eq!(
+            store
+                .finish_missing(&Alerts::new(vec![active]), at(4))
+                .unwrap(),
+            0
+        );
+        assert_eq!(store.finish_missing(&Alerts::default(), at(5)).unwrap(), 1);
+        let finished = store.get_alert(3).unwrap().unwrap();
+        assert_eq!(finished.get_finished_at(), Some(at(5)));
+    }
+
+    #[test]
+    fn test_query_alerts() {
+        let mut store = AlertStore::open_in_memory().unwrap();
+        let alerts = [
+            alert(1, 14, 14, 0, Some(3)),
+            // hromada inside of the oblast
+            alert(2, 1000, 14, 1, Some(2)),
+            alert(3, 31, 31, 5, None),
+            // a month before
+            alert(5, 14, 14, -24 * 30, Some(-24 * 30 + 1)),
+        ];
+        store.save_alerts(&alerts).unwrap();
+
+        let query = |query: AlertQuery| ids(&store.query_alerts(&query).unwrap());
+        assert_eq!(query(AlertQuery::default()), vec![5, 1, 2, 3]);
+        let oblast = AlertQuery {
+            location_uid: Some(14),
+            ..Default::default()
+        };
+        assert_eq!(query(oblast.clone()), vec![5, 1, 2]);
+        assert_eq!(
+            query(AlertQuery {
+                alert_type: Some(AlertType::AirRaid),
+                ..oblast.clone()
+            }),
+            vec![5, 1]
+        );
+        // overlapping the range, the active one lasts until now
+        let range = AlertQuery {
+            from: Some(at(2)),
+            to: Some(at(6)),
+            ..Default::default()
+        };
+        assert_eq!(query(range), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_transitions() {
+        let mut store = AlertStore::open_in_memory().unwrap();
+        let previous =
+            AirRaidAlertOblastStatuses::new("NNNNNNNNNNNNNNNNNNNNNNNNNNN".into(), None);
+        let current =
+            AirRaidAlertOblastStatuses::new("NNNNNNNNNNNNNNNNNNNNNNNNANN".into(), None);
+        let changes = current.diff(&previous);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(store.save_transitions(&changes, at(0)).unwrap(), 1);
+        assert_eq!(store.save_transitions(&changes, at(0)).unwrap(), 0);
+        assert_eq!(store.save_transitions(&changes, at(1)).unwrap(), 1);
+
+        let uid = changes[0].location_uid;
+        let stored = store
+            .query_transitions(Some(uid), Some(at(1)), None)
+            .unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].change, changes[0]);
+        assert_eq!(stored[0].observed_at, at(1));
+        assert!(store
+            .query_transitions(Some(uid + 1), None, None)
+            .unwrap()
+            .is_empty());
+    }
+}
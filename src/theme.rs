@@ -0,0 +1,300 @@
+use ralertsinua_geo::FillPattern;
+use ralertsinua_models::AlertStatus;
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use strum::{Display, EnumString, VariantNames};
+
+/// Built-in palettes, `theme` setting
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Display, EnumString, VariantNames)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ThemeName {
+    /// Named terminal colors, follows the palette of the terminal
+    #[default]
+    Dark,
+    Light,
+    /// Bright colors and strong selection
+    HighContrast,
+    /// Okabe-Ito palette, alerts and partial alerts differ in lightness as well as hue
+    Colorblind,
+}
+
+/// Colors every component draws with, the built-in palette with overrides from the
+/// `[colors]` table of the config file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+    /// Air raid alert on the whole oblast
+    pub alert: Color,
+    /// Alert on a part of the oblast
+    pub partial: Color,
+    pub no_alert: Color,
+    pub loading: Color,
+    pub offline: Color,
+    pub text: Color,
+    /// Titles of secondary views and help descriptions
+    pub accent: Color,
+    /// Stale data and hints
+    pub muted: Color,
+    /// Selected row and tab
+    pub selected: Color,
+    /// Borders of locations on the map
+    pub outline: Color,
+    pub selected_outline: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub success: Color,
+    pub info: Color,
+    pub banner_fg: Color,
+    pub banner_bg: Color,
+    /// Text on the popup of the selected location, over the color of its status
+    pub popup_fg: Color,
+    /// Behind the map, its popup and the selected row, the terminal one by default
+    pub background: Color,
+    /// No colors at all, statuses differ by fill patterns only
+    #[serde(skip)]
+    pub monochrome: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::builtin(ThemeName::default())
+    }
+}
+
+impl Theme {
+    /// Names of the colors in the `[colors]` table
+    pub const COLORS: &'static [&'static str] = &[
+        "alert",
+        "partial",
+        "no_alert",
+        "loading",
+        "offline",
+        "text",
+        "accent",
+        "muted",
+        "selected",
+        "outline",
+        "selected_outline",
+        "warning",
+        "error",
+        "success",
+        "info",
+        "banner_fg",
+        "banner_bg",
+        "popup_fg",
+        "background",
+    ];
+
+    pub fn builtin(name: ThemeName) -> Self {
+        let dark = Self {
+            alert: Color::Red,
+            partial: Color::Yellow,
+            no_alert: Color::Blue,
+            loading: Color::Gray,
+            offline: Color::DarkGray,
+            text: Color::Reset,
+            accent: Color::LightBlue,
+            muted: Color::DarkGray,
+            selected: Color::Blue,
+            outline: Color::Reset,
+            selected_outline: Color::White,
+            warning: Color::Yellow,
+            error: Color::Red,
+            success: Color::Green,
+            info: Color::Cyan,
+            banner_fg: Color::White,
+            banner_bg: Color::Red,
+            popup_fg: Color::White,
+            background: Color::Reset,
+            monochrome: false,
+        };
+        match name {
+            ThemeName::Dark => dark,
+            ThemeName::Light => Self {
+                alert: Color::Rgb(211, 47, 47),
+                partial: Color::Rgb(230, 145, 0),
+                no_alert: Color::Rgb(25, 118, 210),
+                loading: Color::DarkGray,
+                offline: Color::Gray,
+                accent: Color::Blue,
+                muted: Color::Gray,
+                selected_outline: Color::Black,
+                warning: Color::Rgb(230, 145, 0),
+                info: Color::Blue,
+                ..dark
+            },
+            ThemeName::HighContrast => Self {
+                alert: Color::LightRed,
+                partial: Color::LightYellow,
+                no_alert: Color::LightCyan,
+                loading: Color::White,
+                offline: Color::Gray,
+                accent: Color::LightCyan,
+                muted: Color::Gray,
+                selected: Color::LightYellow,
+                selected_outline: Color::LightMagenta,
+                warning: Color::LightYellow,
+                error: Color::LightRed,
+                success: Color::LightGreen,
+                info: Color::LightCyan,
+                banner_fg: Color::Black,
+                banner_bg: Color::LightYellow,
+                popup_fg: Color::Black,
+                ..dark
+            },
+            ThemeName::Colorblind => Self {
+                // vermillion, yellow and blue
+                alert: Color::Rgb(213, 94, 0),
+                partial: Color::Rgb(240, 228, 66),
+                no_alert: Color::Rgb(0, 114, 178),
+                accent: Color::Rgb(86, 180, 233),
+                selected: Color::Rgb(86, 180, 233),
+                warning: Color::Rgb(230, 159, 0),
+                error: Color::Rgb(213, 94, 0),
+                success: Color::Rgb(0, 158, 115),
+                info: Color::Rgb(86, 180, 233),
+                banner_bg: Color::Rgb(213, 94, 0),
+                popup_fg: Color::Black,
+                ..dark
+            },
+        }
+    }
+
+    /// Terminal defaults only, for `NO_COLOR`
+    pub fn no_color() -> Self {
+        let reset = Color::Reset;
+        Self {
+            alert: reset,
+            partial: reset,
+            no_alert: reset,
+            loading: reset,
+            offline: reset,
+            text: reset,
+            accent: reset,
+            muted: reset,
+            selected: reset,
+            outline: reset,
+            selected_outline: reset,
+            warning: reset,
+            error: reset,
+            success: reset,
+            info: reset,
+            banner_fg: reset,
+            banner_bg: reset,
+            popup_fg: reset,
+            background: reset,
+            monochrome: true,
+        }
+    }
+
+    /// The built-in theme, or no colors when `NO_COLOR` is set to anything but empty
+    pub fn load(name: ThemeName) -> Self {
+        Self::load_with_env(name, |name| std::env::var(name).ok())
+    }
+
+    /// [`Theme::load`] with environment variables looked up by `env`
+    pub fn load_with_env<F>(name: ThemeName, env: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        match env("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            true => Self::no_color(),
+            false => Self::builtin(name),
+        }
+    }
+
+    /// Color of the key in the `[colors]` table
+    fn color_mut(&mut self, key: &str) -> Option<&mut Color> {
+        let color = match key {
+            "alert" => &mut self.alert,
+            "partial" => &mut self.partial,
+            "no_alert" => &mut self.no_alert,
+            "loading" => &mut self.loading,
+            "offline" => &mut self.offline,
+            "text" => &mut self.text,
+            "accent" => &mut self.accent,
+            "muted" => &mut self.muted,
+            "selected" => &mut self.selected,
+            "outline" => &mut self.outline,
+            "selected_outline" => &mut self.selected_outline,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "success" => &mut self.success,
+            "info" => &mut self.info,
+            "banner_fg" => &mut self.banner_fg,
+            "banner_bg" => &mut self.banner_bg,
+            "popup_fg" => &mut self.popup_fg,
+            "background" => &mut self.background,
+            _ => return None,
+        };
+        Some(color)
+    }
+
+    /// Override the color, e.g. `alert` with `"#ff0000"` or `"light-red"`. Ignored without
+    /// colors
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let color =
+            Color::from_str(value).map_err(|_| format!("invalid color '{}'", value))?;
+        let monochrome = self.monochrome;
+        let field = self.color_mut(key).ok_or_else(|| {
+            format!(
+                "unknown color '{}', expected one of: {}",
+                key,
+                Self::COLORS.join(", ")
+            )
+        })?;
+        if !monochrome {
+            *field = color;
+        }
+        Ok(())
+    }
+
+    pub fn status(&self, status: &AlertStatus) -> Color {
+        match status {
+            AlertStatus::A => self.alert,
+            AlertStatus::P => self.partial,
+            AlertStatus::N => self.no_alert,
+            AlertStatus::L => self.loading,
+            AlertStatus::O => self.offline,
+        }
+    }
+
+    /// How the location of the status is filled on the map, hatched for partial alerts.
+    /// Without colors only the alerts are filled
+    pub fn fill_pattern(&self, status: &AlertStatus) -> Option<FillPattern> {
+        match status {
+            AlertStatus::P => Some(FillPattern::Hatched),
+            AlertStatus::A => Some(FillPattern::Solid),
+            _ if self.monochrome => None,
+            _ => Some(FillPattern::Solid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme() {
+        let name: ThemeName = "high-contrast".parse().unwrap();
+        assert_eq!(name, ThemeName::HighContrast);
+        assert_eq!(ThemeName::Colorblind.to_string(), "colorblind");
+
+        let mut theme = Theme::builtin(ThemeName::Dark);
+        assert_eq!(theme.status(&AlertStatus::A), Color::Red);
+        theme.set("alert", "#ff8800").unwrap();
+        assert_eq!(theme.status(&AlertStatus::A), Color::Rgb(255, 136, 0));
+        assert!(theme.set("alarm", "red").is_err());
+        assert!(theme.set("alert", "reddish").is_err());
+
+        let mut theme = Theme::no_color();
+        theme.set("alert", "red").unwrap();
+        assert_eq!(theme.status(&AlertStatus::A), Color::Reset);
+        assert_eq!(theme.fill_pattern(&AlertStatus::N), None);
+        assert_eq!(
+            theme.fill_pattern(&AlertStatus::P),
+            Some(FillPattern::Hatched)
+        );
+    }
+}
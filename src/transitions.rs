@@ -0,0 +1,33 @@
+use ralertsinua_models::AirRaidAlertOblastStatusChange;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Start or end of an alert, reported to webhooks and hooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransitionKind {
+    Started,
+    Ended,
+}
+
+impl TransitionKind {
+    /// Kind of the status change, partial alerts are neither
+    pub fn of(change: &AirRaidAlertOblastStatusChange) -> Option<Self> {
+        if change.is_alert_start() {
+            Some(TransitionKind::Started)
+        } else if change.is_alert_end() {
+            Some(TransitionKind::Ended)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for TransitionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransitionKind::Started => write!(f, "started"),
+            TransitionKind::Ended => write!(f, "ended"),
+        }
+    }
+}
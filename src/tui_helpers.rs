@@ -1,22 +1,18 @@
 use michie::memoized;
+use ralertsinua_http::{Connectivity, ConnectivityState};
 use ralertsinua_models::AlertStatus;
 use ratatui::{
     layout::{Constraint::*, Offset},
     prelude::*,
 };
+use rust_i18n::t;
 use std::collections::HashMap;
-use std::{rc::Rc, str::FromStr};
+use std::rc::Rc;
 use strum::EnumProperty;
 #[allow(unused_imports)]
 use tracing::{debug, info};
 
-use crate::layout::*;
-
-pub fn get_color_by_status(status: &AlertStatus) -> Color {
-    let color_str: &str = status.get_str("color").unwrap();
-    let color: Color = Color::from_str(color_str).unwrap();
-    color
-}
+use crate::{layout::*, theme::Theme};
 
 /// Builds new [`Line`] with styled text
 // #[memoized(key_expr = input, store_type = HashMap<usize, usize>)]
@@ -24,12 +20,13 @@ pub fn get_styled_line_by_status<'a, S>(
     text: S,
     status: &AlertStatus,
     is_selected: &bool,
+    theme: &Theme,
 ) -> Line<'a>
 where
     S: Into<String>,
 {
     let icon: &str = status.get_str("icon").unwrap();
-    let color = get_color_by_status(status);
+    let color = theme.status(status);
     let mut line: Line = Line::from(format!("{} {}", icon, text.into())).style(color);
 
     if *is_selected {
@@ -52,9 +49,10 @@ where
 pub fn get_styled_line_icon_by_status<'a>(
     status: &AlertStatus,
     is_selected: &bool,
+    theme: &Theme,
 ) -> Line<'a> {
     let icon: &str = status.get_str("icon").unwrap();
-    let color = get_color_by_status(status);
+    let color = theme.status(status);
     let mut line: Line = Line::from(icon).style(color);
 
     if *is_selected {
@@ -67,7 +65,7 @@ pub fn get_styled_line_icon_by_status<'a>(
             .add_modifier(Modifier::RAPID_BLINK),
         AlertStatus::P => line.add_modifier(Modifier::ITALIC),
         AlertStatus::L | AlertStatus::O => {
-            line.add_modifier(Modifier::DIM).style(Color::DarkGray)
+            line.add_modifier(Modifier::DIM).style(theme.muted)
         }
         _ => line,
     };
@@ -75,17 +73,62 @@ pub fn get_styled_line_icon_by_status<'a>(
     line
 }
 
-pub fn get_title_with_online_status<'a, S>(text: S, is_online: &bool) -> Line<'a>
+/// Builds new [`Line`] with the status in words after the text instead of the icon, for
+/// screen readers
+pub fn get_accessible_line_by_status<'a, S>(
+    text: S,
+    status: &AlertStatus,
+    theme: &Theme,
+) -> Line<'a>
 where
     S: Into<String>,
 {
-    #[rustfmt::skip]
-        let suffix: &str = match is_online { true => "", false => ": Offline", };
-    let mut line: Line = Line::from(text.into() + suffix);
+    let key = format!("statuses.{}", char::from(status));
+    Line::from(format!("{}: {}", text.into(), t!(&key))).style(theme.status(status))
+}
+
+/// Formats the age of the last successful update, truncated to whole seconds
+pub fn format_last_update_age(connectivity: &Connectivity) -> Option<String> {
+    connectivity.last_success_age().map(|age| {
+        let age = std::time::Duration::from_secs(age.as_secs());
+        dur::Duration::from_std(age).to_string()
+    })
+}
+
+/// Formats a duration in seconds, negative ones as zero
+pub fn format_seconds(seconds: i64) -> String {
+    let seconds = u64::try_from(seconds).unwrap_or_default();
+    dur::Duration::from_std(std::time::Duration::from_secs(seconds)).to_string()
+}
 
-    if !is_online {
-        line = line.add_modifier(Modifier::DIM).style(Color::DarkGray)
+/// Builds title [`Line`] with connectivity state and age of the last successful update
+///
+/// Components rebuild it on [`Action::Tick`](crate::action::Action::Tick) so the age stays current
+pub fn get_title_with_online_status<'a, S>(
+    text: S,
+    connectivity: &Connectivity,
+    theme: &Theme,
+) -> Line<'a>
+where
+    S: Into<String>,
+{
+    let state = connectivity.state();
+    let mut title: String = text.into();
+    if state != ConnectivityState::Online {
+        title = format!("{}: {}", title, t!(&format!("connectivity.{}", state)));
+    }
+    if let Some(age) = format_last_update_age(connectivity) {
+        title = format!("{} · {}", title, t!("connectivity.updated", age = age));
     }
+    let mut line: Line = Line::from(title);
+
+    line = match state {
+        ConnectivityState::Online => line,
+        ConnectivityState::Pending => line.style(theme.muted),
+        ConnectivityState::Degraded => line.style(theme.warning),
+        ConnectivityState::Offline => line.add_modifier(Modifier::DIM).style(theme.muted),
+        ConnectivityState::Unauthorized => line.style(theme.error),
+    };
 
     line
 }
@@ -110,6 +153,61 @@ pub fn centered_rect(r: Rect, percent_x: u16, percent_y: u16) -> Rect {
         .split(popup_layout[1])[1]
 }
 
+/// Position of the terminal cell inside the bordered area, relative to its top left corner
+pub fn get_inner_position(area: Rect, column: u16, row: u16) -> Option<(u16, u16)> {
+    let inner = area.inner(&Margin::new(1, 1));
+    let inside = (inner.left()..inner.right()).contains(&column)
+        && (inner.top()..inner.bottom()).contains(&row);
+    inside.then(|| (column - inner.x, row - inner.y))
+}
+
+/// Label to put on the map centered at the cell, with variants from the longest to the
+/// shortest
+#[derive(Debug, Clone)]
+pub struct MapLabel<'a> {
+    pub column: u16,
+    pub row: u16,
+    pub variants: Vec<Line<'a>>,
+}
+
+/// Place labels in the order of their priority, each one with the longest variant that does
+/// not overlap labels placed before, with a gap of a cell between them. Labels without such
+/// variant are dropped. Returns the left cell of each placed label
+pub fn place_labels<'a>(
+    labels: Vec<MapLabel<'a>>,
+    size: (u16, u16),
+) -> Vec<(u16, u16, Line<'a>)> {
+    let (columns, rows) = (usize::from(size.0), usize::from(size.1));
+    let mut occupied = vec![false; columns * rows];
+    let mut placed = Vec::new();
+    for label in labels {
+        let row = usize::from(label.row);
+        if row >= rows || usize::from(label.column) >= columns {
+            continue;
+        }
+        let fits = label.variants.into_iter().find_map(|line| {
+            let width = line.width().max(1);
+            if width > columns {
+                return None;
+            }
+            let left = usize::from(label.column)
+                .saturating_sub(width / 2)
+                .min(columns - width);
+            let (from, to) = (left.saturating_sub(1), (left + width + 1).min(columns));
+            let cells = &mut occupied[row * columns..(row + 1) * columns];
+            if cells[from..to].iter().any(|&o| o) {
+                return None;
+            }
+            cells[left..left + width].iter_mut().for_each(|o| *o = true);
+            Some((left as u16, line))
+        });
+        if let Some((left, line)) = fits {
+            placed.push((left, label.row, line));
+        }
+    }
+    placed
+}
+
 #[memoized(key_expr = (r, p_x, p_y), store_type = HashMap<(Rect, u16, u16), Rect>)]
 pub fn get_bottom_left_rect(r: Rect, p_x: u16, p_y: u16) -> Rect {
     let vertical: Rc<[Rect]> = Layout::default()
@@ -170,6 +268,7 @@ pub fn get_terminal_area_max_height(frame_size: Rect, max_height: u16) -> Rect {
 #[cfg(test)]
 mod tests {
     #![allow(unused_comparisons)]
+    #![allow(clippy::absurd_extreme_comparisons)]
     use super::*;
 
     #[test]
@@ -197,4 +296,48 @@ mod tests {
             assert!(result.height > 0);
         }
     }
+
+    #[test]
+    fn test_place_labels() {
+        let label = |column, row, variants: &[&'static str]| MapLabel {
+            column,
+            row,
+            variants: variants.iter().map(|v| Line::from(*v)).collect(),
+        };
+        let labels = vec![
+            label(10, 2, &["Kharkiv", "Kha.", "A"]),
+            // overlaps the first one, abbreviated
+            label(18, 2, &["Poltava", "Pol.", "P"]),
+            // no room even for the shortest one
+            label(11, 2, &["Sumy", "Sum.", "S"]),
+            // other row, clamped to the right edge
+            label(19, 3, &["Luhansk", "Luh.", "L"]),
+        ];
+
+        let placed = place_labels(labels, (20, 5));
+
+        let placed: Vec<(u16, u16, String)> = placed
+            .into_iter()
+            .map(|(column, row, line)| (column, row, line.to_string()))
+            .collect();
+        assert_eq!(
+            placed,
+            [
+                (7, 2, "Kharkiv".to_string()),
+                (16, 2, "Pol.".to_string()),
+                (13, 3, "Luhansk".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_inner_position() {
+        let area = Rect::new(10, 5, 20, 10);
+        assert_eq!(get_inner_position(area, 11, 6), Some((0, 0)));
+        assert_eq!(get_inner_position(area, 28, 13), Some((17, 7)));
+        // border
+        assert_eq!(get_inner_position(area, 10, 6), None);
+        assert_eq!(get_inner_position(area, 29, 13), None);
+        assert_eq!(get_inner_position(area, 15, 14), None);
+    }
 }
@@ -2,6 +2,7 @@ use directories::ProjectDirs;
 use lazy_static::lazy_static;
 use std::path::PathBuf;
 use std::{any::type_name, env};
+use time::{OffsetDateTime, UtcOffset};
 use tracing_error::ErrorLayer;
 use tracing_subscriber::{
     self, prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt,
@@ -94,8 +95,8 @@ pub fn get_config_dir() -> PathBuf {
 pub fn initialize_logging(log_path: Option<impl Into<PathBuf>>) -> Result<()> {
     let disable_file_logging: bool = log_path.is_none();
 
-    if log_path.is_some() {
-        let log_path: PathBuf = log_path.unwrap().into();
+    if let Some(log_path) = log_path {
+        let log_path: PathBuf = log_path.into();
         // let directory = get_data_dir();
         // std::fs::create_dir_all(directory.clone())?;
         // let log_path: PathBuf = directory.join(LOG_FILE.clone());
@@ -167,6 +168,13 @@ Data directory: {data_dir_path}"
     )
 }
 
+/// Current time in the local offset of the system, UTC if it is unknown
+pub fn now_local() -> OffsetDateTime {
+    let seconds = chrono::Local::now().offset().local_minus_utc();
+    let offset = UtcOffset::from_whole_seconds(seconds).unwrap_or(UtcOffset::UTC);
+    OffsetDateTime::now_utc().to_offset(offset)
+}
+
 pub fn type_of<T>(_: T) -> &'static str {
     type_name::<T>().split("::").last().unwrap()
 }
@@ -191,7 +199,7 @@ mod tests {
     #[test]
     fn test_initialize_logging_with_path() -> Result<()> {
         let log_path = std::env::temp_dir().join("tmp.log");
-        File::create(&log_path).map_err(|err| AppError::Io(err))?;
+        File::create(&log_path).map_err(AppError::Io)?;
 
         let result = initialize_logging(Some(log_path.clone()));
         assert!(result.is_ok());
@@ -204,11 +212,11 @@ mod tests {
         info!("{}", log_line);
 
         // Read the log file
-        let mut log_file = File::open(&log_path).map_err(|err| AppError::Io(err))?;
+        let mut log_file = File::open(&log_path).map_err(AppError::Io)?;
         let mut log_contents = String::new();
         log_file
             .read_to_string(&mut log_contents)
-            .map_err(|err| AppError::Io(err))?;
+            .map_err(AppError::Io)?;
 
         // Check that the log file contains the log line
         assert!(log_contents.contains(log_line));
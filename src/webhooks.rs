@@ -0,0 +1,729 @@
+use miette::{miette, IntoDiagnostic, Result, WrapErr};
+use ralertsinua_http::AlertsInUaApi;
+use ralertsinua_models::*;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    fmt,
+    path::Path,
+    time::Duration,
+};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tracing::{debug, warn};
+
+use crate::transitions::TransitionKind;
+
+/// Number of delivered transition keys remembered per target
+const DELIVERED_KEYS_LIMIT: usize = 1000;
+/// How long undelivered transitions are retried on the next polls
+const PENDING_MAX_AGE: Duration = Duration::from_secs(3600);
+
+/// Start or end of an alert, as sent to the webhooks
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transition {
+    /// Stable identifier of the transition, used for deduplication
+    pub key: String,
+    pub kind: TransitionKind,
+    #[serde_as(as = "DisplayFromStr")]
+    pub alert_type: AlertType,
+    pub location_uid: i32,
+    pub location_title: String,
+    pub location_oblast_uid: i32,
+    #[serde(with = "time::serde::rfc3339")]
+    pub observed_at: OffsetDateTime,
+}
+
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} alert {}: {}",
+            self.alert_type.to_string().replace('_', " "),
+            self.kind,
+            self.location_title
+        )
+    }
+}
+
+/// Air raid alert transitions of the oblasts, partial alerts are not reported
+pub fn transitions_from_statuses(
+    previous: &AirRaidAlertOblastStatuses,
+    current: &AirRaidAlertOblastStatuses,
+    observed_at: OffsetDateTime,
+) -> Vec<Transition> {
+    current
+        .diff(previous)
+        .into_iter()
+        .filter_map(|change| {
+            let kind = TransitionKind::of(&change)?;
+            Some(Transition {
+                key: format!(
+                    "status:{}:{}:{}",
+                    change.location_uid,
+                    kind,
+                    observed_at.unix_timestamp()
+                ),
+                kind,
+                alert_type: AlertType::AirRaid,
+                location_uid: change.location_uid,
+                location_title: change.location_title().to_string(),
+                location_oblast_uid: change.location_uid,
+                observed_at,
+            })
+        })
+        .collect()
+}
+
+/// Transitions of the alerts other than air raid (which are reported by oblast statuses),
+/// by comparing active alert ids
+pub fn transitions_from_alerts(
+    previous: &Alerts,
+    current: &Alerts,
+    observed_at: OffsetDateTime,
+) -> Vec<Transition> {
+    let ids = |alerts: &Alerts| alerts.iter().map(|a| a.id).collect::<HashSet<_>>();
+    let (previous_ids, current_ids) = (ids(previous), ids(current));
+    let transition = |alert: &Alert, kind: TransitionKind| Transition {
+        key: format!("alert:{}:{}", alert.id, kind),
+        kind,
+        alert_type: alert.alert_type.clone(),
+        location_uid: alert.location_uid,
+        location_title: alert.location_title.clone(),
+        location_oblast_uid: alert.location_oblast_uid,
+        observed_at,
+    };
+
+    let started = current
+        .iter()
+        .filter(|a| !previous_ids.contains(&a.id))
+        .map(|a| transition(a, TransitionKind::Started));
+    let ended = previous
+        .iter()
+        .filter(|a| !current_ids.contains(&a.id))
+        .map(|a| transition(a, TransitionKind::Ended));
+    started
+        .chain(ended)
+        .filter(|t| t.alert_type != AlertType::AirRaid)
+        .collect()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    /// Slack incoming webhook message, `{"text": "..."}`
+    Slack,
+    /// [`Transition`] as JSON
+    #[default]
+    Json,
+    /// Body rendered from `template`, with `{{field}}` placeholders of [`Transition`]
+    Template,
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+/// Webhook endpoint with its filters
+#[serde_as]
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookTarget {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// Location or oblast uids, all when empty
+    #[serde(default)]
+    pub locations: Vec<i32>,
+    /// Alert types, all when empty
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    #[serde(default)]
+    pub alert_types: Vec<AlertType>,
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+}
+
+impl WebhookTarget {
+    pub fn matches(&self, transition: &Transition) -> bool {
+        let location = self.locations.is_empty()
+            || self.locations.contains(&transition.location_uid)
+            || self.locations.contains(&transition.location_oblast_uid);
+        let alert_type = self.alert_types.is_empty()
+            || self.alert_types.contains(&transition.alert_type);
+        location && alert_type
+    }
+
+    /// Content type and body of the request
+    pub fn body(&self, transition: &Transition) -> Result<(String, String)> {
+        let body = match self.format {
+            WebhookFormat::Slack => {
+                let emoji = match transition.kind {
+                    TransitionKind::Started => ":red_circle:",
+                    TransitionKind::Ended => ":large_green_circle:",
+                };
+                serde_json::json!({ "text": format!("{} {}", emoji, transition) })
+                    .to_string()
+            }
+            WebhookFormat::Json => serde_json::to_string(transition).into_diagnostic()?,
+            WebhookFormat::Template => {
+                let template = self.template.as_deref().ok_or_else(|| {
+                    miette!(
+                        "webhook '{}' has template format, but no template",
+                        self.name
+                    )
+                })?;
+                render_template(template, transition)
+            }
+        };
+        let content_type = self.content_type.clone().unwrap_or_else(|| {
+            match self.format {
+                WebhookFormat::Template => "text/plain",
+                _ => "application/json",
+            }
+            .to_string()
+        });
+        Ok((content_type, body))
+    }
+}
+
+fn render_template(template: &str, transition: &Transition) -> String {
+    [
+        ("kind", transition.kind.to_string()),
+        ("alert_type", transition.alert_type.to_string()),
+        ("location_uid", transition.location_uid.to_string()),
+        ("location_title", transition.location_title.clone()),
+        (
+            "location_oblast_uid",
+            transition.location_oblast_uid.to_string(),
+        ),
+        (
+            "observed_at",
+            transition.observed_at.format(&Rfc3339).unwrap_or_default(),
+        ),
+        ("text", transition.to_string()),
+    ]
+    .iter()
+    .fold(template.to_string(), |body, (name, value)| {
+        body.replace(&format!("{{{{{}}}}}", name), value)
+    })
+}
+
+/// Webhook targets, read from a TOML file with `[[targets]]` tables
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct WebhooksConfig {
+    #[serde(default)]
+    pub targets: Vec<WebhookTarget>,
+}
+
+impl WebhooksConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .into_diagnostic()
+            .wrap_err(format!("Error opening file, {}", path.display()))?;
+        toml::from_str(&content)
+            .into_diagnostic()
+            .wrap_err(format!("Error parsing webhooks config, {}", path.display()))
+    }
+}
+
+/// Last seen data, delivered and pending transitions, persisted to survive restarts
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct WebhooksState {
+    pub statuses: Option<String>,
+    pub alerts: Option<Alerts>,
+    pub delivered: BTreeMap<String, VecDeque<String>>,
+    /// Transitions not delivered to every matching target yet
+    #[serde(default)]
+    pub pending: Vec<Transition>,
+}
+
+impl WebhooksState {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path).into_diagnostic()?;
+        serde_json::from_str(&content)
+            .into_diagnostic()
+            .wrap_err(format!("Error parsing webhooks state, {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).into_diagnostic()?;
+        }
+        let content = serde_json::to_string(self).into_diagnostic()?;
+        std::fs::write(path, content).into_diagnostic()
+    }
+
+    fn is_delivered(&self, target: &str, key: &str) -> bool {
+        self.delivered
+            .get(target)
+            .is_some_and(|keys| keys.iter().any(|k| k == key))
+    }
+
+    fn mark_delivered(&mut self, target: &str, key: &str) {
+        let keys = self.delivered.entry(target.to_string()).or_default();
+        keys.push_back(key.to_string());
+        while keys.len() > DELIVERED_KEYS_LIMIT {
+            keys.pop_front();
+        }
+    }
+}
+
+/// Sends transitions to the webhook targets
+#[derive(Debug)]
+pub struct Notifier {
+    client: reqwest::Client,
+    targets: Vec<WebhookTarget>,
+    /// Delay before the first retry, doubled on every next one
+    backoff: Duration,
+    dry_run: bool,
+}
+
+impl Notifier {
+    pub fn new(targets: Vec<WebhookTarget>, backoff: Duration, dry_run: bool) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            targets,
+            backoff,
+            dry_run,
+        }
+    }
+
+    /// POST the body, retrying on network errors, `429` and `5xx` responses
+    async fn post(
+        &self,
+        target: &WebhookTarget,
+        content_type: &str,
+        body: &str,
+    ) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .client
+                .post(&target.url)
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(body.to_string())
+                .send()
+                .await;
+            let retryable = match &result {
+                Ok(res) if res.status().is_success() => return Ok(()),
+                Ok(res) => {
+                    res.status().is_server_error()
+                        || res.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                }
+                Err(_) => true,
+            };
+            let error = match result {
+                Ok(res) => miette!("webhook '{}' responded {}", target.name, res.status()),
+                Err(e) => miette!("webhook '{}' failed: {}", target.name, e),
+            };
+            if !retryable || attempt >= target.max_retries {
+                return Err(error);
+            }
+            let delay = self.backoff * 2u32.saturating_pow(attempt);
+            warn!(target: "app", "{}, retrying in {:?}", error, delay);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Deliver transitions to the matching targets, skipping the ones delivered before.
+    /// In dry-run mode requests are printed instead of sent. Returns the number of deliveries
+    pub async fn dispatch(
+        &self,
+        transitions: &[Transition],
+        state: &mut WebhooksState,
+    ) -> usize {
+        let mut delivered = 0;
+        for transition in transitions {
+            for target in self.targets.iter().filter(|t| t.matches(transition)) {
+                if state.is_delivered(&target.name, &transition.key) {
+                    debug!(target: "app", "'{}' already delivered to '{}'", transition.key, target.name);
+                    continue;
+                }
+                let (content_type, body) = match target.body(transition) {
+                    Ok(request) => request,
+                    Err(e) => {
+                        warn!(target: "app", "{}", e);
+                        continue;
+                    }
+                };
+                if self.dry_run {
+                    println!("POST {} ({})\n{}", target.url, content_type, body);
+                    // the state of a dry run is not saved
+                    state.mark_delivered(&target.name, &transition.key);
+                    delivered += 1;
+                    continue;
+                }
+                match self.post(target, &content_type, &body).await {
+                    Ok(()) => {
+                        state.mark_delivered(&target.name, &transition.key);
+                        delivered += 1;
+                    }
+                    Err(e) => warn!(target: "app", "{}", e),
+                }
+            }
+        }
+        delivered
+    }
+
+    /// Transitions some matching target has not received yet, dropped once too old
+    fn undelivered(
+        &self,
+        transitions: Vec<Transition>,
+        state: &WebhooksState,
+        now: OffsetDateTime,
+    ) -> Vec<Transition> {
+        transitions
+            .into_iter()
+            .filter(|transition| now - transition.observed_at < PENDING_MAX_AGE)
+            .filter(|transition| {
+                self.targets.iter().any(|target| {
+                    target.matches(transition)
+                        && !state.is_delivered(&target.name, &transition.key)
+                })
+            })
+            .collect()
+    }
+}
+
+/// Fetch statuses and active alerts, diff them with the last seen ones and dispatch
+/// transitions, along with the ones not delivered by the previous polls. The first poll
+/// without a saved state only sets the baseline
+pub async fn poll_once(
+    api_client: &dyn AlertsInUaApi,
+    notifier: &Notifier,
+    state: &mut WebhooksState,
+) -> Result<usize> {
+    let statuses = api_client
+        .get_air_raid_alert_statuses_by_location()
+        .await
+        .into_diagnostic()?;
+    let alerts = api_client.get_active_alerts().await.into_diagnostic()?;
+    let observed_at = OffsetDateTime::now_utc();
+
+    let mut transitions = std::mem::take(&mut state.pending);
+    if let Some(previous) = &state.statuses {
+        let previous = AirRaidAlertOblastStatuses::new(previous.clone(), Some(true));
+        transitions.extend(transitions_from_statuses(&previous, &statuses, observed_at));
+    }
+    if let Some(previous) = &state.alerts {
+        transitions.extend(transitions_from_alerts(previous, &alerts, observed_at));
+    }
+    let delivered = notifier.dispatch(&transitions, state).await;
+    state.pending = notifier.undelivered(transitions, state, observed_at);
+
+    state.statuses = Some(statuses.raw_data().to_string());
+    state.alerts = Some(alerts);
+    Ok(delivered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server as MockServer};
+    use ralertsinua_http::AlertsInUaClient;
+
+    fn statuses(data: &str) -> AirRaidAlertOblastStatuses {
+        AirRaidAlertOblastStatuses::new(data.to_string(), Some(true))
+    }
+
+    fn alerts(alert_types: &[(i32, &str)]) -> Alerts {
+        let alerts: Vec<serde_json::Value> = alert_types
+            .iter()
+            .map(|(id, alert_type)| {
+                serde_json::json!({
+                    "alert_type": alert_type,
+                    "finished_at": null,
+                    "id": id,
+                    "location_oblast": "Дніпропетровська область",
+                    "location_oblast_uid": 9,
+                    "location_title": "Нікопольська територіальна громада",
+                    "location_type": "hromada",
+                    "location_uid": "351",
+                    "notes": null,
+                    "started_at": "2024-05-05T15:48:31.000Z",
+                    "updated_at": "2024-05-05T15:48:31.818Z"
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "alerts": alerts,
+            "disclaimer": "",
+            "meta": { "last_updated_at": "2024/05/06 10:02:45 +0000", "type": "full" }
+        }))
+        .unwrap()
+    }
+
+    fn target(url: String, format: WebhookFormat) -> WebhookTarget {
+        WebhookTarget {
+            name: "test".to_string(),
+            url,
+            format,
+            template: None,
+            content_type: None,
+            locations: vec![],
+            alert_types: vec![],
+            max_retries: 2,
+        }
+    }
+
+    #[test]
+    fn test_transitions_from_statuses() {
+        let now = OffsetDateTime::now_utc();
+        let previous = statuses("ANNNNNNNNNNNNNNNNNNNNNNNNNN");
+        let current = statuses("NANNNNNNNNNNNNNNNNNNNNNNNNN");
+
+        let transitions = transitions_from_statuses(&previous, &current, now);
+
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].location_uid, 29);
+        assert_eq!(transitions[0].kind, TransitionKind::Ended);
+        assert_eq!(transitions[1].location_uid, 8);
+        assert_eq!(transitions[1].kind, TransitionKind::Started);
+        assert_eq!(transitions[1].alert_type, AlertType::AirRaid);
+        // a repeated alert is a new transition
+        let later = now + Duration::from_secs(60);
+        let again = transitions_from_statuses(&previous, &current, later);
+        assert_ne!(transitions[1].key, again[1].key);
+    }
+
+    #[test]
+    fn test_transitions_from_alerts() {
+        let now = OffsetDateTime::now_utc();
+        let previous = alerts(&[(1, "artillery_shelling"), (2, "air_raid")]);
+        let current = alerts(&[(3, "urban_fights")]);
+
+        let transitions = transitions_from_alerts(&previous, &current, now);
+
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].key, "alert:3:started");
+        assert_eq!(transitions[0].alert_type, AlertType::UrbanFights);
+        assert_eq!(transitions[1].key, "alert:1:ended");
+    }
+
+    #[test]
+    fn test_target_config() {
+        let config: WebhooksConfig = toml::from_str(
+            r#"
+            [[targets]]
+            name = "ops"
+            url = "http://localhost/hook"
+            format = "template"
+            template = "{{location_title}} {{kind}} ({{alert_type}})"
+            locations = [9]
+            alert_types = ["artillery_shelling"]
+            "#,
+        )
+        .unwrap();
+        let target = &config.targets[0];
+        assert_eq!(target.max_retries, 3);
+        let transitions = transitions_from_alerts(
+            &alerts(&[]),
+            &alerts(&[(1, "artillery_shelling"), (2, "urban_fights")]),
+            OffsetDateTime::now_utc(),
+        );
+
+        assert!(target.matches(&transitions[0]));
+        assert!(!target.matches(&transitions[1]));
+        let (content_type, body) = target.body(&transitions[0]).unwrap();
+        assert_eq!(content_type, "text/plain");
+        assert_eq!(
+            body,
+            "Нікопольська територіальна громада started (artillery_shelling)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_retries_and_dedupes() {
+        let mut server = MockServer::new_async().await;
+        let failing = server
+            .mock("POST", "/hook")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("POST", "/hook")
+            .match_body(Matcher::PartialJsonString(
+                r#"{"text": ":red_circle: urban fights alert started: Нікопольська територіальна громада"}"#.to_string(),
+            ))
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+        let notifier = Notifier::new(
+            vec![target(
+                format!("{}/hook", server.url()),
+                WebhookFormat::Slack,
+            )],
+            Duration::from_millis(1),
+            false,
+        );
+        let transitions = transitions_from_alerts(
+            &alerts(&[]),
+            &alerts(&[(3, "urban_fights")]),
+            OffsetDateTime::now_utc(),
+        );
+        let mut state = WebhooksState::default();
+
+        // the first attempt is answered with 503, the retry succeeds
+        assert_eq!(notifier.dispatch(&transitions, &mut state).await, 1);
+        assert_eq!(notifier.dispatch(&transitions, &mut state).await, 0);
+
+        failing.assert_async().await;
+        ok.assert_async().await;
+        assert!(state.is_delivered("test", "alert:3:started"));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_gives_up_on_client_error() {
+        let mut server = MockServer::new_async().await;
+        let hook = server
+            .mock("POST", "/hook")
+            .with_status(400)
+            .expect(1)
+            .create_async()
+            .await;
+        let notifier = Notifier::new(
+            vec![target(
+                format!("{}/hook", server.url()),
+                WebhookFormat::Json,
+            )],
+            Duration::from_millis(1),
+            false,
+        );
+        let transitions = transitions_from_alerts(
+            &alerts(&[]),
+            &alerts(&[(3, "urban_fights")]),
+            OffsetDateTime::now_utc(),
+        );
+        let mut state = WebhooksState::default();
+
+        assert_eq!(notifier.dispatch(&transitions, &mut state).await, 0);
+        hook.assert_async().await;
+        assert!(!state.is_delivered("test", "alert:3:started"));
+    }
+
+    #[tokio::test]
+    async fn test_poll_once() {
+        let mut server = MockServer::new_async().await;
+        let _statuses = server
+            .mock("GET", "/v1/iot/active_air_raid_alerts_by_oblast.json")
+            .with_body(r#""NANNNNNNNNNNNNNNNNNNNNNNNNN""#)
+            .create_async()
+            .await;
+        let _alerts = server
+            .mock("GET", "/v1/alerts/active.json")
+            .with_body(serde_json::to_string(&alerts(&[])).unwrap())
+            .create_async()
+            .await;
+        let hook = server
+            .mock("POST", "/hook")
+            .match_body(Matcher::PartialJsonString(
+                r#"{"kind": "started", "location_uid": 8}"#.to_string(),
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        let api_client = AlertsInUaClient::new(&server.url(), "token");
+        let notifier = Notifier::new(
+            vec![target(
+                format!("{}/hook", server.url()),
+                WebhookFormat::Json,
+            )],
+            Duration::from_millis(1),
+            false,
+        );
+        let dir = std::env::temp_dir()
+            .join(format!("ralertsinua-webhooks-{}", std::process::id()));
+        let path = dir.join("state.json");
+        let state = WebhooksState {
+            statuses: Some("NNNNNNNNNNNNNNNNNNNNNNNNNNN".to_string()),
+            ..Default::default()
+        };
+        state.save(&path).unwrap();
+        let mut state = WebhooksState::load(&path).unwrap();
+
+        assert_eq!(
+            poll_once(&api_client, &notifier, &mut state).await.unwrap(),
+            1
+        );
+        // nothing changed since
+        assert_eq!(
+            poll_once(&api_client, &notifier, &mut state).await.unwrap(),
+            0
+        );
+
+        hook.assert_async().await;
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_retries_undelivered() {
+        let mut server = MockServer::new_async().await;
+        let _statuses = server
+            .mock("GET", "/v1/iot/active_air_raid_alerts_by_oblast.json")
+            .with_body(r#""NANNNNNNNNNNNNNNNNNNNNNNNNN""#)
+            .create_async()
+            .await;
+        let _alerts = server
+            .mock("GET", "/v1/alerts/active.json")
+            .with_body(serde_json::to_string(&alerts(&[])).unwrap())
+            .create_async()
+            .await;
+        let failing = server
+            .mock("POST", "/hook")
+            .with_status(400)
+            .expect(1)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("POST", "/hook")
+            .match_body(Matcher::PartialJsonString(
+                r#"{"kind": "started", "location_uid": 8}"#.to_string(),
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        let api_client = AlertsInUaClient::new(&server.url(), "token");
+        let notifier = Notifier::new(
+            vec![target(
+                format!("{}/hook", server.url()),
+                WebhookFormat::Json,
+            )],
+            Duration::from_millis(1),
+            false,
+        );
+        let mut state = WebhooksState {
+            statuses: Some("NNNNNNNNNNNNNNNNNNNNNNNNNNN".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            poll_once(&api_client, &notifier, &mut state).await.unwrap(),
+            0
+        );
+        assert_eq!(state.pending.len(), 1);
+        // statuses did not change, the pending transition is delivered
+        assert_eq!(
+            poll_once(&api_client, &notifier, &mut state).await.unwrap(),
+            1
+        );
+        assert!(state.pending.is_empty());
+        assert_eq!(
+            poll_once(&api_client, &notifier, &mut state).await.unwrap(),
+            0
+        );
+
+        failing.assert_async().await;
+        ok.assert_async().await;
+    }
+}